@@ -0,0 +1,98 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::process;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Determines what happens when a Rust closure called from C++ panics.
+///
+/// A panic must not unwind through C++ code, so generated callbacks
+/// catch it before returning to C++ and handle it according to the current policy.
+/// The policy can be changed with `set_callback_panic_policy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallbackPanicPolicy {
+    /// Abort the process (default).
+    Abort,
+    /// Return to C++ as if the callback finished normally.
+    /// The panic message is still printed by the panic hook.
+    Ignore,
+    /// Store the panic payload and return to C++ as if the callback finished normally.
+    /// The stored panic can be re-raised later with `resume_callback_panic`.
+    /// Only the first panic is stored until it's resumed.
+    Defer,
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(0);
+
+thread_local! {
+    static PENDING_PANIC: RefCell<Option<Box<dyn Any + Send>>> = RefCell::new(None);
+}
+
+/// Sets the policy used for panics in Rust closures called from C++.
+///
+/// Closures that return a value to C++ always abort the process on panic
+/// because there is no value that could be returned instead.
+pub fn set_callback_panic_policy(policy: CallbackPanicPolicy) {
+    let value = match policy {
+        CallbackPanicPolicy::Abort => 0,
+        CallbackPanicPolicy::Ignore => 1,
+        CallbackPanicPolicy::Defer => 2,
+    };
+    POLICY.store(value, Ordering::SeqCst);
+}
+
+/// Returns the policy used for panics in Rust closures called from C++.
+pub fn callback_panic_policy() -> CallbackPanicPolicy {
+    match POLICY.load(Ordering::SeqCst) {
+        1 => CallbackPanicPolicy::Ignore,
+        2 => CallbackPanicPolicy::Defer,
+        _ => CallbackPanicPolicy::Abort,
+    }
+}
+
+/// Re-raises the panic stored in the current thread by a callback
+/// when `CallbackPanicPolicy::Defer` is used. Does nothing if there is no stored panic.
+pub fn resume_callback_panic() {
+    if let Some(payload) = PENDING_PANIC.with(|p| p.borrow_mut().take()) {
+        std::panic::resume_unwind(payload);
+    }
+}
+
+/// Handles a panic caught in a callback that doesn't return a value.
+///
+/// This function is used by the generated code.
+#[doc(hidden)]
+pub fn handle_callback_panic(payload: Box<dyn Any + Send>) {
+    match callback_panic_policy() {
+        CallbackPanicPolicy::Abort => abort_on_callback_panic(payload),
+        CallbackPanicPolicy::Ignore => {}
+        CallbackPanicPolicy::Defer => PENDING_PANIC.with(|p| {
+            let mut p = p.borrow_mut();
+            if p.is_none() {
+                *p = Some(payload);
+            }
+        }),
+    }
+}
+
+/// Handles a panic caught in a callback that returns a value.
+///
+/// This function is used by the generated code.
+#[doc(hidden)]
+pub fn abort_on_callback_panic(_payload: Box<dyn Any + Send>) -> ! {
+    eprintln!("panic in a Rust callback called from C++, aborting");
+    process::abort()
+}
+
+#[test]
+fn deferred_panic() {
+    set_callback_panic_policy(CallbackPanicPolicy::Defer);
+    assert_eq!(callback_panic_policy(), CallbackPanicPolicy::Defer);
+    handle_callback_panic(Box::new("first"));
+    handle_callback_panic(Box::new("second"));
+    set_callback_panic_policy(CallbackPanicPolicy::Abort);
+
+    let payload = std::panic::catch_unwind(resume_callback_panic).unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"first"));
+    // the stored panic is consumed
+    resume_callback_panic();
+}
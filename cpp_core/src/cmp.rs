@@ -3,6 +3,13 @@
 //! C++'s comparison operators have different semantics from Rust's `PartialOrd` and `Ord` traits.
 //! If all the operators (`Lt`, `Le`, `Gt`, `Ge`) are implemented for a type, the pointer types
 //! (`CppBox`, `Ptr`, `Ref`) automatically implement `PartialOrd`.
+//!
+//! If a type's `operator==` is an equivalence relation and its `operator<` defines
+//! a total order consistent with it, the crate's configuration can mark the type as such.
+//! `ritual` then implements `TotalEq` and `TotalOrd` for the type,
+//! and `CppBox` of this type implements `Eq` and `Ord`.
+
+use crate::{CppBox, CppDeletable, Ref};
 
 /// Represents C++'s `operator<`.
 pub trait Lt<Rhs: ?Sized = Self> {
@@ -48,3 +55,14 @@ pub trait Ge<Rhs: ?Sized = Self> {
     /// may invoke arbitrary foreign code, so no safety guarantees can be made.
     unsafe fn ge(&self, other: &Rhs) -> bool;
 }
+
+/// Marks types whose C++ `operator==` is an equivalence relation.
+///
+/// If this trait is implemented for a type, `CppBox` of this type implements `Eq`.
+pub trait TotalEq: CppDeletable + PartialEq<CppBox<Self>> {}
+
+/// Marks types whose C++ `operator<` defines a total order consistent with `operator==`.
+///
+/// If this trait is implemented for a type, `CppBox` of this type implements `PartialOrd`
+/// and `Ord`, based on the type's `operator<`.
+pub trait TotalOrd: TotalEq + Lt<Ref<Self>> {}
@@ -1,6 +1,7 @@
 use crate::ops::{Begin, BeginMut, End, EndMut, Increment, Indirection};
 use crate::vector_ops::{Data, DataMut, Size};
 use crate::{cpp_iter, CppIterator, DynamicCast, Ptr, Ref, StaticDowncast, StaticUpcast};
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 use std::{fmt, mem, ptr, slice};
 
@@ -18,6 +19,50 @@ pub trait CppDeletable: Sized {
     unsafe fn delete(&self);
 }
 
+/// Objects that can be copied using C++'s copy constructor.
+///
+/// This trait is automatically implemented by `ritual` for class types
+/// with a public copy constructor. `CppBox<T>` implements `Clone` if `T` implements `CppClone`.
+pub trait CppClone: CppDeletable {
+    /// Creates a copy of `self` on the heap using C++'s `new T(*self)`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn clone(&self) -> CppBox<Self>;
+}
+
+/// Objects that can be created using C++'s default constructor.
+///
+/// This trait is automatically implemented by `ritual` for class types
+/// with a public default constructor.
+/// `CppBox<T>` implements `Default` if `T` implements `CppDefault`.
+pub trait CppDefault: CppDeletable {
+    /// Creates a new object on the heap using C++'s `new T()`.
+    ///
+    /// # Safety
+    ///
+    /// This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn default() -> CppBox<Self>;
+}
+
+/// Objects that can be hashed using a hash function provided by the C++ library
+/// (e.g. `qHash`).
+///
+/// This trait is implemented by `ritual` if the hash function was specified
+/// in the generator's configuration. `CppBox<T>` implements `Hash` if `T` implements `CppHash`.
+pub trait CppHash {
+    /// Returns the hash value computed by the C++ hash function.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn hash_value(&self) -> u64;
+}
+
 /// An owning pointer to a C++ object.
 ///
 /// `CppBox` is automatically used in places where C++ class objects are passed by value
@@ -48,6 +93,10 @@ pub trait CppDeletable: Sized {
 /// `&CppBox<T>` and `&mut CppBox<T>` will implement `IntoIterator`,
 /// so you can iterate on them directly.
 ///
+/// `CppBox<T>` implements `Clone`, `Default`, `Hash`, `Eq` and `Ord` if the C++ class
+/// provides the corresponding functionality (see `CppClone`, `CppDefault`, `CppHash`,
+/// `cmp::TotalEq` and `cmp::TotalOrd`).
+///
 /// ### Safety
 ///
 /// It's not possible to automatically track the ownership of objects possibly managed by C++
@@ -317,6 +366,27 @@ impl<T: CppDeletable> Drop for CppBox<T> {
     }
 }
 
+/// Copies the object using its C++ copy constructor.
+impl<T: CppClone> Clone for CppBox<T> {
+    fn clone(&self) -> Self {
+        unsafe { CppClone::clone(&**self) }
+    }
+}
+
+/// Creates the object using its C++ default constructor.
+impl<T: CppDefault> Default for CppBox<T> {
+    fn default() -> Self {
+        unsafe { T::default() }
+    }
+}
+
+/// Hashes the value computed by the C++ hash function.
+impl<T: CppDeletable + CppHash> Hash for CppBox<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        unsafe { (**self).hash_value() }.hash(state);
+    }
+}
+
 impl<T: CppDeletable> fmt::Debug for CppBox<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "CppBox({:?})", self.0)
@@ -325,8 +395,10 @@ impl<T: CppDeletable> fmt::Debug for CppBox<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{CppBox, CppDeletable, Ptr};
+    use crate::cmp::{Lt, TotalEq, TotalOrd};
+    use crate::{ops, CppBox, CppClone, CppDeletable, Ptr, Ref};
     use std::cell::RefCell;
+    use std::collections::BTreeSet;
     use std::rc::Rc;
 
     struct Struct1 {
@@ -355,4 +427,98 @@ mod tests {
         }
         assert!(*value1.borrow() == 42);
     }
+
+    struct Struct2 {
+        value: i32,
+    }
+
+    impl CppDeletable for Struct2 {
+        unsafe fn delete(&self) {
+            drop(Box::from_raw(self as *const Struct2 as *mut Struct2));
+        }
+    }
+
+    impl CppClone for Struct2 {
+        unsafe fn clone(&self) -> CppBox<Self> {
+            CppBox::from_raw(Box::into_raw(Box::new(Struct2 { value: self.value }))).unwrap()
+        }
+    }
+
+    impl PartialEq<Ref<Struct2>> for Struct2 {
+        fn eq(&self, other: &Ref<Struct2>) -> bool {
+            self.value == other.value
+        }
+    }
+
+    impl PartialEq<CppBox<Struct2>> for Struct2 {
+        fn eq(&self, other: &CppBox<Struct2>) -> bool {
+            self.value == other.value
+        }
+    }
+
+    impl Lt<Ref<Struct2>> for Struct2 {
+        unsafe fn lt(&self, other: &Ref<Struct2>) -> bool {
+            self.value < other.value
+        }
+    }
+
+    impl TotalEq for Struct2 {}
+    impl TotalOrd for Struct2 {}
+
+    #[test]
+    fn clone_and_ord() {
+        let object1 =
+            unsafe { CppBox::from_raw(Box::into_raw(Box::new(Struct2 { value: 2 }))).unwrap() };
+        let object2 =
+            unsafe { CppBox::from_raw(Box::into_raw(Box::new(Struct2 { value: 1 }))).unwrap() };
+        let object3 = object1.clone();
+        assert_eq!(object3.value, 2);
+        assert_ne!(object1.as_raw_ptr(), object3.as_raw_ptr());
+        assert!(object1 == object3);
+        assert!(object2 < object1);
+        assert!(object1 >= object3);
+
+        let set: BTreeSet<_> = vec![object1, object2, object3].into_iter().collect();
+        assert_eq!(set.iter().map(|x| x.value).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    struct Struct3 {
+        values: [i32; 3],
+    }
+
+    impl CppDeletable for Struct3 {
+        unsafe fn delete(&self) {
+            drop(Box::from_raw(self as *const Struct3 as *mut Struct3));
+        }
+    }
+
+    impl ops::Index<usize> for Struct3 {
+        type Output = Ref<i32>;
+
+        unsafe fn index(&self, index: usize) -> Ref<i32> {
+            Ref::from_raw(&self.values[index]).unwrap()
+        }
+    }
+
+    impl ops::IndexMut<usize> for Struct3 {
+        type Output = Ref<i32>;
+
+        unsafe fn index_mut(&self, index: usize) -> Ref<i32> {
+            Ref::from_raw(&self.values[index]).unwrap()
+        }
+    }
+
+    #[test]
+    fn index() {
+        let mut object1 = unsafe {
+            CppBox::from_raw(Box::into_raw(Box::new(Struct3 { values: [1, 2, 3] }))).unwrap()
+        };
+        assert_eq!(object1[1], 2);
+        object1[2] = 5;
+        assert_eq!(object1.values, [1, 2, 5]);
+
+        let mut ptr = unsafe { object1.as_ptr() };
+        ptr[0] = 4;
+        assert_eq!(unsafe { object1.as_ref() }[0], 4);
+    }
 }
@@ -0,0 +1,57 @@
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
+
+/// A C++ exception caught by a generated FFI function.
+///
+/// Functions that are configured to catch exceptions return
+/// `Result<T, CppException>` instead of `T`.
+/// Only the message of the exception is preserved.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CppException {
+    message: String,
+}
+
+impl CppException {
+    /// Creates a `CppException` with the specified `message`.
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        CppException {
+            message: message.into(),
+        }
+    }
+
+    /// Creates a `CppException` from the message reported by a generated FFI function.
+    ///
+    /// ### Safety
+    ///
+    /// `message` must point to a valid null-terminated string.
+    pub unsafe fn from_message_ptr(message: *const c_char) -> Self {
+        CppException {
+            message: CStr::from_ptr(message).to_string_lossy().into_owned(),
+        }
+    }
+
+    /// Returns the message of the exception.
+    ///
+    /// For exceptions derived from `std::exception`, this is the value returned by `what()`.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for CppException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "C++ exception: {}", self.message)
+    }
+}
+
+impl Error for CppException {}
+
+#[test]
+fn exception_from_message_ptr() {
+    let message = b"out of range\0";
+    let exception = unsafe { CppException::from_message_ptr(message.as_ptr() as *const c_char) };
+    assert_eq!(exception.message(), "out of range");
+    assert_eq!(exception.to_string(), "C++ exception: out of range");
+}
@@ -22,6 +22,7 @@
 //!
 //! Pointer types implement operator traits and delegate them to the corresponding C++ operators.
 //! This means that you can use `ptr1 + ptr2` to access the object's `operator+`.
+//! Similarly, `ptr[index]` accesses the object's `operator[]` if it returns a reference.
 //!
 //! Pointer types implement `Deref`, allowing to call the object's methods
 //! directly. In addition, methods of the object's first base class are also directly available
@@ -53,18 +54,26 @@
 
 #![deny(missing_docs)]
 
+#[doc(hidden)]
+pub use crate::callback_panic::{abort_on_callback_panic, handle_callback_panic};
+pub use crate::callback_panic::{
+    callback_panic_policy, resume_callback_panic, set_callback_panic_policy, CallbackPanicPolicy,
+};
 pub use crate::casts::{DynamicCast, StaticDowncast, StaticUpcast};
 pub use crate::convert::{CastFrom, CastInto};
-pub use crate::cpp_box::{CppBox, CppDeletable};
+pub use crate::cpp_box::{CppBox, CppClone, CppDefault, CppDeletable, CppHash};
+pub use crate::cpp_exception::CppException;
 pub use crate::iterator::{cpp_iter, CppIterator, EndPtr};
 pub use crate::ptr::{NullPtr, Ptr};
 pub use crate::ref_::Ref;
 pub use libc::wchar_t;
 
+mod callback_panic;
 mod casts;
 pub mod cmp;
 mod convert;
 mod cpp_box;
+mod cpp_exception;
 mod iterator;
 pub mod ops;
 mod ops_impls;
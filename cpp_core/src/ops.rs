@@ -101,3 +101,41 @@ pub trait EndMut {
     /// may invoke arbitrary foreign code, so no safety guarantees can be made.
     unsafe fn end_mut(&self) -> Self::Output;
 }
+
+/// Represents C++'s `operator[]() const`.
+///
+/// If the class only has a non-const `operator[]()`, this trait is implemented
+/// using it. Note that such an operator may modify the object
+/// (e.g. `std::map::operator[]` inserts a missing element).
+///
+/// Pointer types implement `std::ops::Index` if the object implements this trait
+/// and returns a `Ref`.
+pub trait Index<Idx> {
+    /// Output type.
+    type Output;
+
+    /// Returns a reference to the element at `index`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn index(&self, index: Idx) -> Self::Output;
+}
+
+/// Represents C++'s `operator[]()`.
+///
+/// Pointer types implement `std::ops::IndexMut` if the object implements this trait
+/// and `Index` and both return a `Ref`.
+pub trait IndexMut<Idx> {
+    /// Output type.
+    type Output;
+
+    /// Returns a mutable reference to the element at `index`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn index_mut(&self, index: Idx) -> Self::Output;
+}
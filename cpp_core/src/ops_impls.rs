@@ -1,9 +1,10 @@
 use crate::{
-    cmp::{Ge, Gt, Le, Lt},
-    CppBox, CppDeletable, Ptr, Ref,
+    cmp::{Ge, Gt, Le, Lt, TotalEq, TotalOrd},
+    ops, CppBox, CppDeletable, Ptr, Ref,
 };
-use std::cmp::{Ordering, PartialEq, PartialOrd};
-use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Shl, Shr, Sub};
+use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Index, IndexMut, Mul, Rem, Shl, Shr, Sub};
+use std::ptr;
 
 macro_rules! define_op {
     ($trait1:ident, $func:ident) => {
@@ -104,6 +105,36 @@ macro_rules! define_comparison_op {
 define_comparison_op!(Ptr);
 define_comparison_op!(Ref);
 
+// `operator[]` returns a C++ reference, so `Index` and `IndexMut` are only implemented
+// when the C++ operator returns a `Ref`.
+macro_rules! define_index_op {
+    ($container:ident $(, $bound:path)?) => {
+        impl<T, I, U> Index<I> for $container<T>
+        where
+            T: ops::Index<I, Output = Ref<U>> $(+ $bound)?,
+        {
+            type Output = U;
+
+            fn index(&self, index: I) -> &U {
+                unsafe { ops::Index::index(&**self, index).as_raw_ref() }
+            }
+        }
+
+        impl<T, I, U> IndexMut<I> for $container<T>
+        where
+            T: ops::Index<I, Output = Ref<U>> + ops::IndexMut<I, Output = Ref<U>> $(+ $bound)?,
+        {
+            fn index_mut(&mut self, index: I) -> &mut U {
+                unsafe { ops::IndexMut::index_mut(&**self, index).as_mut_raw_ref() }
+            }
+        }
+    };
+}
+
+define_index_op!(CppBox, CppDeletable);
+define_index_op!(Ptr);
+define_index_op!(Ref);
+
 impl<T: CppDeletable, U> PartialEq<U> for CppBox<T>
 where
     T: PartialEq<U>,
@@ -147,3 +178,51 @@ where
         unsafe { (**self).ge(other) }
     }
 }
+
+impl<T: TotalEq> Eq for CppBox<T> {}
+
+/// Returns a `Ref` pointing to `value`.
+unsafe fn ref_to<T>(value: &T) -> Ref<T> {
+    Ref::from_raw_non_null(ptr::NonNull::from(value))
+}
+
+// Comparisons with `CppBox` are implemented in terms of `operator<` so that
+// `CppBox<T>` implements `PartialOrd<CppBox<T>>` required by `Ord`.
+
+impl<T: TotalOrd> Lt<CppBox<T>> for T {
+    unsafe fn lt(&self, other: &CppBox<T>) -> bool {
+        Lt::lt(self, &other.as_ref())
+    }
+}
+
+impl<T: TotalOrd> Le<CppBox<T>> for T {
+    unsafe fn le(&self, other: &CppBox<T>) -> bool {
+        !Lt::lt(&**other, &ref_to(self))
+    }
+}
+
+impl<T: TotalOrd> Gt<CppBox<T>> for T {
+    unsafe fn gt(&self, other: &CppBox<T>) -> bool {
+        Lt::lt(&**other, &ref_to(self))
+    }
+}
+
+impl<T: TotalOrd> Ge<CppBox<T>> for T {
+    unsafe fn ge(&self, other: &CppBox<T>) -> bool {
+        !Lt::lt(self, &other.as_ref())
+    }
+}
+
+impl<T: TotalOrd> Ord for CppBox<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        unsafe {
+            if Lt::lt(&**self, &other.as_ref()) {
+                Ordering::Less
+            } else if Lt::lt(&**other, &self.as_ref()) {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        }
+    }
+}
@@ -30,7 +30,11 @@ impl QApplication {
                 let mut args = QCoreApplicationArgs::new();
                 let (argc, argv) = args.get();
                 let app = QApplication::new_2a(argc, argv);
-                f(app.as_ptr())
+                let exit_code = f(app.as_ptr());
+                // re-raise a panic deferred by a callback
+                // (see `cpp_core::CallbackPanicPolicy::Defer`)
+                ::cpp_core::resume_callback_panic();
+                exit_code
             }
         }; // drop `app` and `args`
         process::exit(exit_code)
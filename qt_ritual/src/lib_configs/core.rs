@@ -401,8 +401,12 @@ fn add_qpointer(data: &mut ProcessorData<'_>) -> Result<()> {
                         template_arguments: Some(vec![CppType::Class(CppPath::from_good_str(
                             "QObject",
                         ))]),
+                        inline_namespaces: Vec::new(),
                     }),
                     kind: CppTypeDeclarationKind::Class,
+                    deprecation: None,
+                    is_deleted: false,
+                    is_final: false,
                 }),
             )?;
         }
@@ -415,12 +419,15 @@ fn connection_to_bool_function() -> CppFunction {
         path: CppPath::from_item(CppPathItem {
             name: "static_cast".into(),
             template_arguments: Some(vec![CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)]),
+            inline_namespaces: Vec::new(),
         }),
         member: None,
         allows_variadic_arguments: false,
         arguments: vec![CppFunctionArgument {
             name: "connection".into(),
             has_default_value: false,
+            default_value: None,
+            type_alias: None,
             argument_type: CppType::new_reference(
                 true,
                 CppType::Class(CppPath::from_good_str("QMetaObject::Connection")),
@@ -429,6 +436,10 @@ fn connection_to_bool_function() -> CppFunction {
         cast: None,
         operator: None,
         declaration_code: None,
+        deprecation: None,
+        is_deleted: false,
+        is_friend: false,
+        return_type_alias: None,
         return_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Bool),
     }
 }
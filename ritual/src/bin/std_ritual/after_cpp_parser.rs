@@ -16,10 +16,12 @@ fn add_vector_from_pointers_functions(data: &mut ProcessorData<'_>) -> Result<()
         name: "_Tp".into(),
         nested_level: 0,
         index: 0,
+        value_type: None,
     });
     let allocator_type = CppType::Class(CppPath::from_good_str("std").join(CppPathItem {
         name: "allocator".into(),
         template_arguments: Some(vec![t.clone()]),
+        inline_namespaces: Vec::new(),
     }));
     data.add_cpp_item(
         None,
@@ -33,14 +35,17 @@ fn add_vector_from_pointers_functions(data: &mut ProcessorData<'_>) -> Result<()
                             name: "_Alloc".into(),
                             nested_level: 0,
                             index: 1,
+                            value_type: None,
                         }),
                     ]),
+                    inline_namespaces: Vec::new(),
                 })
                 .join(CppPathItem::from_good_str("vector")),
             member: Some(CppFunctionMemberData {
                 kind: CppFunctionKind::Constructor,
                 is_virtual: false,
                 is_pure_virtual: false,
+                is_final: false,
                 is_const: false,
                 is_static: false,
                 visibility: CppVisibility::Public,
@@ -54,21 +59,32 @@ fn add_vector_from_pointers_functions(data: &mut ProcessorData<'_>) -> Result<()
                     name: "first".into(),
                     argument_type: CppType::new_pointer(true, t.clone()),
                     has_default_value: false,
+                    default_value: None,
+                    type_alias: None,
                 },
                 CppFunctionArgument {
                     name: "last".into(),
                     argument_type: CppType::new_pointer(true, t.clone()),
                     has_default_value: false,
+                    default_value: None,
+                    type_alias: None,
                 },
                 CppFunctionArgument {
                     name: "alloc".into(),
                     argument_type: allocator_type,
                     has_default_value: true,
+                    default_value: None,
+                    type_alias: None,
                 },
             ],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: None,
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }),
     )?;
     data.add_cpp_item(
@@ -83,14 +99,17 @@ fn add_vector_from_pointers_functions(data: &mut ProcessorData<'_>) -> Result<()
                             name: "_Alloc".into(),
                             nested_level: 0,
                             index: 1,
+                            value_type: None,
                         }),
                     ]),
+                    inline_namespaces: Vec::new(),
                 })
                 .join(CppPathItem::from_good_str("assign")),
             member: Some(CppFunctionMemberData {
                 kind: CppFunctionKind::Regular,
                 is_virtual: false,
                 is_pure_virtual: false,
+                is_final: false,
                 is_const: false,
                 is_static: false,
                 visibility: CppVisibility::Public,
@@ -104,16 +123,25 @@ fn add_vector_from_pointers_functions(data: &mut ProcessorData<'_>) -> Result<()
                     name: "first".into(),
                     argument_type: CppType::new_pointer(true, t.clone()),
                     has_default_value: false,
+                    default_value: None,
+                    type_alias: None,
                 },
                 CppFunctionArgument {
                     name: "last".into(),
                     argument_type: CppType::new_pointer(true, t),
                     has_default_value: false,
+                    default_value: None,
+                    type_alias: None,
                 },
             ],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: None,
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }),
     )?;
     Ok(())
@@ -189,6 +217,7 @@ fn add_vector_instantiations(data: &mut ProcessorData<'_>) -> Result<()> {
         let allocator_type = CppType::Class(CppPath::from_good_str("std").join(CppPathItem {
             name: "allocator".into(),
             template_arguments: Some(vec![arg.clone()]),
+            inline_namespaces: Vec::new(),
         }));
         data.add_cpp_item(
             None,
@@ -197,7 +226,11 @@ fn add_vector_instantiations(data: &mut ProcessorData<'_>) -> Result<()> {
                 path: CppPath::from_good_str("std").join(CppPathItem {
                     name: "vector".into(),
                     template_arguments: Some(vec![arg.clone(), allocator_type]),
+                    inline_namespaces: Vec::new(),
                 }),
+                deprecation: None,
+                is_deleted: false,
+                is_final: false,
             }),
         )?;
     }
@@ -2,6 +2,7 @@
 
 use crate::cpp_checker::PreliminaryTest;
 use crate::cpp_data::{CppItem, CppPath};
+use crate::cpp_function::CppFunction;
 use crate::cpp_parser::CppParserOutput;
 use crate::processor::{ProcessingSteps, ProcessorData};
 use crate::rust_info::{NameType, RustItem, RustPathScope};
@@ -177,6 +178,8 @@ pub type RustItemHook = dyn Fn(&mut RustItem, &ProcessorData<'_>) -> Result<()>
 pub type AfterCppParserHook =
     dyn Fn(&mut ProcessorData<'_>, &CppParserOutput) -> Result<()> + 'static;
 pub type CppItemFilterHook = dyn Fn(&CppItem) -> Result<bool> + 'static;
+pub type CatchExceptionsHook = dyn Fn(&CppFunction) -> Result<bool> + 'static;
+pub type MacroFilterHook = dyn Fn(&str) -> Result<bool> + 'static;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkerLibraryConfig {
@@ -222,6 +225,19 @@ pub struct Config {
     cluster_config: Option<ClusterConfig>,
     cpp_checker_tests: Vec<PreliminaryTest>,
     write_dependencies_local_paths: bool,
+    generate_virtual_subclasses: bool,
+    catch_exceptions: bool,
+    catch_exceptions_hook: Option<Box<CatchExceptionsHook>>,
+    parse_macros: bool,
+    macro_filter_hook: Option<Box<MacroFilterHook>>,
+    exhaustive_enums: Vec<CppPath>,
+    options_struct_min_default_arguments: Option<usize>,
+    parse_doc_comments: bool,
+    template_instantiations: Vec<String>,
+    instantiate_template_functions: bool,
+    expose_protected_members: bool,
+    hash_functions: Vec<String>,
+    total_order_types: Vec<CppPath>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -255,6 +271,19 @@ impl Config {
             cluster_config: None,
             cpp_checker_tests: Default::default(),
             write_dependencies_local_paths: true,
+            generate_virtual_subclasses: false,
+            catch_exceptions: false,
+            catch_exceptions_hook: None,
+            parse_macros: false,
+            macro_filter_hook: None,
+            exhaustive_enums: Default::default(),
+            options_struct_min_default_arguments: None,
+            parse_doc_comments: false,
+            template_instantiations: Vec::new(),
+            instantiate_template_functions: false,
+            expose_protected_members: false,
+            hash_functions: Vec::new(),
+            total_order_types: Vec::new(),
         }
     }
 
@@ -398,6 +427,14 @@ impl Config {
         &self.cpp_build_config
     }
 
+    /// Sets the hook that determines whether objects of a class can be stored
+    /// inline on Rust side. Movable classes are wrapped in structs with the same size and
+    /// alignment as the C++ class, and their constructors and functions returning them
+    /// by value return the struct instead of a `CppBox`.
+    ///
+    /// Rust moves values by copying their bytes, so only classes that remain valid
+    /// after such a copy (e.g. classes that don't store pointers to themselves)
+    /// should be reported as movable.
     pub fn set_movable_types_hook(
         &mut self,
         hook: impl Fn(&CppPath) -> Result<MovableTypesHookOutput> + 'static,
@@ -522,6 +559,196 @@ impl Config {
     pub fn write_dependencies_local_paths(&self) -> bool {
         self.write_dependencies_local_paths
     }
+
+    /// Enables generation of C++ subclasses that allow to override
+    /// virtual functions of the library's classes from Rust.
+    /// Each class gets a trait whose default functions call the base class
+    /// implementation, and its subclass can be created with an implementation
+    /// of this trait. Individual functions can also be overridden
+    /// with Rust closures. Disabled by default.
+    pub fn set_generate_virtual_subclasses(&mut self, value: bool) {
+        self.generate_virtual_subclasses = value;
+    }
+
+    pub fn generate_virtual_subclasses(&self) -> bool {
+        self.generate_virtual_subclasses
+    }
+
+    /// Enables catching C++ exceptions in the generated FFI functions.
+    /// Rust wrappers of such functions return `Result<T, cpp_core::CppException>`.
+    /// Disabled by default.
+    pub fn set_catch_exceptions(&mut self, value: bool) {
+        self.catch_exceptions = value;
+    }
+
+    pub fn catch_exceptions(&self) -> bool {
+        self.catch_exceptions
+    }
+
+    /// Sets the hook that determines whether C++ exceptions should be caught
+    /// for a particular function. If the hook is set, the value set by
+    /// `set_catch_exceptions` is ignored.
+    pub fn set_catch_exceptions_hook(
+        &mut self,
+        hook: impl Fn(&CppFunction) -> Result<bool> + 'static,
+    ) {
+        assert!(
+            self.catch_exceptions_hook.is_none(),
+            "only one hook can be set"
+        );
+        self.catch_exceptions_hook = Some(Box::new(hook));
+    }
+
+    pub fn catch_exceptions_hook(&self) -> Option<&CatchExceptionsHook> {
+        self.catch_exceptions_hook.as_ref().map(|b| &**b)
+    }
+
+    /// Enables parsing of object-like `#define` macros with literal values
+    /// in the library's headers. Such macros are exposed as constants
+    /// in the `macros` module of the generated crate. Disabled by default.
+    pub fn set_parse_macros(&mut self, value: bool) {
+        self.parse_macros = value;
+    }
+
+    pub fn parse_macros(&self) -> bool {
+        self.parse_macros
+    }
+
+    /// Sets the hook that determines whether a macro with the specified name
+    /// should be parsed. All macros from the target include paths are parsed
+    /// if the hook is not set.
+    pub fn set_macro_filter_hook(&mut self, hook: impl Fn(&str) -> Result<bool> + 'static) {
+        assert!(self.macro_filter_hook.is_none(), "only one hook can be set");
+        self.macro_filter_hook = Some(Box::new(hook));
+    }
+
+    pub fn macro_filter_hook(&self) -> Option<&MacroFilterHook> {
+        self.macro_filter_hook.as_ref().map(|b| &**b)
+    }
+
+    /// Marks the C++ enum `path` as exhaustive. A closed Rust `enum` with
+    /// `TryFrom` conversion from the underlying integer type is generated for such enums
+    /// instead of a wrapper struct that can hold any value.
+    ///
+    /// The C++ library must never produce values of the enum other than its declared
+    /// variants. Receiving any other value from C++ is undefined behavior.
+    pub fn add_exhaustive_enum(&mut self, path: CppPath) {
+        self.exhaustive_enums.push(path);
+    }
+
+    pub fn exhaustive_enums(&self) -> &[CppPath] {
+        &self.exhaustive_enums
+    }
+
+    /// Enables generation of options structs for functions that have at least
+    /// `min_default_arguments` arguments with default values.
+    ///
+    /// An options struct contains a public field for each defaulted argument,
+    /// and its `Default` implementation initializes the fields with the default values
+    /// declared in C++. An additional `<function>_with_options` method accepts
+    /// the non-defaulted arguments and the options struct, so the caller only needs
+    /// to set the fields they care about.
+    ///
+    /// Arguments whose default value can't be evaluated outside of the function's
+    /// declaration (e.g. because it refers to names that are not qualified)
+    /// prevent generation of the options struct for that function.
+    ///
+    /// Options structs are not generated by default.
+    pub fn set_options_struct_min_default_arguments(&mut self, value: Option<usize>) {
+        self.options_struct_min_default_arguments = value;
+    }
+
+    pub fn options_struct_min_default_arguments(&self) -> Option<usize> {
+        self.options_struct_min_default_arguments
+    }
+
+    /// Enables reading documentation comments (e.g. Doxygen comments) of
+    /// the parsed items from the library's headers. The comments are added
+    /// to the documentation of the generated crate. Disabled by default.
+    ///
+    /// The comments are added by the C++ parser, so steps that add documentation
+    /// later (e.g. from a custom source) don't affect items that have a comment.
+    pub fn set_parse_doc_comments(&mut self, value: bool) {
+        self.parse_doc_comments = value;
+    }
+
+    pub fn parse_doc_comments(&self) -> bool {
+        self.parse_doc_comments
+    }
+
+    /// Requests an instantiation of a class template that is not necessarily
+    /// used by the library's API. `type_code` must be a valid C++ type
+    /// (e.g. `"QHash<QString, QVariant>"` or `"std::array<int, 4>"`) that can be
+    /// resolved using the library's include directives.
+    ///
+    /// Methods of the template are instantiated with the specified arguments
+    /// and checked by the C++ checker like other template instantiations.
+    pub fn add_template_instantiation(&mut self, type_code: impl Into<String>) {
+        self.template_instantiations.push(type_code.into());
+    }
+
+    pub fn template_instantiations(&self) -> &[String] {
+        &self.template_instantiations
+    }
+
+    /// Enables instantiation of member function templates with a single type parameter
+    /// (e.g. `QObject::findChild<T>`) for every class type of the library and
+    /// its dependencies. Each instantiation is verified by the C++ checker.
+    /// If the template parameter is only used in the return type, the Rust crate
+    /// exposes the instantiations as a generic method bounded by a trait implemented
+    /// for all supported template arguments.
+    ///
+    /// This can produce a large amount of items to check, so it's disabled by default.
+    pub fn set_instantiate_template_functions(&mut self, value: bool) {
+        self.instantiate_template_functions = value;
+    }
+
+    pub fn instantiate_template_functions(&self) -> bool {
+        self.instantiate_template_functions
+    }
+
+    /// Enables FFI wrappers for protected methods and fields of C++ classes.
+    /// The C++ wrappers access them through a local subclass that makes
+    /// the member public. In the Rust crate, protected members of a class are
+    /// only available through a separate `*Protected` trait implemented for the class.
+    ///
+    /// Protected members are not intended to be used outside of the class hierarchy,
+    /// so they are not exposed by default.
+    pub fn set_expose_protected_members(&mut self, value: bool) {
+        self.expose_protected_members = value;
+    }
+
+    pub fn expose_protected_members(&self) -> bool {
+        self.expose_protected_members
+    }
+
+    /// Adds the name of a free function that computes hashes of the library's values
+    /// (e.g. `"qHash"`). An overload of this function that accepts a single class
+    /// object (other arguments must have default values) is used to implement
+    /// `cpp_core::CppHash` for the class, so `CppBox` of the class implements `Hash`.
+    pub fn add_hash_function(&mut self, name: impl Into<String>) {
+        self.hash_functions.push(name.into());
+    }
+
+    pub fn hash_functions(&self) -> &[String] {
+        &self.hash_functions
+    }
+
+    /// Marks the C++ class `path` as having a total order: its `operator==` is
+    /// an equivalence relation, and its `operator<` (if present) is a strict total order
+    /// consistent with it. `cpp_core::cmp::TotalEq` (and `cpp_core::cmp::TotalOrd`
+    /// if `operator<` is available) is implemented for such classes,
+    /// so `CppBox` of the class implements `Eq` (and `Ord`).
+    ///
+    /// Marking a class that doesn't satisfy these requirements breaks
+    /// collections like `HashSet` and `BTreeMap` that contain its values.
+    pub fn add_total_order_type(&mut self, path: CppPath) {
+        self.total_order_types.push(path);
+    }
+
+    pub fn total_order_types(&self) -> &[CppPath] {
+        &self.total_order_types
+    }
 }
 
 #[derive(Default)]
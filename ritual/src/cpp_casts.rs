@@ -15,6 +15,7 @@ fn create_cast_method(cast: CppCast, from: &CppType, to: &CppType) -> Result<Cpp
         path: CppPath::from_item(CppPathItem {
             name: cast.cpp_method_name().into(),
             template_arguments: Some(vec![to.clone()]),
+            inline_namespaces: Vec::new(),
         }),
         member: None,
         operator: None,
@@ -23,9 +24,16 @@ fn create_cast_method(cast: CppCast, from: &CppType, to: &CppType) -> Result<Cpp
             name: "ptr".to_string(),
             argument_type: from.clone(),
             has_default_value: false,
+            default_value: None,
+            type_alias: None,
         }],
         allows_variadic_arguments: false,
+        has_c_linkage: false,
         declaration_code: None,
+        deprecation: None,
+        is_deleted: false,
+        is_friend: false,
+        return_type_alias: None,
         cast: Some(cast),
     };
     Ok(CppItem::Function(function))
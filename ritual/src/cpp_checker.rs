@@ -59,6 +59,10 @@ fn snippet_for_item(item: DbItem<&CppFfiItem>, database: &DatabaseClient) -> Res
             item.item.source_item_cpp_code(database)?,
             true,
         )),
+        CppFfiItem::VirtualSubclass(_) => Ok(Snippet::new_global(
+            item.item.source_item_cpp_code(database)?,
+            false,
+        )),
     }
 }
 
@@ -667,6 +671,7 @@ fn type_paths(type1: &CppType) -> Vec<&CppPath> {
         | CppType::PointerSizedInteger { .. }
         | CppType::TemplateParameter { .. } => Vec::new(),
         CppType::Enum { path } | CppType::Class(path) => vec![path],
+        CppType::IntegralConstant { value_type, .. } => type_paths(value_type),
         CppType::FunctionPointer(function) => function
             .arguments
             .iter()
@@ -674,6 +679,7 @@ fn type_paths(type1: &CppType) -> Vec<&CppPath> {
             .flat_map(|type1| type_paths(type1))
             .collect(),
         CppType::PointerLike { target, .. } => type_paths(target),
+        CppType::Array { element, .. } => type_paths(element),
     }
 }
 
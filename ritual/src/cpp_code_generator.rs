@@ -1,11 +1,14 @@
 use crate::config::Config;
 use crate::cpp_checks::Condition;
+use crate::cpp_data::CppItem;
 use crate::cpp_ffi_data::{
     CppFfiArgumentMeaning, CppFfiFunctionKind, CppFfiType, CppFieldAccessorType,
-    CppToFfiTypeConversion, QtSignalWrapper, QtSlotWrapper,
+    CppToFfiTypeConversion, CppVirtualFunctionOverride, CppVirtualSubclass, QtSignalWrapper,
+    QtSlotWrapper,
 };
 use crate::cpp_ffi_data::{CppFfiFunction, CppFfiItem};
-use crate::cpp_function::{CppFunction, ReturnValueAllocationPlace};
+use crate::cpp_function::{CppFunction, CppFunctionArgument, ReturnValueAllocationPlace};
+use crate::cpp_type::CppFunctionPointerType;
 use crate::cpp_type::CppPointerLikeTypeKind;
 use crate::cpp_type::CppType;
 use crate::database::{DatabaseClient, DbItem};
@@ -14,12 +17,64 @@ use itertools::Itertools;
 use ritual_common::cpp_lib_builder::version_to_number;
 use ritual_common::errors::{bail, err_msg, format_err, Result};
 use ritual_common::file_utils::{create_file, os_str_to_str, path_to_str, read_dir};
+use ritual_common::sized_types;
 use ritual_common::utils::MapIfOk;
 use std::collections::HashSet;
 use std::io::Write;
 use std::iter::once;
 use std::path::{Path, PathBuf};
 
+/// Name of the local subclass used to access protected members.
+const PROTECTED_ACCESSOR_NAME: &str = "ritual_protected_accessor";
+
+/// Generates a local subclass that makes the protected member `item` public.
+fn protected_accessor_definition(item: &CppItem) -> Result<String> {
+    let path = item
+        .path()
+        .ok_or_else(|| err_msg("protected member expected to have a path"))?;
+    let class_code = path.parent()?.to_cpp_code()?;
+    let name = item
+        .as_field_ref()
+        .and_then(|field| field.member_access.as_ref())
+        .and_then(|member_access| member_access.split('.').next())
+        .unwrap_or(path.last().name.as_str());
+    Ok(format!(
+        "struct {accessor} : {class} {{ using {class}::{name}; }};\n  ",
+        accessor = PROTECTED_ACCESSOR_NAME,
+        class = class_code,
+        name = name
+    ))
+}
+
+/// Returns a pointer to the protected member `member` of `item`, taken through
+/// the accessor subclass. Member pointers refer to the base class, so
+/// they can be applied to the original object.
+fn protected_member_pointer(item: &CppItem, member: &str) -> Result<String> {
+    let address = format!("&{}::{}", PROTECTED_ACCESSOR_NAME, member);
+    let function = if let Some(function) = item.as_function_ref() {
+        function
+    } else {
+        return Ok(address);
+    };
+    // the type selects the overload
+    let mut arg_texts = function
+        .arguments
+        .iter()
+        .map_if_ok(|arg| arg.argument_type.to_cpp_code(None))?;
+    if function.allows_variadic_arguments {
+        arg_texts.push("...".to_string());
+    }
+    let is_const = function.member.as_ref().map_or(false, |m| m.is_const);
+    Ok(format!(
+        "static_cast<{} ({}::*)({}){}>({})",
+        function.return_type.to_cpp_code(None)?,
+        function.class_path()?.to_cpp_code()?,
+        arg_texts.join(", "),
+        if is_const { " const" } else { "" },
+        address
+    ))
+}
+
 struct Generator<'a>(&'a DatabaseClient);
 
 impl Generator<'_> {
@@ -96,6 +151,230 @@ impl Generator<'_> {
         ))
     }
 
+    /// Generates declaration of `function` with renamed arguments, as it appears
+    /// inside the generated subclass.
+    fn virtual_function_signature(&self, function: &CppFunction, name: &str) -> Result<String> {
+        let arguments = function
+            .arguments
+            .iter()
+            .enumerate()
+            .map_if_ok(|(num, arg)| {
+                CppFunctionArgument {
+                    name: format!("arg{}", num),
+                    argument_type: arg.argument_type.clone(),
+                    has_default_value: false,
+                    default_value: None,
+                    type_alias: None,
+                }
+                .to_cpp_code()
+            })?
+            .join(", ");
+        let name_with_args = format!("{}({})", name, arguments);
+        let mut r = if let CppType::FunctionPointer(..) = function.return_type {
+            function.return_type.to_cpp_code(Some(&name_with_args))?
+        } else {
+            format!(
+                "{} {}",
+                function.return_type.to_cpp_code(None)?,
+                name_with_args
+            )
+        };
+        if function.member.as_ref().map_or(false, |m| m.is_const) {
+            r = format!("{} const", r);
+        }
+        Ok(r)
+    }
+
+    /// Generates code that calls a Rust callback using `callback_call` expression
+    /// and returns its result converted back to `return_type.original_type()`.
+    fn callback_return_code(
+        &self,
+        return_type: &CppFfiType,
+        callback_call: &str,
+    ) -> Result<String> {
+        Ok(if return_type.original_type().is_void() {
+            format!("{};\n            return;", callback_call)
+        } else {
+            match return_type.conversion() {
+                CppToFfiTypeConversion::NoChange | CppToFfiTypeConversion::ImplicitCast { .. } => {
+                    format!("return {};", callback_call)
+                }
+                CppToFfiTypeConversion::ReferenceToPointer => format!("return *{};", callback_call),
+                CppToFfiTypeConversion::ValueToPointer { .. } => format!(
+                    "std::unique_ptr<{}> result({});\n            return std::move(*result);",
+                    return_type.original_type().to_cpp_code(None)?,
+                    callback_call
+                ),
+                CppToFfiTypeConversion::QFlagsToInt => format!(
+                    "return {}({});",
+                    return_type.original_type().to_cpp_code(None)?,
+                    callback_call
+                ),
+                CppToFfiTypeConversion::RValueReferenceToPointer
+                | CppToFfiTypeConversion::ArrayToPointer
+                | CppToFfiTypeConversion::FunctionToCallback { .. } => {
+                    bail!("unsupported return type conversion for callback")
+                }
+            }
+        })
+    }
+
+    /// Generates the body of a virtual function override that calls
+    /// the callback with index `index`.
+    fn virtual_function_override_body(
+        &self,
+        function: &CppVirtualFunctionOverride,
+        index: usize,
+    ) -> Result<String> {
+        let callback_args = once(format!("m_callback_{}.data()", index))
+            .chain(
+                function
+                    .arguments
+                    .iter()
+                    .enumerate()
+                    .map_if_ok(|(num, t)| self.convert_type_to_ffi(t, format!("arg{}", num)))?,
+            )
+            .join(", ");
+        let callback_call = format!("callback({})", callback_args);
+        let callback_code = self.callback_return_code(&function.return_type, &callback_call)?;
+
+        let member = function
+            .function
+            .member
+            .as_ref()
+            .ok_or_else(|| err_msg("virtual function must be a class member"))?;
+        let fallback = if member.is_pure_virtual {
+            format!(
+                "std::cout << \"ritual: pure virtual function {} is not overridden\\n\";\n        \
+                 std::abort();",
+                function.function.path.to_cpp_pseudo_code()
+            )
+        } else {
+            format!(
+                "return {}({});",
+                function.function.path.to_cpp_code()?,
+                (0..function.arguments.len())
+                    .map(|num| format!("arg{}", num))
+                    .join(", ")
+            )
+        };
+        Ok(format!(
+            "        auto callback = m_callback_{index}.get();
+        if (callback) {{
+            {callback_code}
+        }}
+        {fallback}
+",
+            index = index,
+            callback_code = callback_code,
+            fallback = fallback
+        ))
+    }
+
+    /// Generates code for a subclass that allows to override virtual functions
+    fn virtual_subclass(&self, subclass: &CppVirtualSubclass) -> Result<String> {
+        let class_name = subclass.class_path.to_cpp_code()?;
+        let base_class_name = subclass.base_class_path.to_cpp_code()?;
+        let constructor_name = &subclass.class_path.last().name;
+
+        let mut constructors = Vec::new();
+        for constructor in &subclass.constructors {
+            let arguments = constructor
+                .arguments
+                .iter()
+                .enumerate()
+                .map_if_ok(|(num, arg)| {
+                    CppFunctionArgument {
+                        name: format!("arg{}", num),
+                        argument_type: arg.argument_type.clone(),
+                        has_default_value: false,
+                        default_value: None,
+                        type_alias: None,
+                    }
+                    .to_cpp_code()
+                })?
+                .join(", ");
+            let values = constructor
+                .arguments
+                .iter()
+                .enumerate()
+                .map(|(num, arg)| {
+                    if let CppType::PointerLike {
+                        kind: CppPointerLikeTypeKind::RValueReference,
+                        ..
+                    } = arg.argument_type
+                    {
+                        format!("std::move(arg{})", num)
+                    } else {
+                        format!("arg{}", num)
+                    }
+                })
+                .join(", ");
+            constructors.push(format!(
+                "    {}({}) : {}({}) {{}}\n",
+                constructor_name, arguments, base_class_name, values
+            ));
+        }
+
+        let mut public_methods = Vec::new();
+        let mut overrides = Vec::new();
+        let mut fields = Vec::new();
+        for (index, function) in subclass.virtual_functions.iter().enumerate() {
+            let func_type = CppType::FunctionPointer(function.function_type.clone());
+            let name = &function.function.path.last().name;
+            public_methods.push(format!(
+                "    void {}({}, void (*deleter)(void*), void* data) {{
+        m_callback_{}.set(callback, deleter, data);
+    }}
+",
+                function.setter_name(),
+                func_type.to_cpp_code(Some("callback"))?,
+                index
+            ));
+            let is_pure_virtual = function
+                .function
+                .member
+                .as_ref()
+                .map_or(false, |m| m.is_pure_virtual);
+            if !is_pure_virtual {
+                public_methods.push(format!(
+                    "    {} {{
+        return {}({});
+    }}
+",
+                    self.virtual_function_signature(
+                        &function.function,
+                        &function.base_function_name()
+                    )?,
+                    function.function.path.to_cpp_code()?,
+                    (0..function.arguments.len())
+                        .map(|num| format!("arg{}", num))
+                        .join(", ")
+                ));
+            }
+            overrides.push(format!(
+                "    {} override {{\n{}    }}\n",
+                self.virtual_function_signature(&function.function, name)?,
+                self.virtual_function_override_body(function, index)?
+            ));
+            fields.push(format!(
+                "    ritual::Callback<{}> m_callback_{};\n",
+                func_type.to_cpp_code(Some(""))?,
+                index
+            ));
+        }
+
+        Ok(format!(
+            include_str!("../templates/c_lib/virtual_subclass.h"),
+            class_name = class_name,
+            base_class_name = base_class_name,
+            constructors = constructors.join(""),
+            public_methods = public_methods.join("\n"),
+            overrides = overrides.join("\n"),
+            fields = fields.join("")
+        ))
+    }
+
     /// Generates code that wraps `expression` of type `type1.original_type` and
     /// converts it to type `type1.ffi_type`
     fn convert_type_to_ffi(&self, type1: &CppFfiType, expression: String) -> Result<String> {
@@ -108,8 +387,13 @@ impl Generator<'_> {
                 type1.original_type().to_cpp_code(None)?,
                 expression
             ),
-            CppToFfiTypeConversion::ReferenceToPointer => format!("&{}", expression),
+            CppToFfiTypeConversion::ReferenceToPointer
+            | CppToFfiTypeConversion::RValueReferenceToPointer
+            | CppToFfiTypeConversion::ArrayToPointer => format!("&{}", expression),
             CppToFfiTypeConversion::QFlagsToInt => format!("int({})", expression),
+            CppToFfiTypeConversion::FunctionToCallback { .. } => {
+                bail!("std::function can't be converted to FFI type")
+            }
         })
     }
 
@@ -124,12 +408,13 @@ impl Generator<'_> {
             .0
             .source_cpp_item(&item.id)?
             .ok_or_else(|| format_err!("failed to find original cpp item for {:?}", item))?;
-        let is_constructor = cpp_item
-            .item
-            .as_function_ref()
-            .map_or(false, |f| f.is_constructor());
-
         let method = item.item;
+        let is_constructor = method.kind == CppFfiFunctionKind::Function
+            && cpp_item
+                .item
+                .as_function_ref()
+                .map_or(false, |f| f.is_constructor());
+
         let mut result = expression;
         match method.return_type.conversion() {
             CppToFfiTypeConversion::NoChange | CppToFfiTypeConversion::ImplicitCast { .. } => {}
@@ -158,9 +443,29 @@ impl Generator<'_> {
             CppToFfiTypeConversion::ReferenceToPointer => {
                 result = format!("&{}", result);
             }
+            CppToFfiTypeConversion::RValueReferenceToPointer => {
+                bail!("rvalue references are not supported as return types");
+            }
+            CppToFfiTypeConversion::ArrayToPointer => {
+                if method.allocation_place != ReturnValueAllocationPlace::Stack {
+                    bail!("arrays can only be returned through the output argument");
+                }
+                let arg = method
+                    .arguments
+                    .iter()
+                    .find(|x| x.meaning == CppFfiArgumentMeaning::ReturnValue)
+                    .ok_or_else(|| err_msg("return value argument not found"))?;
+                return Ok(format!(
+                    "std::memcpy({0}, &{1}, sizeof(*{0}))",
+                    arg.name, result
+                ));
+            }
             CppToFfiTypeConversion::QFlagsToInt => {
                 result = format!("int({})", result);
             }
+            CppToFfiTypeConversion::FunctionToCallback { .. } => {
+                bail!("std::function is not supported as a return type");
+            }
         }
 
         if method.allocation_place == ReturnValueAllocationPlace::Stack && !is_constructor {
@@ -176,6 +481,53 @@ impl Generator<'_> {
         Ok(result)
     }
 
+    /// Generates code that creates a `std::function` calling the Rust callback
+    /// passed to the FFI function in `ritual_callback`, `ritual_callback_deleter`
+    /// and `ritual_callback_data` arguments.
+    fn callback_to_function(
+        &self,
+        original_type: &CppType,
+        arguments: &[CppFfiType],
+        return_type: &CppFfiType,
+    ) -> Result<String> {
+        let function_type = match original_type {
+            CppType::PointerLike { target, .. } => &**target,
+            other => other,
+        };
+        let lambda_args = once("void* data".to_string())
+            .chain(arguments.iter().enumerate().map_if_ok(|(num, arg)| {
+                CppFunctionArgument {
+                    name: format!("arg{}", num),
+                    argument_type: arg.original_type().clone(),
+                    has_default_value: false,
+                    default_value: None,
+                    type_alias: None,
+                }
+                .to_cpp_code()
+            })?)
+            .join(", ");
+        let callback_args = once("data".to_string())
+            .chain(
+                arguments
+                    .iter()
+                    .enumerate()
+                    .map_if_ok(|(num, t)| self.convert_type_to_ffi(t, format!("arg{}", num)))?,
+            )
+            .join(", ");
+        let callback_code =
+            self.callback_return_code(return_type, &format!("ritual_callback({})", callback_args))?;
+        Ok(format!(
+            "{}(ritual::bind_callback_data(
+            [ritual_callback]({}) -> {} {{
+            {}
+        }}, ritual_callback_deleter, ritual_callback_data))",
+            function_type.to_cpp_code(None)?,
+            lambda_args,
+            return_type.original_type().to_cpp_code(None)?,
+            callback_code
+        ))
+    }
+
     /// Generates code for values passed to the original C++ method.
     fn arguments_values(&self, method: &CppFfiFunction) -> Result<String> {
         let r = method
@@ -186,7 +538,11 @@ impl Generator<'_> {
                 let mut result = argument.name.clone();
                 match argument.argument_type.conversion() {
                     CppToFfiTypeConversion::ValueToPointer { .. }
-                    | CppToFfiTypeConversion::ReferenceToPointer => result = format!("*{}", result),
+                    | CppToFfiTypeConversion::ReferenceToPointer
+                    | CppToFfiTypeConversion::ArrayToPointer => result = format!("*{}", result),
+                    CppToFfiTypeConversion::RValueReferenceToPointer => {
+                        result = format!("std::move(*{})", result)
+                    }
                     CppToFfiTypeConversion::NoChange
                     | CppToFfiTypeConversion::ImplicitCast { .. } => {}
                     CppToFfiTypeConversion::QFlagsToInt => {
@@ -206,6 +562,16 @@ impl Generator<'_> {
                         };
                         result = format!("{}({})", type_text, result);
                     }
+                    CppToFfiTypeConversion::FunctionToCallback {
+                        arguments,
+                        return_type,
+                    } => {
+                        result = self.callback_to_function(
+                            argument.argument_type.original_type(),
+                            arguments,
+                            return_type,
+                        )?;
+                    }
                 }
                 Ok(result)
             })?;
@@ -235,6 +601,21 @@ impl Generator<'_> {
             } else {
                 bail!("no this arg in destructor");
             }
+        } else if let CppFfiFunctionKind::DefaultArgument { index } = &method.kind {
+            let cpp_function = cpp_item
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            let default_value = cpp_function
+                .arguments
+                .get(*index)
+                .and_then(|arg| arg.default_value.as_ref())
+                .ok_or_else(|| err_msg("default value of the argument is unknown"))?;
+            format!(
+                "static_cast<{}>({})",
+                method.return_type.original_type().to_cpp_code(None)?,
+                default_value
+            )
         } else {
             let result_without_args = if let Some(cpp_function) = cpp_item
                 .item
@@ -274,18 +655,66 @@ impl Generator<'_> {
                     .iter()
                     .find(|x| x.meaning == CppFfiArgumentMeaning::This)
                 {
-                    format!("{}->{}", arg.name, path.last().to_cpp_code()?)
+                    let member = match cpp_item
+                        .item
+                        .as_field_ref()
+                        .and_then(|field| field.member_access.clone())
+                    {
+                        Some(member_access) => member_access,
+                        None => path.last().to_cpp_code()?,
+                    };
+                    if cpp_item.item.is_protected_member() {
+                        // nested members of anonymous structs are accessed
+                        // through the outer field
+                        let (name, rest) = match member.find('.') {
+                            Some(index) => member.split_at(index),
+                            None => (member.as_str(), ""),
+                        };
+                        format!(
+                            "({}->*{}){}",
+                            arg.name,
+                            protected_member_pointer(cpp_item.item, name)?,
+                            rest
+                        )
+                    } else {
+                        format!("{}->{}", arg.name, member)
+                    }
+                } else if cpp_item.item.is_protected_member() {
+                    format!(
+                        "{}::{}",
+                        PROTECTED_ACCESSOR_NAME,
+                        path.last().to_cpp_code()?
+                    )
+                } else if cpp_item.item.as_function_ref().map_or(false, |f| {
+                    f.is_friend && f.member.is_none() && f.path.last().template_arguments.is_none()
+                }) {
+                    // functions declared only as friends can't be found
+                    // by qualified lookup, only by argument-dependent lookup
+                    path.last().to_cpp_code()?
                 } else {
                     path.to_cpp_code()?
                 }
             };
             if let CppFfiFunctionKind::FieldAccessor { accessor_type, .. } = &method.kind {
                 if accessor_type == &CppFieldAccessorType::Setter {
-                    format!(
-                        "{} = {}",
-                        result_without_args,
-                        self.arguments_values(method)?
-                    )
+                    let value = method
+                        .arguments
+                        .iter()
+                        .find(|arg| arg.meaning.is_argument())
+                        .ok_or_else(|| err_msg("setter value argument not found"))?;
+                    if value.argument_type.conversion() == &CppToFfiTypeConversion::ArrayToPointer {
+                        // arrays are not assignable
+                        format!(
+                            "std::memcpy(&{0}, {1}, sizeof({0}))",
+                            result_without_args, value.name
+                        )
+                    } else {
+                        format!(
+                            "{} = {}",
+                            result_without_args,
+                            self.arguments_values(method)?
+                        )
+                    }
                 } else {
                     result_without_args
                 }
@@ -324,8 +753,14 @@ impl Generator<'_> {
                 panic!("Error: no this argument found\n{:?}", method);
             }
         } else {
+            let accessor = if cpp_item.item.is_protected_member() {
+                protected_accessor_definition(cpp_item.item)?
+            } else {
+                String::new()
+            };
             Ok(format!(
-                "{}{};\n",
+                "{}{}{};\n",
+                accessor,
                 if method.return_type.ffi_type().is_void() {
                     ""
                 } else {
@@ -336,12 +771,72 @@ impl Generator<'_> {
         }
     }
 
+    /// Generates a compile-time check of the signature of a variadic function.
+    /// Such functions are called directly, so no wrapper is generated.
+    fn variadic_function_check(&self, method: DbItem<&CppFfiFunction>) -> Result<String> {
+        let cpp_item = self
+            .0
+            .source_cpp_item(&method.id)?
+            .ok_or_else(|| format_err!("failed to find original cpp item for {:?}", method))?;
+        let cpp_function = cpp_item
+            .item
+            .as_function_ref()
+            .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+
+        let function_type = CppType::FunctionPointer(CppFunctionPointerType {
+            return_type: Box::new(method.item.return_type.ffi_type().clone()),
+            arguments: method
+                .item
+                .arguments
+                .iter()
+                .map(|arg| arg.argument_type.ffi_type().clone())
+                .collect(),
+            allows_variadic_arguments: true,
+        });
+        let path_code = cpp_function.path.to_cpp_code()?;
+        Ok(format!(
+            "static_assert(std::is_convertible<decltype(&{}), {}>::value, \"{} has unexpected signature\");\n\n",
+            path_code,
+            function_type.to_cpp_code(Some(""))?,
+            path_code
+        ))
+    }
+
     /// Generates implementation of the FFI method for the source file.
     fn function_implementation(&self, method: DbItem<&CppFfiFunction>) -> Result<String> {
+        if method.item.allows_variadic_arguments {
+            return self.variadic_function_check(method);
+        }
+        let mut body = self.source_body(method.clone())?;
+        if let Some(arg) = method
+            .item
+            .arguments
+            .iter()
+            .find(|x| x.meaning == CppFfiArgumentMeaning::ExceptionMessage)
+        {
+            // exceptions must not propagate through `extern "C"` functions
+            body = format!(
+                "*{arg} = nullptr;
+  try {{
+    {body}  }} catch (const std::exception& e) {{
+    *{arg} = ritual::exception_message(e.what());
+  }} catch (...) {{
+    *{arg} = ritual::exception_message(\"unknown C++ exception\");
+  }}
+  {fallback}",
+                arg = arg.name,
+                body = body,
+                fallback = if method.item.return_type.ffi_type().is_void() {
+                    ""
+                } else {
+                    "return {};\n"
+                }
+            );
+        }
         Ok(format!(
             "RITUAL_EXPORT {} {{\n  {}}}\n\n",
             self.function_signature(method.item)?,
-            self.source_body(method)?
+            body
         ))
     }
 
@@ -427,7 +922,16 @@ impl Generator<'_> {
                     let code = self.qt_signal_wrapper(qt_signal_wrapper)?;
                     write!(cpp_file, "{}", self.wrap_with_condition(&code, &condition))?;
                 }
-                _ => {}
+                CppFfiItem::VirtualSubclass(virtual_subclass) => {
+                    let checks = self.0.cpp_checks(&ffi_item.id)?;
+                    if !checks.any_success() {
+                        continue;
+                    }
+                    let condition = checks.condition(self.0.environments());
+                    let code = self.virtual_subclass(virtual_subclass)?;
+                    write!(cpp_file, "{}", self.wrap_with_condition(&code, &condition))?;
+                }
+                CppFfiItem::Function(_) => {}
             }
         }
 
@@ -454,9 +958,10 @@ impl Generator<'_> {
         Ok(())
     }
 
-    /// Generates a C++ program that determines sizes of target C++ types
-    /// on the current platform and outputs the Rust code for `sized_types.rs` module
-    /// to the standard output.
+    /// Generates C++ code that stores sizes and alignments of target C++ types
+    /// in an array in the format expected by `ritual_common::sized_types`.
+    /// The build script reads the array from the compiled library, so the code
+    /// doesn't need to run on the build platform.
     fn generate_cpp_type_size_requester(
         &self,
         include_directives: &[PathBuf],
@@ -465,31 +970,40 @@ impl Generator<'_> {
         for dir in include_directives {
             writeln!(output, "#include <{}>", path_to_str(dir)?)?;
         }
-        writeln!(output, "#include <stdio.h>\n\nint main() {{")?;
+        let u64_bytes = (0..8)
+            .map(|i| format!("(unsigned char)((unsigned long long)(value) >> {})", i * 8))
+            .join(", \\\n    ");
+        writeln!(
+            output,
+            "\n#define RITUAL_U64_BYTES(value) \\\n    {}\n",
+            u64_bytes
+        )?;
+        writeln!(
+            output,
+            "extern \"C\" const unsigned char ritual_sized_types[] = {{"
+        )?;
+        writeln!(output, "  {},", sized_types::MAGIC.iter().join(", "))?;
 
         let rust_items = self.0.rust_items().map(|i| i.item);
         for item in rust_items {
             if let RustItem::Struct(data) = item {
                 if let RustStructKind::SizedType(sized_type) = &data.kind {
                     let cpp_path_code = sized_type.cpp_path.to_cpp_code()?;
-
                     writeln!(
                         output,
-                        "printf(\"#[repr(C, align(%zu))]\\n\", alignof({}));",
-                        cpp_path_code
+                        "  {}, 0,",
+                        data.path.last().as_bytes().iter().join(", ")
                     )?;
-
                     writeln!(
                         output,
-                        "printf(\"pub struct {}([u8; %zu]);\\n\\n\", sizeof({}));",
-                        data.path.last(),
+                        "  RITUAL_U64_BYTES(sizeof({0})), RITUAL_U64_BYTES(alignof({0})),",
                         cpp_path_code
                     )?;
                 }
             }
         }
 
-        writeln!(output, "}}")?;
+        writeln!(output, "  0\n}};")?;
         Ok(())
     }
 }
@@ -509,6 +1023,10 @@ pub fn qt_signal_wrapper(db: &DatabaseClient, wrapper: &QtSignalWrapper) -> Resu
     Generator(db).qt_signal_wrapper(wrapper)
 }
 
+pub fn virtual_subclass(db: &DatabaseClient, subclass: &CppVirtualSubclass) -> Result<String> {
+    Generator(db).virtual_subclass(subclass)
+}
+
 pub fn generate_cpp_file(
     db: &DatabaseClient,
     file_path: &Path,
@@ -2,13 +2,16 @@
 
 use crate::cpp_function::CppFunction;
 pub use crate::cpp_operator::CppOperator;
-use crate::cpp_type::{CppTemplateParameter, CppType};
+use crate::cpp_type::{CppBuiltInNumericType, CppTemplateParameter, CppType};
 use crate::database::DatabaseClient;
 use itertools::Itertools;
 use ritual_common::errors::{bail, ensure, Error, Result};
 use ritual_common::utils::MapIfOk;
+use serde::{Deserialize, Deserializer};
 use serde_derive::{Deserialize, Serialize};
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::once;
 use std::str::FromStr;
 
 /// One item of a C++ enum declaration
@@ -16,7 +19,8 @@ use std::str::FromStr;
 pub struct CppEnumValue {
     /// Full path containing enum path and variant name.
     pub path: CppPath,
-    /// Corresponding value
+    /// Corresponding value. Values that don't fit in `i64` (only possible
+    /// with unsigned 64-bit underlying types) are stored as their bit pattern.
     pub value: i64,
 }
 
@@ -58,6 +62,16 @@ pub struct CppClassField {
     /// Visibility
     pub visibility: CppVisibility,
     pub is_static: bool,
+    #[serde(default)]
+    pub is_const: bool,
+    /// Value of the field if it's a static constant that can be evaluated at compile time
+    #[serde(default)]
+    pub value: Option<CppConstantValue>,
+    /// C++ expression that accesses the field from an object if it's different
+    /// from the field's name (e.g. `data.i` for a member of a field
+    /// of an unnamed type `union { int i; } data;`)
+    #[serde(default)]
+    pub member_access: Option<String>,
 }
 
 impl CppClassField {
@@ -83,6 +97,51 @@ impl CppClassField {
     }
 }
 
+/// Variable declared in a namespace or in the global scope
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct CppVariable {
+    pub path: CppPath,
+    /// Variable type
+    pub variable_type: CppType,
+    pub is_const: bool,
+    /// Value of the variable if it's a constant that can be evaluated at compile time
+    pub value: Option<CppConstantValue>,
+}
+
+impl CppVariable {
+    pub fn is_same(&self, other: &CppVariable) -> bool {
+        self.path == other.path && self.variable_type == other.variable_type
+    }
+
+    pub fn short_text(&self) -> String {
+        format!(
+            "{}{} {}",
+            if self.is_const { "const " } else { "" },
+            self.variable_type.to_cpp_pseudo_code(),
+            self.path.to_cpp_pseudo_code(),
+        )
+    }
+}
+
+/// Object-like `#define` macro with a literal value
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct CppMacroConstant {
+    /// Name of the macro
+    pub path: CppPath,
+    /// Type of the literal
+    pub value_type: CppType,
+    pub value: CppConstantValue,
+}
+
+/// Value of a C++ constant evaluated by clang
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub enum CppConstantValue {
+    SignedInteger(i64),
+    UnsignedInteger(u64),
+    /// Floating point value formatted as a Rust literal
+    FloatingPoint(String),
+}
+
 /// Item of base class list in a class declaration
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct CppBaseSpecifier {
@@ -120,10 +179,31 @@ pub enum CppVisibility {
     Private,
 }
 
-#[derive(PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CppPathItem {
     pub name: String,
     pub template_arguments: Option<Vec<CppType>>,
+    /// Inline namespaces enclosing this item that are omitted from the path
+    /// (e.g. `__1` in `std::__1::basic_string` in libc++).
+    /// They are only used in the generated C++ code and are ignored
+    /// when paths are compared, so Rust names don't depend on them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inline_namespaces: Vec<String>,
+}
+
+impl PartialEq for CppPathItem {
+    fn eq(&self, other: &CppPathItem) -> bool {
+        self.name == other.name && self.template_arguments == other.template_arguments
+    }
+}
+
+impl Eq for CppPathItem {}
+
+impl Hash for CppPathItem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.template_arguments.hash(state);
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
@@ -153,11 +233,20 @@ impl CppPath {
         &self.items
     }
 
+    /// Returns the fully qualified name, including the inline namespaces
+    /// omitted from the path.
     pub fn to_cpp_code(&self) -> Result<String> {
         Ok(self
             .items
             .iter()
-            .map_if_ok(CppPathItem::to_cpp_code)?
+            .map_if_ok(|item| -> Result<_> {
+                Ok(item
+                    .inline_namespaces
+                    .iter()
+                    .map(|name| format!("{}::", name))
+                    .chain(once(item.to_cpp_code()?))
+                    .join(""))
+            })?
             .join("::"))
     }
 
@@ -267,12 +356,20 @@ impl CppPath {
         let mut nested_level = 0;
         for item in &mut path.items {
             if let Some(args) = &mut item.template_arguments {
-                *args = (0..args.len())
-                    .map(|index| {
+                *args = args
+                    .iter()
+                    .enumerate()
+                    .map(|(index, arg)| {
+                        let value_type = if let CppType::IntegralConstant { value_type, .. } = arg {
+                            Some(value_type.clone())
+                        } else {
+                            None
+                        };
                         CppType::TemplateParameter(CppTemplateParameter {
                             nested_level,
                             index,
                             name: format!("T{}_{}", nested_level, index),
+                            value_type,
                         })
                     })
                     .collect();
@@ -298,6 +395,7 @@ impl FromStr for CppPath {
             .map(|item| CppPathItem {
                 name: item.into(),
                 template_arguments: None,
+                inline_namespaces: Vec::new(),
             })
             .collect();
         Ok(CppPath { items })
@@ -348,6 +446,7 @@ impl FromStr for CppPathItem {
         Ok(CppPathItem {
             name: name.into(),
             template_arguments: None,
+            inline_namespaces: Vec::new(),
         })
     }
 }
@@ -369,8 +468,48 @@ impl fmt::Debug for CppPathItem {
 /// Information about a C++ type declaration
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Hash)]
 pub enum CppTypeDeclarationKind {
-    Enum,
+    Enum {
+        /// Integer type used to represent values of the enum
+        underlying_type: CppType,
+    },
     Class,
+    Union,
+}
+
+/// Deserializes `CppTypeDeclarationKind`, also accepting the unit `Enum` variant
+/// stored in databases created before underlying types of enums were recorded.
+/// Such enums are assumed to have the `int` underlying type.
+fn deserialize_type_declaration_kind<'de, D>(
+    deserializer: D,
+) -> std::result::Result<CppTypeDeclarationKind, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    enum OldKind {
+        Enum,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyKind {
+        Current(CppTypeDeclarationKind),
+        Old(OldKind),
+    }
+
+    Ok(match AnyKind::deserialize(deserializer)? {
+        AnyKind::Current(kind) => kind,
+        AnyKind::Old(OldKind::Enum) => CppTypeDeclarationKind::Enum {
+            underlying_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        },
+    })
+}
+
+/// Information about deprecation of a C++ item
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Hash)]
+pub struct CppDeprecation {
+    /// Message specified in the deprecation attribute, if any
+    pub message: Option<String>,
 }
 
 /// Information about a C++ type declaration
@@ -378,7 +517,17 @@ pub enum CppTypeDeclarationKind {
 pub struct CppTypeDeclaration {
     /// Identifier, including namespaces and nested classes
     pub path: CppPath,
+    #[serde(deserialize_with = "deserialize_type_declaration_kind")]
     pub kind: CppTypeDeclarationKind,
+    /// Set if the type is marked as deprecated
+    #[serde(default)]
+    pub deprecation: Option<CppDeprecation>,
+    /// Whether the type is unavailable and can't be used
+    #[serde(default)]
+    pub is_deleted: bool,
+    /// Whether the class is marked as `final`
+    #[serde(default)]
+    pub is_final: bool,
 }
 
 impl CppTypeDeclaration {
@@ -388,17 +537,24 @@ impl CppTypeDeclaration {
 }
 
 impl CppTypeDeclarationKind {
-    /// Checks if the type is a class type.
+    /// Checks if the type is a class type. Unions are also considered class types.
     pub fn is_class(&self) -> bool {
         match self {
-            CppTypeDeclarationKind::Class { .. } => true,
+            CppTypeDeclarationKind::Class { .. } | CppTypeDeclarationKind::Union => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_union(&self) -> bool {
+        match self {
+            CppTypeDeclarationKind::Union => true,
             _ => false,
         }
     }
 
     pub fn is_enum(&self) -> bool {
         match self {
-            CppTypeDeclarationKind::Enum => true,
+            CppTypeDeclarationKind::Enum { .. } => true,
             _ => false,
         }
     }
@@ -409,15 +565,33 @@ pub struct CppNamespace {
     pub path: CppPath,
 }
 
+/// Information about a C++ typedef or a type alias declared with `using`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CppTypeAlias {
+    /// Identifier, including namespaces and nested classes
+    pub path: CppPath,
+    /// Type the alias refers to
+    pub target_type: CppType,
+}
+
+impl CppTypeAlias {
+    pub fn is_same(&self, other: &CppTypeAlias) -> bool {
+        self.path == other.path
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum CppItem {
     Namespace(CppNamespace),
     Type(CppTypeDeclaration),
+    TypeAlias(CppTypeAlias),
     EnumValue(CppEnumValue),
     Function(CppFunction),
     ClassField(CppClassField),
     ClassBase(CppBaseSpecifier),
+    Variable(CppVariable),
+    MacroConstant(CppMacroConstant),
 }
 
 impl CppItem {
@@ -439,6 +613,13 @@ impl CppItem {
                     false
                 }
             }
+            TypeAlias(v) => {
+                if let TypeAlias(v2) = &other {
+                    v.is_same(v2)
+                } else {
+                    false
+                }
+            }
             EnumValue(v) => {
                 if let EnumValue(v2) = &other {
                     v.is_same(v2)
@@ -467,6 +648,20 @@ impl CppItem {
                     false
                 }
             }
+            Variable(v) => {
+                if let Variable(v2) = &other {
+                    v.is_same(v2)
+                } else {
+                    false
+                }
+            }
+            MacroConstant(v) => {
+                if let MacroConstant(v2) = &other {
+                    v.path == v2.path
+                } else {
+                    false
+                }
+            }
         }
     }
 
@@ -474,22 +669,37 @@ impl CppItem {
         let path = match self {
             CppItem::Namespace(data) => &data.path,
             CppItem::Type(data) => &data.path,
+            CppItem::TypeAlias(data) => &data.path,
             CppItem::EnumValue(data) => &data.path,
             CppItem::Function(data) => &data.path,
             CppItem::ClassField(data) => &data.path,
+            CppItem::Variable(data) => &data.path,
+            CppItem::MacroConstant(data) => &data.path,
             CppItem::ClassBase(_) => return None,
         };
         Some(path)
     }
 
+    /// Returns true if the item is a protected method or field of a class.
+    pub fn is_protected_member(&self) -> bool {
+        match self {
+            CppItem::Function(function) => function.is_protected(),
+            CppItem::ClassField(field) => field.visibility == CppVisibility::Protected,
+            _ => false,
+        }
+    }
+
     pub fn all_involved_types(&self) -> Vec<CppType> {
         match self {
             CppItem::Type(t) => match t.kind {
-                CppTypeDeclarationKind::Enum => vec![CppType::Enum {
+                CppTypeDeclarationKind::Enum { .. } => vec![CppType::Enum {
                     path: t.path.clone(),
                 }],
-                CppTypeDeclarationKind::Class { .. } => vec![CppType::Class(t.path.clone())],
+                CppTypeDeclarationKind::Class { .. } | CppTypeDeclarationKind::Union => {
+                    vec![CppType::Class(t.path.clone())]
+                }
             },
+            CppItem::TypeAlias(alias) => vec![alias.target_type.clone()],
             CppItem::EnumValue(enum_value) => vec![CppType::Enum {
                 path: enum_value
                     .path
@@ -507,6 +717,8 @@ impl CppItem {
                 CppType::Class(base.base_class_type.clone()),
                 CppType::Class(base.derived_class_type.clone()),
             ],
+            CppItem::Variable(variable) => vec![variable.variable_type.clone()],
+            CppItem::MacroConstant(constant) => vec![constant.value_type.clone()],
         }
     }
 
@@ -531,6 +743,20 @@ impl CppItem {
             None
         }
     }
+    pub fn as_variable_ref(&self) -> Option<&CppVariable> {
+        if let CppItem::Variable(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
+    pub fn as_macro_constant_ref(&self) -> Option<&CppMacroConstant> {
+        if let CppItem::MacroConstant(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
     pub fn as_enum_value_ref(&self) -> Option<&CppEnumValue> {
         if let CppItem::EnumValue(data) = self {
             Some(data)
@@ -552,6 +778,13 @@ impl CppItem {
             None
         }
     }
+    pub fn as_type_alias_ref(&self) -> Option<&CppTypeAlias> {
+        if let CppItem::TypeAlias(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
     pub fn as_type_mut(&mut self) -> Option<&mut CppTypeDeclaration> {
         if let CppItem::Type(data) = self {
             Some(data)
@@ -564,9 +797,12 @@ impl CppItem {
         match self {
             CppItem::Namespace(value) => format!("namespace {}", value.path.to_cpp_pseudo_code()),
             CppItem::Type(value) => format!("type {}", value.path.to_cpp_pseudo_code()),
+            CppItem::TypeAlias(value) => format!("type alias {}", value.path.to_cpp_pseudo_code()),
             CppItem::EnumValue(value) => format!("enum value {}", value.path.to_cpp_pseudo_code()),
             CppItem::Function(value) => value.short_text(),
             CppItem::ClassField(value) => value.short_text(),
+            CppItem::Variable(value) => value.short_text(),
+            CppItem::MacroConstant(value) => format!("macro {}", value.path.to_cpp_pseudo_code()),
             CppItem::ClassBase(_) => format!("{:?}", self),
         }
     }
@@ -579,11 +815,21 @@ impl fmt::Display for CppItem {
                 format!("namespace {}", namespace.path.to_cpp_pseudo_code())
             }
             CppItem::Type(type1) => match type1.kind {
-                CppTypeDeclarationKind::Enum => format!("enum {}", type1.path.to_cpp_pseudo_code()),
+                CppTypeDeclarationKind::Enum { .. } => {
+                    format!("enum {}", type1.path.to_cpp_pseudo_code())
+                }
                 CppTypeDeclarationKind::Class { .. } => {
                     format!("class {}", type1.path.to_cpp_pseudo_code())
                 }
+                CppTypeDeclarationKind::Union => {
+                    format!("union {}", type1.path.to_cpp_pseudo_code())
+                }
             },
+            CppItem::TypeAlias(alias) => format!(
+                "using {} = {}",
+                alias.path.to_cpp_pseudo_code(),
+                alias.target_type.to_cpp_pseudo_code()
+            ),
             CppItem::Function(method) => method.short_text(),
             CppItem::EnumValue(value) => format!(
                 "enum value {} = {}",
@@ -591,6 +837,12 @@ impl fmt::Display for CppItem {
                 value.value
             ),
             CppItem::ClassField(field) => field.short_text(),
+            CppItem::Variable(variable) => variable.short_text(),
+            CppItem::MacroConstant(constant) => format!(
+                "#define {} {:?}",
+                constant.path.to_cpp_pseudo_code(),
+                constant.value
+            ),
             CppItem::ClassBase(class_base) => {
                 let virtual_text = if class_base.is_virtual {
                     "virtual "
@@ -1,11 +1,12 @@
 use crate::cpp_code_generator;
 use crate::cpp_data::CppPath;
-use crate::cpp_function::ReturnValueAllocationPlace;
+use crate::cpp_function::{CppFunction, ReturnValueAllocationPlace};
 use crate::cpp_type::{CppBuiltInNumericType, CppFunctionPointerType, CppType};
 use crate::database::DatabaseClient;
 use itertools::Itertools;
 use ritual_common::errors::{bail, Result};
 use serde_derive::{Deserialize, Serialize};
+use std::iter::once;
 
 /// Variation of a field accessor method
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
@@ -69,6 +70,22 @@ pub enum CppFfiFunctionKind {
         /// Type of the accessor
         accessor_type: CppFieldAccessorType,
     },
+    /// This is a non-existing function returning the default value
+    /// of an argument of the source C++ function.
+    DefaultArgument {
+        /// Index of the argument in the source C++ function
+        index: usize,
+    },
+}
+
+impl CppFfiFunctionKind {
+    pub fn is_default_argument(&self) -> bool {
+        if let CppFfiFunctionKind::DefaultArgument { .. } = self {
+            true
+        } else {
+            false
+        }
+    }
 }
 
 /// Relation between original C++ method's argument value
@@ -83,11 +100,29 @@ pub enum CppToFfiTypeConversion {
     /// C++ argument is a reference (like QPoint&)
     /// and FFI argument is a pointer (like QPoint*)
     ReferenceToPointer,
+    /// C++ argument is an rvalue reference (like QPoint&&)
+    /// and FFI argument is a pointer (like QPoint*).
+    /// The value is moved from the pointer when calling the C++ function.
+    RValueReferenceToPointer,
+    /// C++ argument is a fixed-size array (like float[4])
+    /// and FFI argument is a pointer to the array.
+    /// Array return values are copied to a buffer provided by the caller.
+    ArrayToPointer,
     /// C++ argument is QFlags<T>
     /// and FFI argument is uint
     QFlagsToInt,
     /// Implicit conversion is used.
     ImplicitCast { ffi_type: CppType },
+    /// C++ argument is `std::function<R(Args...)>` (or a const reference to it)
+    /// and FFI argument is a callback `R (*)(void*, Args...)`.
+    /// The FFI function also receives the callback's deleter and data
+    /// in the following arguments.
+    FunctionToCallback {
+        /// Arguments of the callback (without the data argument)
+        arguments: Vec<CppFfiType>,
+        /// Return type of the callback
+        return_type: Box<CppFfiType>,
+    },
 }
 
 /// Information that indicates how an FFI function argument
@@ -104,6 +139,17 @@ pub enum CppFfiArgumentMeaning {
     /// This argument receives pointer to the buffer where
     /// the return value should be transferred to using placement new.
     ReturnValue,
+    /// This argument receives the message of a C++ exception
+    /// thrown by the original C++ method.
+    ExceptionMessage,
+    /// This argument receives the deleter of the callback data
+    /// for the original C++ method's `std::function` argument
+    /// with the associated index.
+    CallbackDeleter(usize),
+    /// This argument receives the data pointer passed to the callback
+    /// for the original C++ method's `std::function` argument
+    /// with the associated index.
+    CallbackData(usize),
 }
 
 impl CppFfiArgumentMeaning {
@@ -162,6 +208,12 @@ pub struct CppFfiFunction {
     pub path: CppPath,
 
     pub kind: CppFfiFunctionKind,
+
+    /// Whether the argument list is terminated with "...". Variadic arguments
+    /// can't be forwarded, so such a function is not wrapped and
+    /// `path` is the name of the original C function.
+    #[serde(default)]
+    pub allows_variadic_arguments: bool,
 }
 
 impl CppFfiFunction {
@@ -178,6 +230,14 @@ impl CppFfiFunction {
         })
     }
 
+    /// Returns true if this function catches C++ exceptions
+    /// and reports them through the exception message argument.
+    pub fn catches_exceptions(&self) -> bool {
+        self.arguments
+            .iter()
+            .any(|arg| arg.meaning == CppFfiArgumentMeaning::ExceptionMessage)
+    }
+
     pub fn has_same_kind(&self, other: &Self) -> bool {
         match &self.kind {
             CppFfiFunctionKind::Function { .. } => {
@@ -198,6 +258,13 @@ impl CppFfiFunction {
                     false
                 }
             }
+            CppFfiFunctionKind::DefaultArgument { index } => {
+                if let CppFfiFunctionKind::DefaultArgument { index: other_index } = &other.kind {
+                    index == other_index
+                } else {
+                    false
+                }
+            }
         }
     }
 }
@@ -236,6 +303,20 @@ impl CppFfiType {
                     conversion,
                 })
             }
+            CppToFfiTypeConversion::RValueReferenceToPointer => {
+                // the pointer is always mutable because the value is moved from it
+                let target = original_type.pointer_like_to_target()?;
+                Ok(CppFfiType {
+                    ffi_type: CppType::new_pointer(false, target.clone()),
+                    original_type,
+                    conversion,
+                })
+            }
+            CppToFfiTypeConversion::ArrayToPointer => Ok(CppFfiType {
+                ffi_type: CppType::new_pointer(false, original_type.clone()),
+                original_type,
+                conversion,
+            }),
             CppToFfiTypeConversion::QFlagsToInt => Ok(CppFfiType {
                 ffi_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
                 original_type,
@@ -246,6 +327,24 @@ impl CppFfiType {
                 original_type,
                 conversion,
             }),
+            CppToFfiTypeConversion::FunctionToCallback {
+                arguments,
+                return_type,
+            } => {
+                let void_ptr = CppType::new_pointer(false, CppType::Void);
+                let function_type = CppFunctionPointerType {
+                    return_type: Box::new(return_type.ffi_type().clone()),
+                    arguments: once(void_ptr)
+                        .chain(arguments.iter().map(|t| t.ffi_type().clone()))
+                        .collect(),
+                    allows_variadic_arguments: false,
+                };
+                Ok(CppFfiType {
+                    ffi_type: CppType::FunctionPointer(function_type),
+                    original_type,
+                    conversion,
+                })
+            }
         }
     }
 
@@ -293,12 +392,69 @@ pub struct QtSlotWrapper {
     pub function_type: CppFunctionPointerType,
 }
 
+/// Information about a virtual function that can be overridden
+/// in a generated subclass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CppVirtualFunctionOverride {
+    /// Original virtual function (the most derived declaration)
+    pub function: CppFunction,
+    /// FFI types of the arguments passed to the callback
+    /// (not including the `void*` data argument)
+    pub arguments: Vec<CppFfiType>,
+    /// FFI type of the value returned by the callback
+    pub return_type: CppFfiType,
+    /// The function pointer type accepted by the setter
+    pub function_type: CppFunctionPointerType,
+    /// Name of the function that is unique among the virtual functions of the subclass
+    /// (overloads have different names). It's used in names of the generated methods.
+    pub name: String,
+}
+
+impl CppVirtualFunctionOverride {
+    /// Name of the method of the subclass that sets the callback
+    pub fn setter_name(&self) -> String {
+        format!("override_{}", self.name)
+    }
+
+    /// Name of the method of the subclass that calls the base class implementation
+    pub fn base_function_name(&self) -> String {
+        format!("base_{}", self.name)
+    }
+}
+
+/// Information about a generated subclass that allows to override
+/// virtual functions of a C++ class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CppVirtualSubclass {
+    /// Class that is being subclassed
+    pub base_class_path: CppPath,
+    /// Generated name of the subclass
+    pub class_path: CppPath,
+    /// Constructors of the base class available in the subclass
+    pub constructors: Vec<CppFunction>,
+    /// Virtual functions that can be overridden
+    pub virtual_functions: Vec<CppVirtualFunctionOverride>,
+}
+
+impl CppVirtualSubclass {
+    /// Returns the virtual function whose callback is set by
+    /// the method `setter` of the subclass.
+    pub fn find_override(&self, setter: &CppFunction) -> Option<&CppVirtualFunctionOverride> {
+        let callback_type = &setter.arguments.get(0)?.argument_type;
+        self.virtual_functions.iter().find(|f| {
+            f.setter_name() == setter.path.last().name
+                && callback_type == &CppType::FunctionPointer(f.function_type.clone())
+        })
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CppFfiItem {
     Function(CppFfiFunction),
     QtSlotWrapper(QtSlotWrapper),
     QtSignalWrapper(QtSignalWrapper),
+    VirtualSubclass(CppVirtualSubclass),
 }
 
 impl CppFfiItem {
@@ -334,6 +490,14 @@ impl CppFfiItem {
         }
     }
 
+    pub fn as_virtual_subclass_ref(&self) -> Option<&CppVirtualSubclass> {
+        if let CppFfiItem::VirtualSubclass(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
+
     pub fn is_slot_wrapper(&self) -> bool {
         if let CppFfiItem::QtSlotWrapper(_) = self {
             true
@@ -350,6 +514,14 @@ impl CppFfiItem {
         }
     }
 
+    pub fn is_virtual_subclass(&self) -> bool {
+        if let CppFfiItem::VirtualSubclass(_) = self {
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn short_text(&self) -> String {
         match self {
             CppFfiItem::Function(function) => function.path.to_cpp_pseudo_code(),
@@ -369,6 +541,10 @@ impl CppFfiItem {
                     .map(CppType::to_cpp_pseudo_code)
                     .join(", ")
             ),
+            CppFfiItem::VirtualSubclass(subclass) => format!(
+                "virtual subclass of {}",
+                subclass.base_class_path.to_cpp_pseudo_code()
+            ),
         }
     }
 
@@ -395,6 +571,13 @@ impl CppFfiItem {
                     false
                 }
             }
+            CppFfiItem::VirtualSubclass(subclass) => {
+                if let CppFfiItem::VirtualSubclass(other_subclass) = other {
+                    subclass.base_class_path == other_subclass.base_class_path
+                } else {
+                    false
+                }
+            }
         }
     }
 
@@ -403,13 +586,16 @@ impl CppFfiItem {
             CppFfiItem::Function(f) => &f.path,
             CppFfiItem::QtSlotWrapper(s) => &s.class_path,
             CppFfiItem::QtSignalWrapper(s) => &s.class_path,
+            CppFfiItem::VirtualSubclass(s) => &s.class_path,
         }
     }
 
     pub fn is_source_item(&self) -> bool {
         match self {
             CppFfiItem::Function(_) => false,
-            CppFfiItem::QtSlotWrapper(_) | CppFfiItem::QtSignalWrapper(_) => true,
+            CppFfiItem::QtSlotWrapper(_)
+            | CppFfiItem::QtSignalWrapper(_)
+            | CppFfiItem::VirtualSubclass(_) => true,
         }
     }
 
@@ -422,6 +608,9 @@ impl CppFfiItem {
             CppFfiItem::QtSignalWrapper(signal_wrapper) => {
                 cpp_code_generator::qt_signal_wrapper(db, signal_wrapper)
             }
+            CppFfiItem::VirtualSubclass(subclass) => {
+                cpp_code_generator::virtual_subclass(db, subclass)
+            }
         }
     }
 }
@@ -1,7 +1,9 @@
+use crate::config::Config;
 use crate::cpp_data::CppClassField;
 use crate::cpp_data::CppItem;
 use crate::cpp_data::CppPath;
 use crate::cpp_data::CppPathItem;
+use crate::cpp_data::CppVariable;
 use crate::cpp_data::CppVisibility;
 use crate::cpp_ffi_data::CppFfiType;
 use crate::cpp_ffi_data::{CppFfiArgumentMeaning, CppToFfiTypeConversion};
@@ -9,15 +11,17 @@ use crate::cpp_ffi_data::{CppFfiFunction, CppFfiFunctionKind, CppFieldAccessorTy
 use crate::cpp_ffi_data::{CppFfiFunctionArgument, CppFfiItem};
 use crate::cpp_function::ReturnValueAllocationPlace;
 use crate::cpp_function::{CppFunction, CppFunctionArgument, CppFunctionKind};
-use crate::cpp_type::CppPointerLikeTypeKind;
 use crate::cpp_type::CppType;
 use crate::cpp_type::CppTypeRole;
 use crate::cpp_type::{is_qflags, CppFunctionPointerType};
+use crate::cpp_type::{CppBuiltInNumericType, CppPointerLikeTypeKind};
 use crate::database::DbItem;
 use crate::processor::ProcessorData;
+use crate::type_allocation_places::is_movable;
 use itertools::Itertools;
 use log::{debug, trace};
 use ritual_common::errors::{bail, Result, ResultExt};
+use ritual_common::utils::MapIfOk;
 use std::collections::HashSet;
 
 /// Converts this C++ type to its adaptation for FFI interface,
@@ -29,15 +33,15 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
         if original_type.is_or_contains_template_parameter() {
             bail!("template parameters cannot be expressed in FFI");
         }
+        if original_type.is_integral_constant() {
+            bail!("template argument values cannot be expressed in FFI");
+        }
         let conversion = match original_type {
             CppType::FunctionPointer(CppFunctionPointerType {
                 return_type,
                 arguments,
-                allows_variadic_arguments,
+                ..
             }) => {
-                if *allows_variadic_arguments {
-                    bail!("function pointers with variadic arguments are not supported");
-                }
                 let mut all_types = arguments.iter().collect_vec();
                 all_types.push(return_type.as_ref());
                 for arg in all_types {
@@ -63,7 +67,11 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
                 CppToFfiTypeConversion::NoChange
             }
             CppType::Class(path) => {
-                if is_qflags(&path) {
+                if let (Some(function_type), CppTypeRole::NotReturnType) =
+                    (original_type.as_std_function(), role)
+                {
+                    function_to_callback(function_type)?
+                } else if is_qflags(&path) {
                     CppToFfiTypeConversion::QFlagsToInt
                 } else {
                     CppToFfiTypeConversion::ValueToPointer {
@@ -71,6 +79,7 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
                     }
                 }
             }
+            CppType::Array { .. } => CppToFfiTypeConversion::ArrayToPointer,
             CppType::PointerLike {
                 kind,
                 is_const,
@@ -84,11 +93,25 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
                                 // TODO: use a separate conversion type (QFlagsConstRefToUInt)?
                                 CppToFfiTypeConversion::QFlagsToInt
                             }
-                            _ => CppToFfiTypeConversion::ReferenceToPointer,
+                            _ => match target.as_std_function() {
+                                Some(function_type)
+                                    if *is_const && role == CppTypeRole::NotReturnType =>
+                                {
+                                    function_to_callback(function_type)?
+                                }
+                                _ => CppToFfiTypeConversion::ReferenceToPointer,
+                            },
                         }
                     }
                     CppPointerLikeTypeKind::RValueReference => {
-                        bail!("rvalue references are not supported");
+                        if role == CppTypeRole::ReturnType {
+                            bail!("rvalue references are not supported as return types");
+                        }
+                        if let Some(function_type) = target.as_std_function() {
+                            function_to_callback(function_type)?
+                        } else {
+                            CppToFfiTypeConversion::RValueReferenceToPointer
+                        }
                     }
                 }
             }
@@ -99,6 +122,53 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
     Ok(inner().with_context(|_| format!("Can't express type to FFI: {:?}", original_type))?)
 }
 
+/// Converts argument types and return type of a callback implemented in Rust
+/// and called from C++ to their FFI adaptations.
+pub fn callback_ffi_types(
+    arguments: &[CppType],
+    return_type: &CppType,
+) -> Result<(Vec<CppFfiType>, CppFfiType)> {
+    let arguments = arguments.iter().map_if_ok(|arg| {
+        let t = ffi_type(arg, CppTypeRole::NotReturnType)?;
+        match t.conversion() {
+            CppToFfiTypeConversion::NoChange
+            | CppToFfiTypeConversion::ImplicitCast { .. }
+            | CppToFfiTypeConversion::ReferenceToPointer
+            | CppToFfiTypeConversion::QFlagsToInt => Ok(t),
+            // the callback would receive a pointer to a temporary object
+            _ => bail!("unsupported argument type: {:?}", arg),
+        }
+    })?;
+
+    let ffi_return_type = ffi_type(return_type, CppTypeRole::ReturnType)?;
+    match ffi_return_type.conversion() {
+        CppToFfiTypeConversion::NoChange
+        | CppToFfiTypeConversion::ImplicitCast { .. }
+        | CppToFfiTypeConversion::ReferenceToPointer
+        | CppToFfiTypeConversion::ValueToPointer { .. } => {}
+        CppToFfiTypeConversion::QFlagsToInt => {
+            if let CppType::PointerLike { .. } = return_type {
+                bail!("unsupported return type: {:?}", return_type);
+            }
+        }
+        _ => bail!("unsupported return type: {:?}", return_type),
+    }
+    Ok((arguments, ffi_return_type))
+}
+
+/// Returns the conversion of a `std::function` argument with the specified function type.
+fn function_to_callback(function_type: &CppFunctionPointerType) -> Result<CppToFfiTypeConversion> {
+    if function_type.allows_variadic_arguments {
+        bail!("variadic std::function is not supported");
+    }
+    let (arguments, return_type) =
+        callback_ffi_types(&function_type.arguments, &function_type.return_type)?;
+    Ok(CppToFfiTypeConversion::FunctionToCallback {
+        arguments,
+        return_type: Box::new(return_type),
+    })
+}
+
 pub struct FfiNameProvider {
     names: HashSet<String>,
     prefix: String,
@@ -145,8 +215,20 @@ impl FfiNameProvider {
 
 /// Runs the FFI generator
 pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
-    // movable types are not supported for now
-    let movable_types = Vec::new();
+    let all_class_paths = data
+        .db
+        .all_cpp_items()
+        .filter_map(|item| item.item.as_type_ref())
+        .filter(|type1| type1.kind.is_class())
+        .map(|type1| type1.path.clone())
+        .collect_vec();
+
+    let mut movable_types = Vec::new();
+    for path in all_class_paths {
+        if is_movable(data, &path)? {
+            movable_types.push(path);
+        }
+    }
 
     let mut name_provider = FfiNameProvider::new(data);
 
@@ -157,21 +239,53 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
 
         let source_ffi_item = data.db.source_ffi_item(&cpp_item_id)?;
 
-        if let Err(err) = check_preconditions(&item.item, source_ffi_item) {
+        if let Err(err) = check_preconditions(
+            &item.item,
+            source_ffi_item,
+            data.config.expose_protected_members(),
+        ) {
             trace!("skipping {}: {}", item.item, err);
             continue;
         }
         let result = match &item.item {
-            CppItem::Function(method) => {
-                generate_ffi_methods_for_method(method, &movable_types, &mut name_provider)
-                    .map(|v| v.into_iter().collect_vec())
-            }
+            CppItem::Function(method) => should_catch_exceptions(data.config, method)
+                .and_then(|catch_exceptions| {
+                    generate_ffi_methods_for_method(
+                        method,
+                        &movable_types,
+                        catch_exceptions,
+                        &mut name_provider,
+                    )
+                })
+                .and_then(|mut methods| {
+                    if should_generate_options_struct(data, &item, method)? {
+                        match generate_default_argument_getters(
+                            method,
+                            &movable_types,
+                            &mut name_provider,
+                        ) {
+                            Ok(getters) => methods.extend(getters),
+                            Err(err) => debug!(
+                                "failed to generate default argument getters for {}: {}",
+                                method.short_text(),
+                                err
+                            ),
+                        }
+                    }
+                    Ok(methods)
+                }),
             CppItem::ClassField(field) => {
                 generate_field_accessors(field, &movable_types, &mut name_provider)
                     .map(|v| v.into_iter().collect_vec())
             }
+            CppItem::Variable(variable) => {
+                generate_variable_accessors(variable, &movable_types, &mut name_provider)
+                    .map(|v| v.into_iter().collect_vec())
+            }
             CppItem::ClassBase(_)
             | CppItem::Type(_)
+            | CppItem::TypeAlias(_)
+            | CppItem::MacroConstant(_)
             | CppItem::EnumValue(_)
             | CppItem::Namespace(_) => {
                 // no FFI methods for these items
@@ -197,20 +311,115 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     Ok(())
 }
 
-fn generate_ffi_methods_for_method(
+/// Returns true if C++ exceptions thrown by `function` should be caught
+/// in its FFI wrapper.
+fn should_catch_exceptions(config: &Config, function: &CppFunction) -> Result<bool> {
+    // destructors and casts don't throw, and variadic functions are not wrapped
+    if function.is_destructor() || function.cast.is_some() || function.allows_variadic_arguments {
+        return Ok(false);
+    }
+    if let Some(hook) = config.catch_exceptions_hook() {
+        hook(function)
+    } else {
+        Ok(config.catch_exceptions())
+    }
+}
+
+/// Returns true if an options struct should be generated for `function`,
+/// so FFI getters for its default argument values are needed.
+fn should_generate_options_struct(
+    data: &ProcessorData<'_>,
+    item: &DbItem<&CppItem>,
+    function: &CppFunction,
+) -> Result<bool> {
+    let min_default_arguments = match data.config.options_struct_min_default_arguments() {
+        Some(value) => value.max(1),
+        None => return Ok(false),
+    };
+    if function.is_destructor()
+        || function.is_operator()
+        || function.allows_variadic_arguments
+        || function.cast.is_some()
+    {
+        return Ok(false);
+    }
+    let default_arguments = function
+        .arguments
+        .iter()
+        .filter(|arg| arg.has_default_value)
+        .collect_vec();
+    if default_arguments.len() < min_default_arguments
+        || default_arguments
+            .iter()
+            .any(|arg| arg.default_value.is_none())
+    {
+        return Ok(false);
+    }
+    // variants with omitted arguments are covered by the original function
+    if let Some(source_id) = &item.source_id {
+        if let Some(source_function) = data.db.cpp_item(source_id)?.item.as_function_ref() {
+            if source_function.path == function.path
+                && source_function.arguments.len() > function.arguments.len()
+            {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Generates FFI functions returning default values of arguments of `function`.
+fn generate_default_argument_getters(
+    function: &CppFunction,
+    movable_types: &[CppPath],
+    name_provider: &mut FfiNameProvider,
+) -> Result<Vec<CppFfiItem>> {
+    let mut getters = Vec::new();
+    for (index, arg) in function.arguments.iter().enumerate() {
+        if !arg.has_default_value {
+            continue;
+        }
+        let getter = to_ffi_method(
+            NewFfiFunctionKind::DefaultArgument {
+                cpp_function: function.clone(),
+                index,
+            },
+            movable_types,
+            name_provider,
+        )?;
+        getters.push(CppFfiItem::Function(getter));
+    }
+    Ok(getters)
+}
+
+pub fn generate_ffi_methods_for_method(
     method: &CppFunction,
     movable_types: &[CppPath],
+    catch_exceptions: bool,
     name_provider: &mut FfiNameProvider,
 ) -> Result<Vec<CppFfiItem>> {
-    let mut methods = Vec::new();
-    methods.push(CppFfiItem::Function(to_ffi_method(
+    let mut function = to_ffi_method(
         NewFfiFunctionKind::Function {
             cpp_function: method.clone(),
         },
         movable_types,
         name_provider,
-    )?));
+    )?;
+    if catch_exceptions {
+        let message_type =
+            CppType::new_pointer(true, CppType::BuiltInNumeric(CppBuiltInNumericType::Char));
+        function.arguments.push(CppFfiFunctionArgument {
+            name: "exception".to_string(),
+            argument_type: CppFfiType::new(
+                CppType::new_pointer(false, message_type),
+                CppToFfiTypeConversion::NoChange,
+            )?,
+            meaning: CppFfiArgumentMeaning::ExceptionMessage,
+        });
+    }
 
+    let mut methods = Vec::new();
+    methods.push(CppFfiItem::Function(function));
     Ok(methods)
 }
 
@@ -220,7 +429,16 @@ pub enum NewFfiFunctionKind {
     },
     FieldAccessor {
         accessor_type: CppFieldAccessorType,
-        field: CppClassField,
+        /// Path of the class field or the variable
+        path: CppPath,
+        field_type: CppType,
+        /// True for static class fields and namespace-scope variables
+        is_static: bool,
+    },
+    DefaultArgument {
+        cpp_function: CppFunction,
+        /// Index of the argument in `cpp_function`
+        index: usize,
     },
 }
 
@@ -237,10 +455,11 @@ pub fn to_ffi_method(
     let ascii_caption = match &kind {
         NewFfiFunctionKind::Function { cpp_function, .. } => cpp_function.path.ascii_caption(),
         NewFfiFunctionKind::FieldAccessor {
-            field,
+            path,
             accessor_type,
+            ..
         } => {
-            let field_caption = field.path.ascii_caption();
+            let field_caption = path.ascii_caption();
             match *accessor_type {
                 CppFieldAccessorType::CopyGetter | CppFieldAccessorType::ConstRefGetter => {
                     field_caption
@@ -249,6 +468,14 @@ pub fn to_ffi_method(
                 CppFieldAccessorType::Setter => format!("set_{}", field_caption),
             }
         }
+        NewFfiFunctionKind::DefaultArgument {
+            cpp_function,
+            index,
+        } => format!(
+            "{}_default_{}",
+            cpp_function.path.ascii_caption(),
+            cpp_function.arguments[*index].name
+        ),
     };
 
     let mut r = CppFfiFunction {
@@ -261,7 +488,11 @@ pub fn to_ffi_method(
             NewFfiFunctionKind::FieldAccessor { accessor_type, .. } => {
                 CppFfiFunctionKind::FieldAccessor { accessor_type }
             }
+            NewFfiFunctionKind::DefaultArgument { index, .. } => {
+                CppFfiFunctionKind::DefaultArgument { index }
+            }
         },
+        allows_variadic_arguments: false,
     };
 
     let this_arg_type = match &kind {
@@ -273,13 +504,15 @@ pub fn to_ffi_method(
             _ => None,
         },
         NewFfiFunctionKind::FieldAccessor {
-            field,
+            path,
             accessor_type,
+            is_static,
+            ..
         } => {
-            if field.is_static {
+            if *is_static {
                 None
             } else {
-                let class_type = CppType::Class(field.path.parent()?);
+                let class_type = CppType::Class(path.parent()?);
                 let is_const = match *accessor_type {
                     CppFieldAccessorType::CopyGetter | CppFieldAccessorType::ConstRefGetter => true,
                     CppFieldAccessorType::MutRefGetter | CppFieldAccessorType::Setter => false,
@@ -287,6 +520,7 @@ pub fn to_ffi_method(
                 Some(CppType::new_pointer(is_const, class_type))
             }
         }
+        NewFfiFunctionKind::DefaultArgument { .. } => None,
     };
 
     if let Some(this_arg_type) = this_arg_type {
@@ -300,7 +534,13 @@ pub fn to_ffi_method(
     let normal_args = match &kind {
         NewFfiFunctionKind::Function { cpp_function, .. } => {
             if cpp_function.allows_variadic_arguments {
-                bail!("Variable arguments are not supported");
+                // a wrapper can't forward variadic arguments,
+                // so the original function is called directly
+                if cpp_function.member.is_some() || !cpp_function.has_c_linkage {
+                    bail!("Variable arguments are only supported for functions with C linkage");
+                }
+                r.path = CppPath::from_item(cpp_function.path.last().clone());
+                r.allows_variadic_arguments = true;
             }
 
             if cpp_function.is_destructor() {
@@ -316,24 +556,57 @@ pub fn to_ffi_method(
             cpp_function.arguments.clone()
         }
         NewFfiFunctionKind::FieldAccessor {
-            field,
+            field_type,
             accessor_type,
+            ..
         } => {
             if accessor_type == &CppFieldAccessorType::Setter {
                 let arg = CppFunctionArgument {
                     name: "value".to_string(),
-                    argument_type: field.field_type.clone(),
+                    argument_type: field_type.clone(),
                     has_default_value: false,
+                    default_value: None,
+                    type_alias: None,
                 };
                 vec![arg]
             } else {
                 Vec::new()
             }
         }
+        NewFfiFunctionKind::DefaultArgument { .. } => Vec::new(),
     };
 
     for (index, arg) in normal_args.iter().enumerate() {
         let c_type = ffi_type(&arg.argument_type, CppTypeRole::NotReturnType)?;
+        if let CppToFfiTypeConversion::FunctionToCallback { .. } = c_type.conversion() {
+            // the Rust generator expects the callback arguments at the end
+            if index + 1 != normal_args.len() {
+                bail!("std::function is only supported as the last argument");
+            }
+            // the names are prefixed to avoid conflicts with other arguments
+            r.arguments.push(CppFfiFunctionArgument {
+                name: "ritual_callback".to_string(),
+                argument_type: c_type,
+                meaning: CppFfiArgumentMeaning::Argument(index),
+            });
+            let void_ptr = CppType::new_pointer(false, CppType::Void);
+            let deleter_type = CppType::FunctionPointer(CppFunctionPointerType {
+                return_type: Box::new(CppType::Void),
+                arguments: vec![void_ptr.clone()],
+                allows_variadic_arguments: false,
+            });
+            r.arguments.push(CppFfiFunctionArgument {
+                name: "ritual_callback_deleter".to_string(),
+                argument_type: CppFfiType::new(deleter_type, CppToFfiTypeConversion::NoChange)?,
+                meaning: CppFfiArgumentMeaning::CallbackDeleter(index),
+            });
+            r.arguments.push(CppFfiFunctionArgument {
+                name: "ritual_callback_data".to_string(),
+                argument_type: CppFfiType::new(void_ptr, CppToFfiTypeConversion::NoChange)?,
+                meaning: CppFfiArgumentMeaning::CallbackData(index),
+            });
+            continue;
+        }
         r.arguments.push(CppFfiFunctionArgument {
             name: arg.name.clone(),
             argument_type: c_type,
@@ -349,18 +622,38 @@ pub fn to_ffi_method(
             _ => cpp_function.return_type.clone(),
         },
         NewFfiFunctionKind::FieldAccessor {
-            field,
+            field_type,
             accessor_type,
+            ..
         } => match *accessor_type {
-            CppFieldAccessorType::CopyGetter => field.field_type.clone(),
+            CppFieldAccessorType::CopyGetter => field_type.clone(),
             CppFieldAccessorType::ConstRefGetter => {
-                CppType::new_reference(true, field.field_type.clone())
-            }
-            CppFieldAccessorType::MutRefGetter => {
-                CppType::new_reference(false, field.field_type.clone())
+                CppType::new_reference(true, field_type.clone())
             }
+            CppFieldAccessorType::MutRefGetter => CppType::new_reference(false, field_type.clone()),
             CppFieldAccessorType::Setter => CppType::Void,
         },
+        NewFfiFunctionKind::DefaultArgument {
+            cpp_function,
+            index,
+        } => match &cpp_function.arguments[*index].argument_type {
+            // the getter returns a value that can be bound to the reference
+            CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::Reference,
+                is_const: true,
+                target,
+            }
+            | CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::RValueReference,
+                target,
+                ..
+            } => (**target).clone(),
+            CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::Reference,
+                ..
+            } => bail!("default values of non-const references are not supported"),
+            other => other.clone(),
+        },
     };
     let real_return_type_ffi = ffi_type(&real_return_type, CppTypeRole::ReturnType)?;
     match &real_return_type {
@@ -378,54 +671,134 @@ pub fn to_ffi_method(
                 r.allocation_place = ReturnValueAllocationPlace::Heap;
             }
         }
+        // arrays can't be returned by value, so they are copied to the output buffer
+        CppType::Array { .. } => {
+            r.arguments.push(CppFfiFunctionArgument {
+                name: "output".to_string(),
+                argument_type: real_return_type_ffi,
+                meaning: CppFfiArgumentMeaning::ReturnValue,
+            });
+            r.allocation_place = ReturnValueAllocationPlace::Stack;
+        }
         _ => {
             r.return_type = real_return_type_ffi;
         }
     }
 
+    if r.allows_variadic_arguments {
+        let needs_conversion = r.return_type.conversion() != &CppToFfiTypeConversion::NoChange
+            || r.arguments.iter().any(|arg| {
+                arg.meaning == CppFfiArgumentMeaning::ReturnValue
+                    || arg.argument_type.conversion() != &CppToFfiTypeConversion::NoChange
+            });
+        if needs_conversion {
+            bail!("variadic function can't be called directly because its types need conversion");
+        }
+    }
+
     Ok(r)
 }
 
-/// Adds fictional getter and setter methods for each known public field of each class.
-fn generate_field_accessors(
-    field: &CppClassField,
+/// Generates getters and setters for a class field or a variable.
+fn generate_accessors(
+    path: &CppPath,
+    field_type: &CppType,
+    is_static: bool,
+    is_const: bool,
     movable_types: &[CppPath],
     name_provider: &mut FfiNameProvider,
 ) -> Result<Vec<CppFfiItem>> {
     let mut new_methods = Vec::new();
     let mut create_method = |accessor_type| -> Result<CppFfiItem> {
         let kind = NewFfiFunctionKind::FieldAccessor {
-            field: field.clone(),
             accessor_type,
+            path: path.clone(),
+            field_type: field_type.clone(),
+            is_static,
         };
         let ffi_function = to_ffi_method(kind, movable_types, name_provider)?;
         Ok(CppFfiItem::Function(ffi_function))
     };
 
-    if field.visibility == CppVisibility::Public {
-        // Classes may be non-copyable, so copy getters may not be possible for them,
-        // so we generate reference getters instead.
-        if field.field_type.is_class() {
-            new_methods.push(create_method(CppFieldAccessorType::ConstRefGetter)?);
+    // Classes may be non-copyable, so copy getters may not be possible for them,
+    // so we generate reference getters instead.
+    if field_type.is_class() {
+        new_methods.push(create_method(CppFieldAccessorType::ConstRefGetter)?);
+        if !is_const {
             new_methods.push(create_method(CppFieldAccessorType::MutRefGetter)?);
-        } else {
-            new_methods.push(create_method(CppFieldAccessorType::CopyGetter)?);
         }
+    } else {
+        new_methods.push(create_method(CppFieldAccessorType::CopyGetter)?);
+    }
+    if !is_const {
         new_methods.push(create_method(CppFieldAccessorType::Setter)?);
     }
 
     Ok(new_methods)
 }
 
-fn check_preconditions(item: &CppItem, source_ffi_item: Option<DbItem<&CppFfiItem>>) -> Result<()> {
+/// Adds fictional getter and setter methods for each known public field of each class.
+fn generate_field_accessors(
+    field: &CppClassField,
+    movable_types: &[CppPath],
+    name_provider: &mut FfiNameProvider,
+) -> Result<Vec<CppFfiItem>> {
+    if field.visibility != CppVisibility::Public || field.value.is_some() {
+        // constants are represented as Rust constants
+        return Ok(Vec::new());
+    }
+    generate_accessors(
+        &field.path,
+        &field.field_type,
+        field.is_static,
+        field.is_const,
+        movable_types,
+        name_provider,
+    )
+}
+
+/// Adds getter and setter methods for a namespace-scope variable.
+fn generate_variable_accessors(
+    variable: &CppVariable,
+    movable_types: &[CppPath],
+    name_provider: &mut FfiNameProvider,
+) -> Result<Vec<CppFfiItem>> {
+    if variable.value.is_some() {
+        // constants are represented as Rust constants
+        return Ok(Vec::new());
+    }
+    generate_accessors(
+        &variable.path,
+        &variable.variable_type,
+        true,
+        variable.is_const,
+        movable_types,
+        name_provider,
+    )
+}
+
+fn check_preconditions(
+    item: &CppItem,
+    source_ffi_item: Option<DbItem<&CppFfiItem>>,
+    allow_protected: bool,
+) -> Result<()> {
     match item {
         CppItem::Function(function) => {
+            if function.is_deleted {
+                bail!("function is deleted");
+            }
             if let Some(membership) = &function.member {
                 if membership.visibility == CppVisibility::Private {
                     bail!("function is private");
                 }
                 if membership.visibility == CppVisibility::Protected {
-                    bail!("function is protected");
+                    if !allow_protected {
+                        bail!("function is protected");
+                    }
+                    if function.is_constructor() || function.is_destructor() {
+                        // the accessor subclass can't be used to create or delete objects
+                        bail!("protected constructors and destructors are not supported");
+                    }
                 }
                 if membership.is_signal {
                     if source_ffi_item.map_or(false, |item| item.item.is_signal_wrapper()) {
@@ -448,7 +821,7 @@ fn check_preconditions(item: &CppItem, source_ffi_item: Option<DbItem<&CppFfiIte
             if field.visibility == CppVisibility::Private {
                 bail!("field is private");
             }
-            if field.visibility == CppVisibility::Protected {
+            if field.visibility == CppVisibility::Protected && !allow_protected {
                 bail!("field is protected");
             }
         }
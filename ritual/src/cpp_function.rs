@@ -1,6 +1,6 @@
 //! Types for handling information about C++ methods.
 
-use crate::cpp_data::{CppPath, CppPathItem, CppVisibility};
+use crate::cpp_data::{CppDeprecation, CppPath, CppPathItem, CppVisibility};
 use crate::cpp_ffi_data::CppCast;
 pub use crate::cpp_operator::{CppOperator, CppOperatorInfo};
 use crate::cpp_type::{CppPointerLikeTypeKind, CppType};
@@ -23,6 +23,13 @@ pub struct CppFunctionArgument {
     /// Flag indicating that the argument has default value and
     /// therefore can be omitted when calling the method
     pub has_default_value: bool,
+    /// C++ expression of the default value, if it could be extracted
+    #[serde(default)]
+    pub default_value: Option<String>,
+    /// C++ type alias used to declare the argument's type, if any.
+    /// `argument_type` contains the resolved type.
+    #[serde(default)]
+    pub type_alias: Option<CppPath>,
 }
 
 impl CppFunctionArgument {
@@ -60,6 +67,9 @@ pub struct CppFunctionMemberData {
     pub is_virtual: bool,
     /// True if this is a pure virtual method (requires is_virtual = true)
     pub is_pure_virtual: bool,
+    /// True if this is a virtual method marked as `final`
+    #[serde(default)]
+    pub is_final: bool,
     /// True if this is a const method, i.e. "this" pointer receives by
     /// this method has const type
     pub is_const: bool,
@@ -113,14 +123,31 @@ pub struct CppFunction {
     /// Return type of the method.
     /// Return type is reported as void for constructors and destructors.
     pub return_type: CppType,
+    /// C++ type alias used to declare the return type, if any.
+    /// `return_type` contains the resolved type.
+    #[serde(default)]
+    pub return_type_alias: Option<CppPath>,
     /// List of the method's arguments
     pub arguments: Vec<CppFunctionArgument>,
     /// Whether the argument list is terminated with "..."
     pub allows_variadic_arguments: bool,
+    /// Whether the function is declared in an `extern "C"` block
+    #[serde(default)]
+    pub has_c_linkage: bool,
     pub cast: Option<CppCast>,
     /// C++ code of the method's declaration.
     /// None if the method was not explicitly declared.
     pub declaration_code: Option<String>,
+    /// Set if the function is marked as deprecated
+    #[serde(default)]
+    pub deprecation: Option<CppDeprecation>,
+    /// Whether the function is deleted (`= delete`) or otherwise unavailable
+    #[serde(default)]
+    pub is_deleted: bool,
+    /// Whether the function is declared as a friend inside a class.
+    /// Such a function may only be found by argument-dependent lookup.
+    #[serde(default)]
+    pub is_friend: bool,
 }
 
 /// Chosen type allocation place for the method
@@ -307,7 +334,10 @@ impl CppFunction {
                     arg.argument_type.to_cpp_pseudo_code(),
                     arg.name,
                     if arg.has_default_value {
-                        " = …".to_string()
+                        format!(
+                            " = {}",
+                            arg.default_value.as_ref().map_or("…", |v| v.as_str())
+                        )
                     } else {
                         String::new()
                     }
@@ -371,6 +401,13 @@ impl CppFunction {
         }
     }
 
+    pub fn is_protected(&self) -> bool {
+        match &self.member {
+            Some(info) => info.visibility == CppVisibility::Protected,
+            None => false,
+        }
+    }
+
     pub fn is_signal(&self) -> bool {
         match &self.member {
             Some(info) => info.is_signal,
@@ -32,6 +32,7 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 member: Some(CppFunctionMemberData {
                     is_virtual: false, // the destructor can actually be virtual but we don't care about it here
                     is_pure_virtual: false,
+                    is_final: false,
                     is_const: false,
                     is_static: false,
                     visibility: CppVisibility::Public,
@@ -43,8 +44,13 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 return_type: CppType::Void,
                 arguments: vec![],
                 allows_variadic_arguments: false,
+                has_c_linkage: false,
                 cast: None,
                 declaration_code: None,
+                deprecation: None,
+                is_deleted: false,
+                is_friend: false,
+                return_type_alias: None,
             };
             methods.push(ItemWithSource::new(&type1.id, destructor));
 
@@ -56,6 +62,7 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 member: Some(CppFunctionMemberData {
                     is_virtual: false,
                     is_pure_virtual: false,
+                    is_final: false,
                     is_const: false,
                     is_static: false,
                     visibility: CppVisibility::Public,
@@ -67,7 +74,12 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 return_type: CppType::Void,
                 arguments: vec![],
                 allows_variadic_arguments: false,
+                has_c_linkage: false,
                 declaration_code: None,
+                deprecation: None,
+                is_deleted: false,
+                is_friend: false,
+                return_type_alias: None,
                 cast: None,
             };
             methods.push(ItemWithSource::new(&type1.id, default_constructor));
@@ -76,6 +88,8 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 argument_type: CppType::new_reference(true, CppType::Class(class_path.clone())),
                 name: "other".to_string(),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             };
 
             let copy_constructor = CppFunction {
@@ -86,6 +100,7 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 member: Some(CppFunctionMemberData {
                     is_virtual: false,
                     is_pure_virtual: false,
+                    is_final: false,
                     is_const: false,
                     is_static: false,
                     visibility: CppVisibility::Public,
@@ -97,8 +112,13 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 return_type: CppType::Void,
                 arguments: vec![copy_arg.clone()],
                 allows_variadic_arguments: false,
+                has_c_linkage: false,
                 cast: None,
                 declaration_code: None,
+                deprecation: None,
+                is_deleted: false,
+                is_friend: false,
+                return_type_alias: None,
             };
             methods.push(ItemWithSource::new(&type1.id, copy_constructor));
 
@@ -110,6 +130,7 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 member: Some(CppFunctionMemberData {
                     is_virtual: false,
                     is_pure_virtual: false,
+                    is_final: false,
                     is_const: false,
                     is_static: false,
                     visibility: CppVisibility::Public,
@@ -121,8 +142,13 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 return_type: CppType::new_reference(false, CppType::Class(class_path.clone())),
                 arguments: vec![copy_arg],
                 allows_variadic_arguments: false,
+                has_c_linkage: false,
                 cast: None,
                 declaration_code: None,
+                deprecation: None,
+                is_deleted: false,
+                is_friend: false,
+                return_type_alias: None,
             };
             methods.push(ItemWithSource::new(&type1.id, assignment_operator));
         }
@@ -1,8 +1,9 @@
 use crate::config::Config;
 use crate::cpp_code_generator::{all_include_directives, write_include_directives};
 use crate::cpp_data::{
-    CppBaseSpecifier, CppClassField, CppEnumValue, CppItem, CppNamespace, CppOriginLocation,
-    CppPath, CppPathItem, CppTypeDeclaration, CppTypeDeclarationKind, CppVisibility,
+    CppBaseSpecifier, CppClassField, CppConstantValue, CppDeprecation, CppEnumValue, CppItem,
+    CppMacroConstant, CppNamespace, CppOriginLocation, CppPath, CppPathItem, CppTypeAlias,
+    CppTypeDeclaration, CppTypeDeclarationKind, CppVariable, CppVisibility,
 };
 use crate::cpp_function::{
     CppFunction, CppFunctionArgument, CppFunctionKind, CppFunctionMemberData,
@@ -12,7 +13,7 @@ use crate::cpp_type::{
     CppBuiltInNumericType, CppFunctionPointerType, CppPointerLikeTypeKind, CppSpecificNumericType,
     CppSpecificNumericTypeKind, CppTemplateParameter, CppType,
 };
-use crate::database::ItemId;
+use crate::database::{DocItem, ItemId};
 use crate::processor::ProcessorData;
 use clang::diagnostic::{Diagnostic, Severity};
 use clang::*;
@@ -79,6 +80,55 @@ struct CppParser<'b, 'a> {
     output: CppParserOutput,
 }
 
+/// Returns the documentation comment of `entity` converted to HTML, if any.
+fn doc_comment_html(entity: Entity<'_>) -> Option<String> {
+    let html = entity.get_parsed_comment()?.as_html();
+    if html.trim().is_empty() {
+        None
+    } else {
+        Some(html)
+    }
+}
+
+/// Returns true if `entity` is a free function declared inside a class
+/// as a friend.
+fn is_friend_function(entity: Entity<'_>) -> bool {
+    entity.get_kind() == EntityKind::FunctionDecl
+        && entity.get_lexical_parent().map_or(false, |parent| {
+            [
+                EntityKind::ClassDecl,
+                EntityKind::ClassTemplate,
+                EntityKind::StructDecl,
+                EntityKind::UnionDecl,
+            ]
+            .contains(&parent.get_kind())
+        })
+}
+
+/// Returns path of the type alias used to spell `type1`, if any.
+/// Pointers and references to a type alias are also accepted.
+/// Aliases declared inside templates are ignored.
+fn type_alias_path(type1: Type<'_>) -> Option<CppPath> {
+    match type1.get_kind() {
+        TypeKind::Typedef => {
+            let path = get_path(type1.get_declaration()?).ok()?;
+            if path
+                .items()
+                .iter()
+                .any(|item| item.template_arguments.is_some())
+            {
+                return None;
+            }
+            Some(path)
+        }
+        TypeKind::Elaborated => type_alias_path(type1.get_elaborated_type()?),
+        TypeKind::Pointer | TypeKind::LValueReference | TypeKind::RValueReference => {
+            type_alias_path(type1.get_pointee_type()?)
+        }
+        _ => None,
+    }
+}
+
 /// Print representation of `entity` and its children to the log.
 /// `level` is current level of recursion.
 fn dump_entity(entity: Entity<'_>, level: usize) {
@@ -129,13 +179,23 @@ fn get_template_arguments(entity: Entity<'_>) -> Option<Vec<CppType>> {
     let args = entity
         .get_children()
         .into_iter()
-        .filter(|c| c.get_kind() == EntityKind::TemplateTypeParameter)
+        .filter(|c| {
+            c.get_kind() == EntityKind::TemplateTypeParameter
+                || c.get_kind() == EntityKind::NonTypeTemplateParameter
+        })
         .enumerate()
         .map(|(i, c)| {
+            let value_type = if c.get_kind() == EntityKind::NonTypeTemplateParameter {
+                // unsupported value types are rejected in `parse_class` and `parse_function`
+                template_parameter_value_type(c).ok().map(Box::new)
+            } else {
+                None
+            };
             CppType::TemplateParameter(CppTemplateParameter {
                 name: c.get_name().unwrap_or_else(|| format!("Type{}", i + 1)),
                 index: i,
                 nested_level,
+                value_type,
             })
         })
         .collect_vec();
@@ -146,6 +206,57 @@ fn get_template_arguments(entity: Entity<'_>) -> Option<Vec<CppType>> {
     }
 }
 
+/// Determines the type of a non-type template parameter `entity`.
+/// Only integral and enum parameters are supported.
+fn template_parameter_value_type(entity: Entity<'_>) -> Result<CppType> {
+    let type1 = entity
+        .get_type()
+        .ok_or_else(|| err_msg("failed to get type of template parameter"))?;
+    let canonical = type1.get_canonical_type();
+    match canonical.get_kind() {
+        TypeKind::Bool
+        | TypeKind::CharS
+        | TypeKind::CharU
+        | TypeKind::SChar
+        | TypeKind::UChar
+        | TypeKind::WChar
+        | TypeKind::Char16
+        | TypeKind::Char32
+        | TypeKind::Short
+        | TypeKind::UShort
+        | TypeKind::Int
+        | TypeKind::UInt
+        | TypeKind::Long
+        | TypeKind::ULong
+        | TypeKind::LongLong
+        | TypeKind::ULongLong => Ok(CppType::BuiltInNumeric(convert_type_kind(
+            canonical.get_kind(),
+        ))),
+        TypeKind::Enum => {
+            let declaration = canonical
+                .get_declaration()
+                .ok_or_else(|| format_err!("failed to get enum declaration: {:?}", canonical))?;
+            Ok(CppType::Enum {
+                path: get_path(declaration)?,
+            })
+        }
+        _ => bail!(
+            "Non-type template parameter of type {} is not supported",
+            type1.get_display_name()
+        ),
+    }
+}
+
+/// Checks that all non-type template parameters of `entity` have supported types.
+fn check_template_parameter_value_types(entity: Entity<'_>) -> Result<()> {
+    for child in entity.get_children() {
+        if child.get_kind() == EntityKind::NonTypeTemplateParameter {
+            template_parameter_value_type(child)?;
+        }
+    }
+    Ok(())
+}
+
 fn get_context_template_args(entity: Entity<'_>) -> Vec<CppType> {
     let mut current_entity = entity;
     let mut args = Vec::new();
@@ -166,9 +277,213 @@ fn get_path_item(entity: Entity<'_>) -> Result<CppPathItem> {
     Ok(CppPathItem {
         name,
         template_arguments,
+        inline_namespaces: Vec::new(),
     })
 }
 
+/// Returns the language of a linkage specification (`extern "C" { ... }`)
+/// or `None` if `entity` is not a linkage specification.
+fn linkage_spec_language(entity: Entity<'_>) -> Option<String> {
+    match entity.get_kind() {
+        // older versions of libclang report linkage specifications as unexposed
+        EntityKind::LinkageSpec | EntityKind::UnexposedDecl => {}
+        _ => return None,
+    }
+    let tokens = entity.get_range()?.tokenize();
+    if tokens.len() < 2 || tokens[0].get_spelling() != "extern" {
+        return None;
+    }
+    let language = tokens[1].get_spelling();
+    if language.len() > 2 && language.starts_with('"') && language.ends_with('"') {
+        Some(language[1..language.len() - 1].to_string())
+    } else {
+        None
+    }
+}
+
+/// Returns true if `entity` is an inline namespace.
+fn is_inline_namespace(entity: Entity<'_>) -> bool {
+    if entity.get_kind() != EntityKind::Namespace {
+        return false;
+    }
+    // `inline` is only required in the first declaration of the namespace
+    let declaration = entity.get_canonical_entity();
+    let start = match declaration.get_range() {
+        Some(range) => range.get_start(),
+        None => return false,
+    };
+    // the declaration may be produced by a macro (e.g. `_LIBCPP_BEGIN_NAMESPACE_STD`
+    // in libc++), so the first token is taken from its spelling location
+    // instead of the location of the macro expansion
+    let location = start.get_spelling_location();
+    let file = match location.file {
+        Some(file) => file,
+        None => return false,
+    };
+    let start = file.get_offset_location(location.offset);
+    // declaration of an inline namespace starts with `inline`
+    // instead of `namespace`
+    SourceRange::new(start, start)
+        .tokenize()
+        .first()
+        .map_or(false, |token| token.get_spelling() == "inline")
+}
+
+/// Returns true if `entity` is declared inside an `extern "C"` block.
+fn has_c_linkage(entity: Entity<'_>) -> bool {
+    let mut current_entity = entity;
+    while let Some(parent) = current_entity.get_lexical_parent() {
+        if parent.get_kind() == EntityKind::TranslationUnit {
+            break;
+        }
+        if let Some(language) = linkage_spec_language(parent) {
+            return language == "C";
+        }
+        current_entity = parent;
+    }
+    false
+}
+
+fn get_visibility(entity: Entity<'_>) -> CppVisibility {
+    match entity.get_accessibility().unwrap_or(Accessibility::Public) {
+        Accessibility::Public => CppVisibility::Public,
+        Accessibility::Protected => CppVisibility::Protected,
+        Accessibility::Private => CppVisibility::Private,
+    }
+}
+
+/// Returns true if `entity` is a struct or a union declaration without a name.
+fn is_anonymous_record(entity: Entity<'_>) -> bool {
+    match entity.get_kind() {
+        EntityKind::StructDecl | EntityKind::UnionDecl | EntityKind::ClassDecl => {}
+        _ => return false,
+    }
+    // newer versions of libclang return names like "(anonymous union at file.h:1:2)"
+    entity.is_anonymous() || entity.get_name().map_or(true, |name| name.contains(' '))
+}
+
+/// Returns true if the underlying type of the enum `entity` is specified explicitly
+/// (e.g. `enum class A : uint8_t`).
+fn has_fixed_underlying_type(entity: Entity<'_>) -> bool {
+    let range = if let Some(range) = entity.get_range() {
+        range
+    } else {
+        return false;
+    };
+    range
+        .tokenize()
+        .iter()
+        .map(|token| token.get_spelling())
+        .take_while(|token| token != "{")
+        .any(|token| token == ":")
+}
+
+/// Returns deprecation information if `entity` is marked as deprecated
+/// (e.g. with `[[deprecated]]` or `Q_DECL_DEPRECATED`).
+fn get_deprecation(entity: Entity<'_>) -> Option<CppDeprecation> {
+    if entity.get_availability() != Availability::Deprecated {
+        return None;
+    }
+    // attributes may come from macros like `Q_DECL_DEPRECATED_X("message")`,
+    // so the message is searched in the spelling of the attributes and
+    // the part of the declaration before its name (the body may contain
+    // deprecated members with their own messages)
+    let head_range = entity.get_range().and_then(|range| {
+        let location = entity.get_location()?;
+        Some(SourceRange::new(range.get_start(), location))
+    });
+    let ranges = entity
+        .get_children()
+        .into_iter()
+        .filter(|child| child.get_kind() == EntityKind::UnexposedAttr)
+        .filter_map(|e| e.get_range())
+        .chain(head_range);
+    for range in ranges {
+        let tokens = range
+            .tokenize()
+            .into_iter()
+            .map(|token| token.get_spelling())
+            .collect_vec();
+        for window in tokens.windows(3) {
+            if window[0].to_lowercase().contains("deprecated")
+                && window[1] == "("
+                && window[2].len() >= 2
+                && window[2].starts_with('"')
+                && window[2].ends_with('"')
+            {
+                let literal = &window[2];
+                let message = literal[1..literal.len() - 1]
+                    .replace("\\\"", "\"")
+                    .replace("\\\\", "\\");
+                return Some(CppDeprecation {
+                    message: Some(message),
+                });
+            }
+        }
+    }
+    Some(CppDeprecation { message: None })
+}
+
+/// Returns true if `entity` is deleted (`= delete`) or otherwise marked unavailable.
+fn is_unavailable(entity: Entity<'_>) -> bool {
+    entity.get_availability() == Availability::Unavailable
+}
+
+/// Returns true if `entity` is a class or a virtual function marked as `final`.
+fn is_final(entity: Entity<'_>) -> bool {
+    entity
+        .get_children()
+        .into_iter()
+        .any(|child| child.get_kind() == EntityKind::FinalAttr)
+}
+
+/// Returns the declaration of the type of the field `entity`
+/// if it's an unnamed struct or union.
+fn unnamed_field_type_declaration(entity: Entity<'_>) -> Option<Entity<'_>> {
+    let declaration = entity.get_type()?.get_declaration()?;
+    if is_anonymous_record(declaration) {
+        Some(declaration)
+    } else {
+        None
+    }
+}
+
+/// Returns true if one of `siblings` is a named field whose type is
+/// the unnamed struct or union `record`.
+fn has_named_field_of_type(siblings: &[Entity<'_>], record: Entity<'_>) -> bool {
+    siblings.iter().any(|&sibling| {
+        sibling.get_kind() == EntityKind::FieldDecl
+            && sibling.get_name().is_some()
+            && unnamed_field_type_declaration(sibling) == Some(record)
+    })
+}
+
+/// Evaluates the initializer of a constant variable `entity` of type `variable_type`.
+/// Returns `None` if the variable is not a constant or clang can't evaluate it.
+fn evaluate_constant(entity: Entity<'_>, variable_type: &CppType) -> Option<CppConstantValue> {
+    match variable_type {
+        CppType::BuiltInNumeric(_)
+        | CppType::SpecificNumeric(_)
+        | CppType::PointerSizedInteger { .. } => {}
+        _ => return None,
+    }
+    if !entity.get_type()?.is_const_qualified() {
+        return None;
+    }
+    match entity.evaluate()? {
+        EvaluationResult::SignedInteger(value) => Some(CppConstantValue::SignedInteger(value)),
+        EvaluationResult::UnsignedInteger(value) => Some(CppConstantValue::UnsignedInteger(value)),
+        EvaluationResult::Float(value) => {
+            if value.is_finite() {
+                Some(CppConstantValue::FloatingPoint(format!("{:?}", value)))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
 /// Returns fully qualified name of `entity`.
 fn get_path(entity: Entity<'_>) -> Result<CppPath> {
     let mut current_entity = entity;
@@ -183,21 +498,38 @@ fn get_path(entity: Entity<'_>) -> Result<CppPath> {
             EntityKind::ClassDecl
             | EntityKind::ClassTemplate
             | EntityKind::StructDecl
+            | EntityKind::UnionDecl
             | EntityKind::Namespace
             | EntityKind::EnumDecl
             | EntityKind::ClassTemplatePartialSpecialization => {
-                parts.insert(0, get_path_item(p)?);
+                // members of an inline namespace are also members of the enclosing
+                // namespace, so the inline namespace is omitted to keep paths portable
+                // between implementations (e.g. `std::__1` in libc++ and `std::__cxx11`
+                // in libstdc++); it's only kept for the generated C++ code
+                if is_inline_namespace(p) {
+                    let name = p.get_name().ok_or_else(|| err_msg("Anonymous namespace"))?;
+                    parts[0].inline_namespaces.insert(0, name);
+                } else {
+                    parts.insert(0, get_path_item(p)?);
+                }
                 current_entity = p;
             }
             EntityKind::Method => {
                 bail!("Type nested in a method");
             }
+            _ if linkage_spec_language(p).is_some() => {
+                // `extern "C" { ... }` doesn't affect the path
+                current_entity = p;
+            }
             _ => bail!("get_full_name: unexpected parent kind: {:?}", p),
         }
     }
     if parts.len() > 1 && parts[0].name == "std" && parts[1].name == "__cxx11" {
         // this is an inline namespace (not portable)
-        parts.remove(1);
+        let inline_namespace = parts.remove(1);
+        if parts.len() > 1 {
+            parts[1].inline_namespaces.insert(0, inline_namespace.name);
+        }
     }
     Ok(CppPath::from_items(parts))
 }
@@ -362,6 +694,70 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     Ok(())
 }
 
+/// Parses object-like macros in the library's headers.
+/// Does nothing unless macro parsing is enabled in the config.
+pub fn parse_macros(data: &mut ProcessorData<'_>) -> Result<()> {
+    if !data.config.parse_macros() {
+        return Ok(());
+    }
+    let mut parser = CppParser {
+        current_target_paths: data
+            .config
+            .target_include_paths()
+            .iter()
+            .map_if_ok(canonicalize)?,
+        source_id: None,
+        data,
+        output: Default::default(),
+    };
+    run_clang(
+        &parser.data.config,
+        &parser.data.workspace.tmp_path(),
+        None,
+        |translation_unit| parser.parse_macro_definitions(translation_unit),
+    )
+}
+
+/// Parses C++ types specified as C++ code (e.g. `std::vector<int>`).
+/// The types must be resolvable using the library's include directives.
+pub fn parse_type_strings(data: &mut ProcessorData<'_>, types: &[String]) -> Result<Vec<CppType>> {
+    let code = types
+        .iter()
+        .enumerate()
+        .map(|(index, type_code)| format!("typedef {} ritual_parsed_type_{};\n", type_code, index))
+        .join("");
+    let mut parser = CppParser {
+        current_target_paths: Vec::new(),
+        source_id: None,
+        data,
+        output: Default::default(),
+    };
+    run_clang(
+        &parser.data.config,
+        &parser.data.workspace.tmp_path(),
+        Some(code),
+        |translation_unit| {
+            let typedefs = translation_unit
+                .get_children()
+                .into_iter()
+                .filter(|entity| entity.get_kind() == EntityKind::TypedefDecl)
+                .collect_vec();
+            types.iter().enumerate().map_if_ok(|(index, type_code)| {
+                let name = format!("ritual_parsed_type_{}", index);
+                let underlying_type = typedefs
+                    .iter()
+                    .find(|entity| entity.get_name().as_ref() == Some(&name))
+                    .and_then(|entity| entity.get_typedef_underlying_type())
+                    .ok_or_else(|| format_err!("failed to find parsed type: {}", type_code))?;
+                let cpp_type = parser
+                    .parse_type(underlying_type, &[])
+                    .with_context(|_| format!("failed to parse type: {}", type_code))?;
+                Ok(cpp_type)
+            })
+        },
+    )
+}
+
 pub fn parse_generated_items(data: &mut ProcessorData<'_>) -> Result<()> {
     let current_target = LibraryTarget {
         cpp_library_version: data.config.cpp_lib_version().map(ToString::to_string),
@@ -403,19 +799,55 @@ impl CppParser<'_, '_> {
     fn add_output(
         &mut self,
         include_file: String,
-        origin_location: CppOriginLocation,
+        entity: Entity<'_>,
         item: CppItem,
     ) -> Result<()> {
+        let doc_comment = if self.data.config.parse_doc_comments() {
+            doc_comment_html(entity).map(|html| (html, item.clone()))
+        } else {
+            None
+        };
         if let Some(id) = self.data.add_cpp_item(self.source_id.clone(), item)? {
             self.output.0.push(CppParserOutputItem {
                 include_file,
-                origin_location,
-                id,
+                origin_location: get_origin_location(entity)?,
+                id: id.clone(),
+            });
+            if let Some((html, _)) = doc_comment {
+                self.add_doc_comment(id, html);
+            }
+        } else if let Some((html, item)) = doc_comment {
+            // the item was already added from another declaration
+            // (e.g. a redeclaration of a function), so it's found by path
+            let crate_name = self.data.db.crate_name().to_string();
+            let id = item.path().and_then(|path| {
+                self.data
+                    .db
+                    .find_cpp_items_by_path(path)
+                    .find(|other| other.id.crate_name() == crate_name && other.item.is_same(&item))
+                    .map(|other| other.id)
             });
+            if let Some(id) = id {
+                self.add_doc_comment(id, html);
+            }
         }
         Ok(())
     }
 
+    /// Adds `html` as a documentation item of the C++ item `id`.
+    fn add_doc_comment(&mut self, id: ItemId, html: String) {
+        let doc_item = DocItem {
+            anchor: None,
+            html,
+            mismatched_declaration: None,
+            url: None,
+            cross_references: Vec::new(),
+        };
+        if self.data.db.add_doc_item(id.clone(), doc_item).is_some() {
+            trace!("added doc comment for {}", id);
+        }
+    }
+
     /// Search for a C++ type information in the types found by the parser
     /// and in types of the dependencies.
     fn find_type(
@@ -459,6 +891,7 @@ impl CppParser<'_, '_> {
                 if declaration.get_kind() == EntityKind::ClassDecl
                     || declaration.get_kind() == EntityKind::ClassTemplate
                     || declaration.get_kind() == EntityKind::StructDecl
+                    || declaration.get_kind() == EntityKind::UnionDecl
                 {
                     if declaration
                         .get_accessibility()
@@ -471,11 +904,15 @@ impl CppParser<'_, '_> {
                         );
                     }
                     if let Some((_class_name, args)) = parse_template_args(&name) {
+                        let parameters = get_template_arguments(
+                            declaration.get_template().unwrap_or(declaration),
+                        )
+                        .unwrap_or_default();
                         let mut arg_types = Vec::new();
-                        for arg in args {
-                            match self.parse_unexposed_type(
-                                None,
-                                Some(arg.trim().to_string()),
+                        for (index, arg) in args.iter().enumerate() {
+                            match self.parse_unexposed_template_argument(
+                                arg,
+                                parameters.get(index),
                                 context_template_args,
                             ) {
                                 Ok(arg_type) => arg_types.push(arg_type),
@@ -519,6 +956,7 @@ impl CppParser<'_, '_> {
                     "encountered not a number while parsing type-parameter-X-X"
                 })?,
                 name: name.clone(),
+                value_type: None,
             }));
         }
 
@@ -586,22 +1024,27 @@ impl CppParser<'_, '_> {
                 CppTypeDeclarationKind::Enum { .. } => {
                     return Ok(CppType::Enum { path });
                 }
-                CppTypeDeclarationKind::Class { .. } => {
+                CppTypeDeclarationKind::Class { .. } | CppTypeDeclarationKind::Union => {
                     return Ok(CppType::Class(path));
                 }
             }
         }
 
         if let Some((class_text, args)) = parse_template_args(&name) {
-            if self
+            if let Some(template_type) = self
                 .find_type(|x| x.kind.is_class() && x.path.to_templateless_string() == class_text)
-                .is_some()
             {
+                let parameters = template_type
+                    .path
+                    .last()
+                    .template_arguments
+                    .clone()
+                    .unwrap_or_default();
                 let mut arg_types = Vec::new();
-                for arg in args {
-                    match self.parse_unexposed_type(
-                        None,
-                        Some(arg.trim().to_string()),
+                for (index, arg) in args.iter().enumerate() {
+                    match self.parse_unexposed_template_argument(
+                        arg,
+                        parameters.get(index),
                         context_template_args,
                     ) {
                         Ok(arg_type) => arg_types.push(arg_type),
@@ -625,6 +1068,66 @@ impl CppParser<'_, '_> {
         bail!("Unrecognized unexposed type: {}", name);
     }
 
+    /// Parses a template argument of an unexposed type from its text.
+    /// `parameter` is the corresponding template parameter (or argument) of
+    /// the template declaration, if it's known.
+    fn parse_unexposed_template_argument(
+        &self,
+        text: &str,
+        parameter: Option<&CppType>,
+        context_template_args: &[CppType],
+    ) -> Result<CppType> {
+        let text = text.trim();
+        let value_type = match parameter {
+            Some(CppType::TemplateParameter(param)) => param.value_type.as_ref(),
+            Some(CppType::IntegralConstant { value_type, .. }) => Some(value_type),
+            _ => None,
+        };
+        if let Some(value_type) = value_type {
+            if let Some(arg) = context_template_args
+                .iter()
+                .find(|t| t.to_cpp_pseudo_code() == text)
+            {
+                return Ok(arg.clone());
+            }
+            return self.parse_template_argument_value(text, value_type);
+        }
+        self.parse_unexposed_type(None, Some(text.to_string()), context_template_args)
+    }
+
+    /// Parses `text` as a value of a non-type template argument of `value_type`.
+    fn parse_template_argument_value(&self, text: &str, value_type: &CppType) -> Result<CppType> {
+        let value = match text {
+            "true" => 1,
+            "false" => 0,
+            _ => {
+                let digits =
+                    text.trim_end_matches(|c| c == 'u' || c == 'U' || c == 'l' || c == 'L');
+                if let Ok(value) = digits.parse::<i64>() {
+                    value
+                } else if let CppType::Enum { path } = value_type {
+                    let name = text.split("::").last().unwrap_or(text);
+                    self.data
+                        .db
+                        .all_cpp_items()
+                        .filter_map(|item| item.item.as_enum_value_ref())
+                        .find(|item| {
+                            item.path.last().name == name
+                                && item.path.parent().ok().as_ref() == Some(path)
+                        })
+                        .ok_or_else(|| format_err!("unknown enum value: {}", text))?
+                        .value
+                } else {
+                    bail!("unsupported template argument value: {}", text);
+                }
+            }
+        };
+        Ok(CppType::IntegralConstant {
+            value_type: Box::new(value_type.clone()),
+            value,
+        })
+    }
+
     /// Parses type `type1`.
     /// Surrounding class and/or
     /// method may be specified in `context_class` and `context_method`.
@@ -648,6 +1151,7 @@ impl CppParser<'_, '_> {
                     name: "T".into(),
                     nested_level: 0,
                     index: 0,
+                    value_type: None,
                 }),
             ));
         }
@@ -660,6 +1164,7 @@ impl CppParser<'_, '_> {
                     name: "T".into(),
                     nested_level: 0,
                     index: 0,
+                    value_type: None,
                 }),
             ));
         }
@@ -739,9 +1244,31 @@ impl CppParser<'_, '_> {
                             if arg_types.is_empty() {
                                 bail!("arg_types is empty");
                             }
-                            for arg_type in arg_types {
+                            for (index, arg_type) in arg_types.into_iter().enumerate() {
                                 match arg_type {
-                                    None => bail!("Template argument is None"),
+                                    None => {
+                                        // clang doesn't expose values of non-type template
+                                        // arguments, so they are extracted from the type's name
+                                        let parameters = get_template_arguments(
+                                            declaration.get_template().unwrap_or(declaration),
+                                        )
+                                        .unwrap_or_default();
+                                        let mut name = type1.get_display_name();
+                                        if name.starts_with("const ") {
+                                            name = name[6..].to_string();
+                                        }
+                                        let arg_texts = parse_template_args(&name)
+                                            .map(|(_, args)| args)
+                                            .unwrap_or_default();
+                                        let arg_text = arg_texts
+                                            .get(index)
+                                            .ok_or_else(|| err_msg("Template argument is None"))?;
+                                        r.push(self.parse_unexposed_template_argument(
+                                            arg_text,
+                                            parameters.get(index),
+                                            context_template_args,
+                                        )?);
+                                    }
                                     Some(arg_type) => {
                                         match self.parse_type(arg_type, context_template_args) {
                                             Ok(parsed_type) => r.push(parsed_type),
@@ -840,6 +1367,21 @@ impl CppParser<'_, '_> {
                     None => bail!("can't get pointee type"),
                 }
             }
+            TypeKind::ConstantArray => {
+                let element = type1
+                    .get_element_type()
+                    .ok_or_else(|| err_msg("failed to get array element type"))?;
+                let len = type1
+                    .get_size()
+                    .ok_or_else(|| err_msg("failed to get array size"))?;
+                let element = self
+                    .parse_type(element, context_template_args)
+                    .with_context(|_| "Can't parse array element type")?;
+                Ok(CppType::Array {
+                    element: Box::new(element),
+                    len,
+                })
+            }
             TypeKind::Elaborated => {
                 self.parse_type(type1.get_canonical_type(), context_template_args)
             }
@@ -967,17 +1509,18 @@ impl CppParser<'_, '_> {
     fn parse_function(&mut self, entity: Entity<'_>) -> Result<()> {
         let class_name = match entity.get_semantic_parent() {
             Some(p) => match p.get_kind() {
-                EntityKind::ClassDecl | EntityKind::ClassTemplate | EntityKind::StructDecl => {
-                    match get_path(p) {
-                        Ok(class_name) => Some(class_name),
-                        Err(msg) => {
-                            bail!(
-                                "function parent is a class but it doesn't have a name: {}",
-                                msg
-                            );
-                        }
+                EntityKind::ClassDecl
+                | EntityKind::ClassTemplate
+                | EntityKind::StructDecl
+                | EntityKind::UnionDecl => match get_path(p) {
+                    Ok(class_name) => Some(class_name),
+                    Err(msg) => {
+                        bail!(
+                            "function parent is a class but it doesn't have a name: {}",
+                            msg
+                        );
                     }
-                }
+                },
                 EntityKind::ClassTemplatePartialSpecialization => {
                     bail!("this function is part of a template partial specialization");
                 }
@@ -1044,8 +1587,7 @@ impl CppParser<'_, '_> {
                         clang_type.get_display_name()
                     )
                 })?;
-            let mut has_default_value = false;
-            for token in argument_entity
+            let tokens = argument_entity
                 .get_range()
                 .ok_or_else(|| {
                     format_err!(
@@ -1054,21 +1596,16 @@ impl CppParser<'_, '_> {
                     )
                 })?
                 .tokenize()
-            {
-                let spelling = token.get_spelling();
-                if spelling == "=" {
-                    has_default_value = true;
-                    break;
-                }
-                if spelling == "{" {
-                    // clang sometimes reports incorrect range for arguments
-                    break;
-                }
-            }
+                .into_iter()
+                .map(|token| token.get_spelling())
+                .collect_vec();
+            let (has_default_value, default_value) = parse_default_value(&tokens);
             arguments.push(CppFunctionArgument {
                 name,
                 argument_type,
                 has_default_value,
+                default_value,
+                type_alias: type_alias_path(clang_type),
             });
         }
 
@@ -1091,13 +1628,7 @@ impl CppParser<'_, '_> {
 
         let template_arguments = match entity.get_kind() {
             EntityKind::FunctionTemplate => {
-                if entity
-                    .get_children()
-                    .into_iter()
-                    .any(|c| c.get_kind() == EntityKind::NonTypeTemplateParameter)
-                {
-                    bail!("Non-type template parameter is not supported");
-                }
+                check_template_parameter_value_types(entity)?;
                 get_template_arguments(entity)
             }
             _ => None,
@@ -1231,6 +1762,7 @@ impl CppParser<'_, '_> {
                     },
                     is_virtual: entity.is_virtual_method(),
                     is_pure_virtual: entity.is_pure_virtual_method(),
+                    is_final: is_final(entity),
                     is_const: entity.is_const_method(),
                     is_static: entity.is_static_method(),
                     visibility: match entity.get_accessibility().unwrap_or(Accessibility::Public) {
@@ -1247,14 +1779,19 @@ impl CppParser<'_, '_> {
             },
             arguments,
             allows_variadic_arguments,
+            has_c_linkage: has_c_linkage(entity),
             return_type: return_type_parsed,
             cast: None,
             declaration_code,
+            deprecation: get_deprecation(entity),
+            is_deleted: is_unavailable(entity),
+            is_friend: is_friend_function(entity),
+            return_type_alias: type_alias_path(return_type),
         };
 
         self.add_output(
             self.entity_include_file(entity)?,
-            get_origin_location(entity)?,
+            entity,
             CppItem::Function(function),
         )?;
 
@@ -1271,14 +1808,14 @@ impl CppParser<'_, '_> {
             )
         })?;
         let enum_name = get_path(entity)?;
-        self.add_output(
-            include_file.clone(),
-            get_origin_location(entity)?,
-            CppItem::Type(CppTypeDeclaration {
-                kind: CppTypeDeclarationKind::Enum,
-                path: enum_name.clone(),
-            }),
-        )?;
+        let clang_underlying_type = entity
+            .get_enum_underlying_type()
+            .ok_or_else(|| err_msg("failed to get enum underlying type"))?;
+        let mut underlying_type = self
+            .parse_type(clang_underlying_type, &get_context_template_args(entity))
+            .with_context(|_| err_msg("failed to parse enum underlying type"))?;
+
+        let mut values = Vec::new();
         for child in entity.get_children() {
             if child.get_kind() == EntityKind::EnumConstantDecl {
                 let val = child
@@ -1288,51 +1825,163 @@ impl CppParser<'_, '_> {
                 let value_name = child
                     .get_name()
                     .ok_or_else(|| err_msg("failed to get name of enum variant"))?;
-                self.add_output(
-                    include_file.clone(),
-                    get_origin_location(child)?,
-                    CppItem::EnumValue(CppEnumValue {
-                        path: enum_name.join(CppPathItem::from_good_str(&value_name)),
-                        value: val.0,
-                    }),
-                )?;
+                values.push((child, value_name, val.0));
             }
         }
+
+        if underlying_type == CppType::BuiltInNumeric(CppBuiltInNumericType::UInt)
+            && !has_fixed_underlying_type(entity)
+            && values
+                .iter()
+                .all(|(_, _, value)| *value <= i64::from(std::i32::MAX))
+        {
+            // clang uses `unsigned int` for enums without negative values
+            // if the type is not specified, but `int` has the same representation
+            // and is more convenient
+            underlying_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+        }
+
+        self.add_output(
+            include_file.clone(),
+            entity,
+            CppItem::Type(CppTypeDeclaration {
+                kind: CppTypeDeclarationKind::Enum { underlying_type },
+                path: enum_name.clone(),
+                deprecation: get_deprecation(entity),
+                is_deleted: is_unavailable(entity),
+                is_final: is_final(entity),
+            }),
+        )?;
+        for (child, value_name, value) in values {
+            self.add_output(
+                include_file.clone(),
+                child,
+                CppItem::EnumValue(CppEnumValue {
+                    path: enum_name.join(CppPathItem::from_good_str(&value_name)),
+                    value,
+                }),
+            )?;
+        }
         Ok(())
     }
 
-    /// Parses a class field `entity`.
-    fn parse_class_field(&mut self, entity: Entity<'_>, class_type: &CppPath) -> Result<()> {
+    /// Parses a typedef or a type alias `entity`.
+    fn parse_type_alias(&mut self, entity: Entity<'_>) -> Result<()> {
+        let include_file = self
+            .entity_include_file(entity)
+            .with_context(|_| err_msg("Origin of type alias is unknown"))?;
+        let path = get_path(entity)?;
+        let target_clang_type = entity
+            .get_typedef_underlying_type()
+            .ok_or_else(|| err_msg("failed to get underlying type"))?;
+        let target_type = self
+            .parse_type(target_clang_type, &get_context_template_args(entity))
+            .with_context(|_| err_msg("failed to parse underlying type"))?;
+        let in_template = path.items().iter().any(|item| {
+            item.template_arguments.as_ref().map_or(false, |args| {
+                args.iter().any(CppType::is_or_contains_template_parameter)
+            })
+        });
+        if in_template || target_type.is_or_contains_template_parameter() {
+            bail!("type aliases depending on template parameters are not supported");
+        }
+        let is_same_name = match &target_type {
+            CppType::Class(target_path) | CppType::Enum { path: target_path } => {
+                target_path == &path
+            }
+            _ => false,
+        };
+        if is_same_name {
+            // `typedef struct A A;`
+            bail!("type alias has the same name as its target type");
+        }
+        self.add_output(
+            include_file,
+            entity,
+            CppItem::TypeAlias(CppTypeAlias { path, target_type }),
+        )?;
+        Ok(())
+    }
+
+    /// Parses a field or a static data member `entity` of `class_type`.
+    ///
+    /// `member_prefix` contains names of the enclosing fields of unnamed types
+    /// if the field belongs to an anonymous struct or union. `visibility` overrides
+    /// the visibility of the field in this case.
+    fn parse_class_field(
+        &mut self,
+        entity: Entity<'_>,
+        class_type: &CppPath,
+        member_prefix: &[String],
+        visibility: Option<CppVisibility>,
+    ) -> Result<()> {
         let include_file = self
             .entity_include_file(entity)
             .with_context(|_| err_msg("Origin of class field is unknown"))?;
         let field_name = entity
             .get_name()
             .ok_or_else(|| err_msg("failed to get field name"))?;
+        let (name, member_access) = if member_prefix.is_empty() {
+            (field_name, None)
+        } else {
+            let name = format!("{}_{}", member_prefix.join("_"), field_name);
+            let member_access = format!("{}.{}", member_prefix.join("."), field_name);
+            (name, Some(member_access))
+        };
         let field_clang_type = entity
             .get_type()
             .ok_or_else(|| err_msg("failed to get field type"))?;
         let field_type = self
             .parse_type(field_clang_type, &get_context_template_args(entity))
             .with_context(|_| err_msg("failed to parse field type"))?;
+        let is_static = entity.get_kind() == EntityKind::VarDecl;
+        let value = if is_static {
+            evaluate_constant(entity, &field_type)
+        } else {
+            None
+        };
         self.add_output(
             include_file,
-            get_origin_location(entity)?,
+            entity,
             CppItem::ClassField(CppClassField {
-                path: class_type.join(CppPathItem::from_good_str(&field_name)),
+                path: class_type.join(CppPathItem::from_good_str(&name)),
                 field_type,
-                visibility: match entity.get_accessibility().unwrap_or(Accessibility::Public) {
-                    Accessibility::Public => CppVisibility::Public,
-                    Accessibility::Protected => CppVisibility::Protected,
-                    Accessibility::Private => CppVisibility::Private,
-                },
-                is_static: entity.get_kind() == EntityKind::VarDecl,
+                visibility: visibility.unwrap_or_else(|| get_visibility(entity)),
+                is_static,
+                is_const: field_clang_type.is_const_qualified(),
+                value,
+                member_access,
             }),
         )?;
 
         Ok(())
     }
 
+    /// Parses a namespace-scope variable `entity`.
+    fn parse_variable(&mut self, entity: Entity<'_>) -> Result<()> {
+        let include_file = self
+            .entity_include_file(entity)
+            .with_context(|_| err_msg("Origin of variable is unknown"))?;
+        let clang_type = entity
+            .get_type()
+            .ok_or_else(|| err_msg("failed to get variable type"))?;
+        let variable_type = self
+            .parse_type(clang_type, &[])
+            .with_context(|_| err_msg("failed to parse variable type"))?;
+        let value = evaluate_constant(entity, &variable_type);
+        self.add_output(
+            include_file,
+            entity,
+            CppItem::Variable(CppVariable {
+                path: get_path(entity)?,
+                variable_type,
+                is_const: clang_type.is_const_qualified(),
+                value,
+            }),
+        )?;
+        Ok(())
+    }
+
     // we pass parent manually because both lexical and semantic parent are missing for these
     // entities for some reason
     fn parse_class_base(
@@ -1350,7 +1999,7 @@ impl CppParser<'_, '_> {
         if let CppType::Class(base_type) = &base_type {
             self.add_output(
                 self.entity_include_file(entity)?,
-                get_origin_location(entity).unwrap(),
+                entity,
                 CppItem::ClassBase(CppBaseSpecifier {
                     base_class_type: base_type.clone(),
                     is_virtual: entity.is_virtual_base(),
@@ -1369,7 +2018,43 @@ impl CppParser<'_, '_> {
         Ok(())
     }
 
-    /// Parses a class or a struct `entity`.
+    /// Parses fields of an anonymous struct or union `entity` as fields of `class_type`.
+    fn parse_anonymous_member(
+        &mut self,
+        entity: Entity<'_>,
+        class_type: &CppPath,
+        member_prefix: &[String],
+        visibility: CppVisibility,
+    ) {
+        let children = entity.get_children();
+        for &child in &children {
+            if child.get_kind() != EntityKind::FieldDecl {
+                if is_anonymous_record(child) && !has_named_field_of_type(&children, child) {
+                    self.parse_anonymous_member(child, class_type, member_prefix, visibility);
+                }
+                continue;
+            }
+            if let Some(declaration) = unnamed_field_type_declaration(child) {
+                if let Some(name) = child.get_name() {
+                    let mut prefix = member_prefix.to_vec();
+                    prefix.push(name);
+                    self.parse_anonymous_member(declaration, class_type, &prefix, visibility);
+                }
+                continue;
+            }
+            if let Err(err) =
+                self.parse_class_field(child, class_type, member_prefix, Some(visibility))
+            {
+                debug!(
+                    "failed to parse field of anonymous member: {}: {}",
+                    class_type.to_cpp_pseudo_code(),
+                    err
+                );
+            }
+        }
+    }
+
+    /// Parses a class, a struct or a union `entity`.
     fn parse_class(&mut self, entity: Entity<'_>) -> Result<()> {
         let include_file = self.entity_include_file(entity).with_context(|_| {
             format!(
@@ -1381,13 +2066,7 @@ impl CppParser<'_, '_> {
         let full_name = get_path(entity)?;
         let template_arguments = get_template_arguments(entity);
         if entity.get_kind() == EntityKind::ClassTemplate {
-            if entity
-                .get_children()
-                .into_iter()
-                .any(|c| c.get_kind() == EntityKind::NonTypeTemplateParameter)
-            {
-                bail!("Non-type template parameter is not supported");
-            }
+            check_template_parameter_value_types(entity)?;
 
             if template_arguments.is_none() {
                 dump_entity(entity, 0);
@@ -1397,10 +2076,28 @@ impl CppParser<'_, '_> {
             bail!("unexpected template arguments");
         }
         let mut current_base_index = 0;
-        for child in entity.get_children() {
+        let children = entity.get_children();
+        for &child in &children {
+            if is_anonymous_record(child) && !has_named_field_of_type(&children, child) {
+                // anonymous struct or union, its fields are accessible
+                // as fields of the enclosing class
+                let visibility = get_visibility(child);
+                self.parse_anonymous_member(child, &full_name, &[], visibility);
+                continue;
+            }
+            if child.get_kind() == EntityKind::FieldDecl {
+                if let Some(declaration) = unnamed_field_type_declaration(child) {
+                    // a field of an unnamed type, e.g. `union { ... } data;`
+                    if let Some(name) = child.get_name() {
+                        let visibility = get_visibility(child);
+                        self.parse_anonymous_member(declaration, &full_name, &[name], visibility);
+                    }
+                    continue;
+                }
+            }
             if child.get_kind() == EntityKind::FieldDecl || child.get_kind() == EntityKind::VarDecl
             {
-                if let Err(err) = self.parse_class_field(child, &full_name) {
+                if let Err(err) = self.parse_class_field(child, &full_name, &[], None) {
                     debug!(
                         "failed to parse class field: {}: {}",
                         get_full_name_display(child),
@@ -1419,16 +2116,20 @@ impl CppParser<'_, '_> {
                 }
                 current_base_index += 1;
             }
-            if child.get_kind() == EntityKind::NonTypeTemplateParameter {
-                bail!("Non-type template parameter is not supported");
-            }
         }
         self.add_output(
             include_file,
-            get_origin_location(entity).unwrap(),
+            entity,
             CppItem::Type(CppTypeDeclaration {
-                kind: CppTypeDeclarationKind::Class,
+                kind: if entity.get_kind() == EntityKind::UnionDecl {
+                    CppTypeDeclarationKind::Union
+                } else {
+                    CppTypeDeclarationKind::Class
+                },
                 path: full_name,
+                deprecation: get_deprecation(entity),
+                is_deleted: is_unavailable(entity),
+                is_final: is_final(entity),
             }),
         )?;
         Ok(())
@@ -1457,22 +2158,26 @@ impl CppParser<'_, '_> {
         Ok(os_str_to_str(file_name)?.to_string())
     }
 
-    /// Returns false if this `entity` was blacklisted in some way.
-    fn should_process_entity(&self, entity: Entity<'_>) -> Result<bool> {
-        if entity.get_kind() == EntityKind::TranslationUnit {
-            return Ok(true);
-        }
+    /// Returns true if `entity` is located in one of the target include paths.
+    fn is_in_target_paths(&self, entity: Entity<'_>) -> Result<bool> {
         if let Ok(file_path) = self.entity_include_path(entity) {
             let file_path = canonicalize(Path::new(&file_path))?;
-            if !self.current_target_paths.is_empty()
-                && !self
+            Ok(self.current_target_paths.is_empty()
+                || self
                     .current_target_paths
                     .iter()
-                    .any(|x| file_path.starts_with(x))
-            {
-                return Ok(false);
-            }
+                    .any(|x| file_path.starts_with(x)))
         } else {
+            Ok(false)
+        }
+    }
+
+    /// Returns false if this `entity` was blacklisted in some way.
+    fn should_process_entity(&self, entity: Entity<'_>) -> Result<bool> {
+        if entity.get_kind() == EntityKind::TranslationUnit {
+            return Ok(true);
+        }
+        if !self.is_in_target_paths(entity)? {
             return Ok(false);
         }
         if let Ok(full_name) = get_path(entity) {
@@ -1498,6 +2203,60 @@ impl CppParser<'_, '_> {
         Ok(())
     }
 
+    /// Parses object-like macros defined in translation unit `entity`.
+    fn parse_macro_definitions(&mut self, entity: Entity<'_>) -> Result<()> {
+        for child in entity.get_children() {
+            if child.get_kind() != EntityKind::MacroDefinition
+                || child.is_builtin_macro()
+                || child.is_function_like_macro()
+            {
+                continue;
+            }
+            if !self.is_in_target_paths(child)? {
+                continue;
+            }
+            if let Err(error) = self.parse_macro(child) {
+                trace!(
+                    "failed to parse macro: {}: {}",
+                    get_full_name_display(child),
+                    error
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses an object-like macro `entity` if its value is a numeric literal.
+    fn parse_macro(&mut self, entity: Entity<'_>) -> Result<()> {
+        let name = entity
+            .get_name()
+            .ok_or_else(|| err_msg("failed to get macro name"))?;
+        if let Some(hook) = self.data.config.macro_filter_hook() {
+            if !hook(&name)? {
+                return Ok(());
+            }
+        }
+        let tokens = entity
+            .get_range()
+            .ok_or_else(|| err_msg("failed to get macro range"))?
+            .tokenize()
+            .iter()
+            .map(Token::get_spelling)
+            .collect_vec();
+        // the first token is the macro name
+        let (value_type, value) = parse_macro_value(tokens.get(1..).unwrap_or_default())?;
+        self.add_output(
+            self.entity_include_file(entity)?,
+            entity,
+            CppItem::MacroConstant(CppMacroConstant {
+                path: CppPath::from_good_str(&name),
+                value_type,
+                value,
+            }),
+        )?;
+        Ok(())
+    }
+
     /// Parses type declarations in translation unit `entity`
     /// and saves them to `self`.
     fn parse_types(&mut self, entity: Entity<'_>) -> Result<()> {
@@ -1520,11 +2279,14 @@ impl CppParser<'_, '_> {
                     }
                 }
             }
-            EntityKind::ClassDecl | EntityKind::ClassTemplate | EntityKind::StructDecl => {
+            EntityKind::ClassDecl
+            | EntityKind::ClassTemplate
+            | EntityKind::StructDecl
+            | EntityKind::UnionDecl => {
                 if entity.get_accessibility() == Some(Accessibility::Private) {
                     return Ok(()); // skipping private stuff
                 }
-                let ok = entity.get_name().is_some() && // not an anonymous struct
+                let ok = !is_anonymous_record(entity) && // not an anonymous struct
                     entity.is_definition() && // not a forward declaration
                     entity.get_template().is_none(); // not a template specialization
                 if ok {
@@ -1538,11 +2300,47 @@ impl CppParser<'_, '_> {
                     }
                 }
             }
+            EntityKind::TypedefDecl | EntityKind::TypeAliasDecl => {
+                if entity.get_accessibility() == Some(Accessibility::Private) {
+                    return Ok(()); // skipping private stuff
+                }
+                if let Err(error) = self.parse_type_alias(entity) {
+                    trace!(
+                        "failed to parse type alias: {}: {}",
+                        get_full_name_display(entity),
+                        error
+                    );
+                }
+            }
+            EntityKind::VarDecl => {
+                let is_in_class = entity
+                    .get_semantic_parent()
+                    .map_or(false, |parent| match parent.get_kind() {
+                        EntityKind::ClassDecl
+                        | EntityKind::ClassTemplate
+                        | EntityKind::StructDecl
+                        | EntityKind::UnionDecl
+                        | EntityKind::ClassTemplatePartialSpecialization => true,
+                        _ => false,
+                    });
+                // static data members are parsed as class fields
+                if !is_in_class {
+                    if let Err(error) = self.parse_variable(entity) {
+                        debug!(
+                            "failed to parse variable: {}: {}",
+                            get_full_name_display(entity),
+                            error
+                        );
+                        trace!("entity: {:?}", entity);
+                    }
+                }
+            }
+            EntityKind::Namespace if is_inline_namespace(entity) => {}
             EntityKind::Namespace => match get_path(entity) {
                 Ok(path) => {
                     self.add_output(
                         self.entity_include_file(entity)?,
-                        get_origin_location(entity).unwrap(),
+                        entity,
                         CppItem::Namespace(CppNamespace { path }),
                     )?;
                 }
@@ -1554,8 +2352,10 @@ impl CppParser<'_, '_> {
             EntityKind::TranslationUnit
             | EntityKind::Namespace
             | EntityKind::StructDecl
+            | EntityKind::UnionDecl
             | EntityKind::ClassDecl
             | EntityKind::UnexposedDecl
+            | EntityKind::LinkageSpec
             | EntityKind::ClassTemplate => {
                 for c in entity.get_children() {
                     self.parse_types(c)?;
@@ -1588,7 +2388,21 @@ impl CppParser<'_, '_> {
                     trace!("entity: {:?}", entity);
                 }
             }
+            EntityKind::FriendDecl => {
+                // operators declared only as friends can't be found by qualified lookup,
+                // but they are still available through argument-dependent lookup
+                for child in entity.get_children() {
+                    let is_operator = child.get_kind() == EntityKind::FunctionDecl
+                        && child
+                            .get_name()
+                            .map_or(false, |name| name.starts_with("operator"));
+                    if is_operator {
+                        self.parse_functions(child)?;
+                    }
+                }
+            }
             EntityKind::StructDecl
+            | EntityKind::UnionDecl
             | EntityKind::ClassDecl
             | EntityKind::ClassTemplate
             | EntityKind::ClassTemplatePartialSpecialization => {
@@ -1622,8 +2436,10 @@ impl CppParser<'_, '_> {
             EntityKind::TranslationUnit
             | EntityKind::Namespace
             | EntityKind::StructDecl
+            | EntityKind::UnionDecl
             | EntityKind::ClassDecl
             | EntityKind::UnexposedDecl
+            | EntityKind::LinkageSpec
             | EntityKind::ClassTemplate => {
                 for c in entity.get_children() {
                     self.parse_functions(c)?;
@@ -1635,6 +2451,175 @@ impl CppParser<'_, '_> {
     }
 }
 
+/// Parses the value of an object-like macro. The value must be a numeric literal,
+/// optionally negated and enclosed in parentheses.
+fn parse_macro_value(mut tokens: &[String]) -> Result<(CppType, CppConstantValue)> {
+    while tokens.len() >= 2 && tokens[0] == "(" && tokens[tokens.len() - 1] == ")" {
+        tokens = &tokens[1..tokens.len() - 1];
+    }
+    let (is_negative, literal) = match tokens {
+        [literal] => (false, literal),
+        [sign, literal] if sign == "-" => (true, literal),
+        [sign, literal] if sign == "+" => (false, literal),
+        _ => bail!("unsupported macro value: {}", tokens.join(" ")),
+    };
+    let literal = literal.to_lowercase().replace('\'', "");
+
+    let (digits, radix) = if literal.starts_with("0x") {
+        (&literal[2..], 16)
+    } else if literal.starts_with("0b") {
+        (&literal[2..], 2)
+    } else if literal.starts_with('0')
+        // `0` with a suffix (e.g. `0u`) is a decimal literal
+        && literal.trim_end_matches(|c| c == 'u' || c == 'l').len() > 1
+        && literal
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == 'u' || c == 'l')
+    {
+        (&literal[1..], 8)
+    } else {
+        (&literal[..], 10)
+    };
+
+    if radix == 10 && literal.contains(|c: char| c == '.' || c == 'e') {
+        let (digits, value_type) = if literal.ends_with('f') {
+            (&literal[..literal.len() - 1], CppBuiltInNumericType::Float)
+        } else if literal.ends_with('l') {
+            bail!("long double literals are not supported");
+        } else {
+            (&literal[..], CppBuiltInNumericType::Double)
+        };
+        let mut value = f64::from_str(digits)?;
+        if is_negative {
+            value = -value;
+        }
+        return Ok((
+            CppType::BuiltInNumeric(value_type),
+            CppConstantValue::FloatingPoint(format!("{:?}", value)),
+        ));
+    }
+
+    let suffix_len = digits
+        .chars()
+        .rev()
+        .take_while(|&c| c == 'u' || c == 'l')
+        .count();
+    let (digits, suffix) = digits.split_at(digits.len() - suffix_len);
+    let value = u64::from_str_radix(digits, radix)?;
+    let is_unsigned = suffix.contains('u');
+    let long_count = suffix.matches('l').count();
+
+    let fits_i32 = value <= i32::max_value() as u64;
+    let fits_u32 = value <= u32::max_value() as u64;
+    let fits_i64 = value <= i64::max_value() as u64;
+    let value_type = if is_unsigned {
+        if long_count == 0 && fits_u32 {
+            CppBuiltInNumericType::UInt
+        } else if long_count == 1 && fits_u32 {
+            CppBuiltInNumericType::ULong
+        } else {
+            CppBuiltInNumericType::ULongLong
+        }
+    } else if long_count == 0 && fits_i32 {
+        CppBuiltInNumericType::Int
+    } else if long_count == 0 && radix != 10 && fits_u32 {
+        CppBuiltInNumericType::UInt
+    } else if long_count == 1 && fits_i32 {
+        CppBuiltInNumericType::Long
+    } else if fits_i64 {
+        CppBuiltInNumericType::LongLong
+    } else if radix != 10 {
+        CppBuiltInNumericType::ULongLong
+    } else {
+        bail!("integer literal is too large: {}", literal);
+    };
+
+    let value = match value_type {
+        CppBuiltInNumericType::UInt
+        | CppBuiltInNumericType::ULong
+        | CppBuiltInNumericType::ULongLong => {
+            if is_negative {
+                bail!("negated unsigned literals are not supported");
+            }
+            CppConstantValue::UnsignedInteger(value)
+        }
+        _ => {
+            let value = value as i64;
+            CppConstantValue::SignedInteger(if is_negative { -value } else { value })
+        }
+    };
+    Ok((CppType::BuiltInNumeric(value_type), value))
+}
+
+/// Joins tokens of a C++ expression, omitting spaces where they are not
+/// conventionally used (e.g. `QSize(0, 0)` instead of `QSize ( 0 , 0 )`).
+fn join_tokens(tokens: &[String]) -> String {
+    let mut result = String::new();
+    for (index, token) in tokens.iter().enumerate() {
+        if index > 0 {
+            let previous = tokens[index - 1].as_str();
+            // `-` in `f(-1)` is unary and shouldn't be followed by a space
+            let previous_is_unary = ["-", "+", "!", "~"].contains(&previous)
+                && (index == 1 || ["(", "[", "{", ","].contains(&tokens[index - 2].as_str()));
+            let no_space = ["::", ".", "->", "<", "(", "[", "{"].contains(&previous)
+                || [
+                    ")", "]", "}", ",", "(", "[", "::", ".", "->", "<", ">", ">>",
+                ]
+                .contains(&token.as_str())
+                || previous_is_unary;
+            if !no_space {
+                result.push(' ');
+            }
+        }
+        result.push_str(token);
+    }
+    result
+}
+
+/// Extracts the default value expression from tokens of a function argument.
+/// Returns `false` if the argument doesn't have a default value.
+fn parse_default_value(tokens: &[String]) -> (bool, Option<String>) {
+    let mut has_default_value = false;
+    let mut default_value_tokens: Vec<String> = Vec::new();
+    let mut depth = 0;
+    let mut angle_depth = 0;
+    for spelling in tokens {
+        if has_default_value {
+            let previous_is_name = default_value_tokens.last().map_or(false, |t| {
+                t.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                    && t.chars().all(|c| c.is_alphanumeric() || c == '_')
+            });
+            match spelling.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" if depth == 0 => break,
+                ")" | "]" | "}" => depth -= 1,
+                // `<` after a name is assumed to start template arguments
+                "<" if previous_is_name => angle_depth += 1,
+                ">" if angle_depth > 0 => angle_depth -= 1,
+                ">>" if angle_depth > 0 => angle_depth -= angle_depth.min(2),
+                "," | ";" if depth == 0 && angle_depth == 0 => break,
+                _ => {}
+            }
+            default_value_tokens.push(spelling.clone());
+            continue;
+        }
+        if spelling == "=" {
+            has_default_value = true;
+            continue;
+        }
+        if spelling == "{" {
+            // clang sometimes reports incorrect range for arguments
+            break;
+        }
+    }
+    let default_value = if default_value_tokens.is_empty() {
+        None
+    } else {
+        Some(join_tokens(&default_value_tokens))
+    };
+    (has_default_value, default_value)
+}
+
 fn parse_template_args(str: &str) -> Option<(String, Vec<String>)> {
     let mut level = 0;
     let mut current_str = String::new();
@@ -1699,3 +2684,133 @@ fn should_parse_template_args_works() {
     assert_eq!(parse_template_args("name<arg,arg2"), None);
     assert_eq!(parse_template_args("name<arg<arg3,arg4>,arg2"), None);
 }
+
+#[test]
+fn parse_macro_value_works() {
+    fn check(value: &str, value_type: CppBuiltInNumericType, result: CppConstantValue) {
+        let tokens = value.split(' ').map(ToString::to_string).collect_vec();
+        assert_eq!(
+            parse_macro_value(&tokens).unwrap(),
+            (CppType::BuiltInNumeric(value_type), result)
+        );
+    }
+
+    check(
+        "42",
+        CppBuiltInNumericType::Int,
+        CppConstantValue::SignedInteger(42),
+    );
+    check(
+        "( - 42 )",
+        CppBuiltInNumericType::Int,
+        CppConstantValue::SignedInteger(-42),
+    );
+    check(
+        "0x050d00",
+        CppBuiltInNumericType::Int,
+        CppConstantValue::SignedInteger(0x050d00),
+    );
+    check(
+        "0xffffffff",
+        CppBuiltInNumericType::UInt,
+        CppConstantValue::UnsignedInteger(0xffff_ffff),
+    );
+    check(
+        "010u",
+        CppBuiltInNumericType::UInt,
+        CppConstantValue::UnsignedInteger(8),
+    );
+    check(
+        "0u",
+        CppBuiltInNumericType::UInt,
+        CppConstantValue::UnsignedInteger(0),
+    );
+    check(
+        "0L",
+        CppBuiltInNumericType::Long,
+        CppConstantValue::SignedInteger(0),
+    );
+    check(
+        "0UL",
+        CppBuiltInNumericType::ULong,
+        CppConstantValue::UnsignedInteger(0),
+    );
+    check(
+        "0ull",
+        CppBuiltInNumericType::ULongLong,
+        CppConstantValue::UnsignedInteger(0),
+    );
+    check(
+        "0",
+        CppBuiltInNumericType::Int,
+        CppConstantValue::SignedInteger(0),
+    );
+    check(
+        "1ULL",
+        CppBuiltInNumericType::ULongLong,
+        CppConstantValue::UnsignedInteger(1),
+    );
+    check(
+        "3000000000",
+        CppBuiltInNumericType::LongLong,
+        CppConstantValue::SignedInteger(3_000_000_000),
+    );
+    check(
+        "1.5f",
+        CppBuiltInNumericType::Float,
+        CppConstantValue::FloatingPoint("1.5".to_string()),
+    );
+    check(
+        "- 1e3",
+        CppBuiltInNumericType::Double,
+        CppConstantValue::FloatingPoint("-1000.0".to_string()),
+    );
+
+    let tokens = vec!["\"text\"".to_string()];
+    assert!(parse_macro_value(&tokens).is_err());
+    let tokens = vec!["A".to_string(), "|".to_string(), "B".to_string()];
+    assert!(parse_macro_value(&tokens).is_err());
+}
+
+#[test]
+fn join_tokens_works() {
+    fn check(tokens: &str, result: &str) {
+        let tokens = tokens.split(' ').map(ToString::to_string).collect_vec();
+        assert_eq!(join_tokens(&tokens), result);
+    }
+
+    check("42", "42");
+    check("- 1", "-1");
+    check("nullptr", "nullptr");
+    check("QSize ( 0 , 0 )", "QSize(0, 0)");
+    check("QPoint ( - 1 , - 1 )", "QPoint(-1, -1)");
+    check(
+        "Qt :: AlignLeft | Qt :: AlignTop",
+        "Qt::AlignLeft | Qt::AlignTop",
+    );
+    check("std :: vector < int > ( )", "std::vector<int>()");
+    check("QString ( \"a\" ) . size ( )", "QString(\"a\").size()");
+}
+
+#[test]
+fn parse_default_value_works() {
+    fn check(tokens: &str, result: Option<&str>) {
+        let tokens = tokens.split(' ').map(ToString::to_string).collect_vec();
+        let (has_default_value, default_value) = parse_default_value(&tokens);
+        assert_eq!(has_default_value, result.is_some());
+        assert_eq!(default_value.as_ref().map(|s| s.as_str()), result);
+    }
+
+    check("int x", None);
+    check("int x = 1 ,", Some("1"));
+    check("QSize s = QSize ( 0 , 0 ) )", Some("QSize(0, 0)"));
+    check(
+        "std :: map < int , int > m = std :: map < int , int > ( ) ,",
+        Some("std::map<int, int>()"),
+    );
+    check(
+        "const QMap < int , QMap < int , int >> & m = QMap < int , QMap < int , int >> ( ) )",
+        Some("QMap<int, QMap<int, int>>()"),
+    );
+    check("bool b = 1 < 2 , int c", Some("1<2"));
+}
@@ -1,11 +1,13 @@
-use crate::cpp_data::{CppItem, CppPath, CppPathItem, CppTypeDeclaration};
+use crate::cpp_data::{CppItem, CppPath, CppPathItem, CppTypeDeclaration, CppVisibility};
 use crate::cpp_function::{CppFunction, CppFunctionArgument, CppOperator};
-use crate::cpp_type::CppType;
+use crate::cpp_parser::parse_type_strings;
+use crate::cpp_type::{CppTemplateParameter, CppType};
 use crate::database::{DatabaseClient, ItemWithSource};
 use crate::processor::ProcessorData;
 use log::{debug, trace};
 use ritual_common::errors::{bail, err_msg, Result};
 use ritual_common::utils::MapIfOk;
+use std::collections::HashMap;
 
 /// Returns true if `type1` is a known template instantiation.
 fn check_template_type(data: &ProcessorData<'_>, type1: &CppType) -> Result<()> {
@@ -48,6 +50,8 @@ pub fn instantiate_function(
         new_method.arguments.push(CppFunctionArgument {
             name: arg.name.clone(),
             has_default_value: arg.has_default_value,
+            default_value: arg.default_value.clone(),
+            type_alias: arg.type_alias.clone(),
             argument_type: arg.argument_type.instantiate(nested_level, arguments)?,
         });
     }
@@ -93,6 +97,7 @@ pub fn instantiate_function(
             *new_method.path.last_mut() = CppPathItem {
                 name: format!("operator {}", conversion_type.to_cpp_code(None)?),
                 template_arguments: None,
+                inline_namespaces: Vec::new(),
             };
         }
         trace!("success: {}", new_method.short_text());
@@ -182,6 +187,9 @@ fn instantiate_types(data: &mut ProcessorData<'_>) -> Result<()> {
                         .item
                         .path
                         .instantiate(substitution.nested_level, substitution.arguments)?,
+                    deprecation: type1.item.deprecation.clone(),
+                    is_deleted: type1.item.is_deleted,
+                    is_final: type1.item.is_final,
                 };
 
                 if data
@@ -283,7 +291,114 @@ pub fn instantiate_templates(data: &mut ProcessorData<'_>) -> Result<()> {
     Ok(())
 }
 
-/// Searches for template instantiations in this library's API,
+/// Returns the template parameter of `function` if it's a member function template
+/// with a single type parameter in a class that is not a template.
+pub fn member_function_template_parameter(function: &CppFunction) -> Option<&CppTemplateParameter> {
+    let member = function.member.as_ref()?;
+    if member.visibility != CppVisibility::Public || function.is_deleted {
+        return None;
+    }
+    let parent = function.path.parent().ok()?;
+    if parent
+        .items()
+        .iter()
+        .any(|item| item.template_arguments.is_some())
+    {
+        return None;
+    }
+    match function.path.last().template_arguments.as_ref()?.as_slice() {
+        [CppType::TemplateParameter(param)] if param.value_type.is_none() => Some(param),
+        _ => None,
+    }
+}
+
+/// Instantiates member function templates with a single type parameter
+/// (e.g. `QObject::findChild<T>`) with all class types and pointers to class types
+/// of this library and its dependencies. Instantiations of templates from the dependencies
+/// are only generated for this library's types.
+///
+/// Instantiations that don't satisfy the template's requirements
+/// are rejected later by the C++ checker.
+pub fn instantiate_template_functions(data: &mut ProcessorData<'_>) -> Result<()> {
+    if !data.config.instantiate_template_functions() {
+        return Ok(());
+    }
+    fn class_paths<'a>(items: impl Iterator<Item = &'a CppItem>) -> Vec<CppPath> {
+        items
+            .filter_map(|item| item.as_type_ref())
+            .filter(|type1| type1.kind.is_class() && !type1.is_deleted)
+            .map(|type1| type1.path.clone())
+            .filter(|path| !CppType::Class(path.clone()).is_or_contains_template_parameter())
+            .collect()
+    }
+    let local_classes = class_paths(data.db.cpp_items().map(|item| item.item));
+    let all_classes = class_paths(data.db.all_cpp_items().map(|item| item.item));
+
+    let mut existing_functions = HashMap::<CppPath, Vec<CppFunction>>::new();
+    for function in data
+        .db
+        .all_cpp_items()
+        .filter_map(|item| item.item.as_function_ref())
+    {
+        existing_functions
+            .entry(function.path.clone())
+            .or_default()
+            .push(function.clone());
+    }
+
+    let mut new_functions = Vec::new();
+    for item in data.db.all_cpp_items() {
+        let function = if let Some(f) = item.item.as_function_ref() {
+            f
+        } else {
+            continue;
+        };
+        let param = if let Some(param) = member_function_template_parameter(function) {
+            param
+        } else {
+            continue;
+        };
+        let classes = if item.id.crate_name() == data.db.crate_name() {
+            &all_classes
+        } else {
+            &local_classes
+        };
+        for class_path in classes {
+            let class_type = CppType::Class(class_path.clone());
+            for argument in &[
+                class_type.clone(),
+                CppType::new_pointer(false, class_type.clone()),
+            ] {
+                let new_function =
+                    match instantiate_function(function, param.nested_level, &[argument.clone()]) {
+                        Ok(f) => f,
+                        Err(err) => {
+                            trace!("failed to instantiate {}: {}", function.short_text(), err);
+                            continue;
+                        }
+                    };
+                let same_path_functions = existing_functions
+                    .entry(new_function.path.clone())
+                    .or_default();
+                if same_path_functions.iter().any(|f| f.is_same(&new_function)) {
+                    continue;
+                }
+                same_path_functions.push(new_function.clone());
+                new_functions.push(ItemWithSource::new(&item.id, new_function));
+            }
+        }
+    }
+    for new_function in new_functions {
+        data.add_cpp_item(
+            Some(new_function.source_id),
+            CppItem::Function(new_function.item),
+        )?;
+    }
+    Ok(())
+}
+
+/// Searches for template instantiations in this library's API
+/// and template instantiations requested in the config,
 /// excluding results that were already processed in dependencies.
 pub fn find_template_instantiations(data: &mut ProcessorData<'_>) -> Result<()> {
     fn check_type(type1: &CppType, data: &ProcessorData<'_>, result: &mut Vec<CppPath>) {
@@ -315,7 +430,25 @@ pub fn find_template_instantiations(data: &mut ProcessorData<'_>) -> Result<()>
             _ => {}
         }
     }
+    let config = data.config;
+    let requested_types = if config.template_instantiations().is_empty() {
+        Vec::new()
+    } else {
+        parse_type_strings(data, config.template_instantiations())?
+    };
+
     let mut result = Vec::new();
+    for type1 in &requested_types {
+        match type1 {
+            CppType::Class(path) if path.last().template_arguments.is_some() => {
+                check_type(type1, &data, &mut result);
+            }
+            _ => bail!(
+                "requested type is not a template class instantiation: {}",
+                type1.to_cpp_pseudo_code()
+            ),
+        }
+    }
     for item in data.db.cpp_items() {
         for type1 in item.item.all_involved_types() {
             check_type(&type1, &data, &mut result);
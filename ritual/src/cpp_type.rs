@@ -2,6 +2,7 @@
 
 use crate::cpp_data::CppPath;
 use ritual_common::errors::{bail, Result};
+use ritual_common::utils::MapIfOk;
 use serde_derive::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
@@ -86,6 +87,10 @@ pub struct CppTemplateParameter {
     pub index: usize,
     /// Declared name of this template parameter
     pub name: String,
+    /// Type of the value if this is a non-type template parameter
+    /// (e.g. `int` for `template<int N>`), or `None` for a type parameter.
+    #[serde(default)]
+    pub value_type: Option<Box<CppType>>,
 }
 
 /// Base C++ type. `CppType` can add indirection
@@ -115,11 +120,22 @@ pub enum CppType {
     TemplateParameter(CppTemplateParameter),
     /// Function pointer type
     FunctionPointer(CppFunctionPointerType),
+    /// Value of a non-type template argument, like `8` in `std::array<int, 8>`.
+    /// This variant is only valid inside template arguments.
+    IntegralConstant {
+        /// Type of the template parameter
+        value_type: Box<CppType>,
+        /// The value (`0` or `1` for `bool` parameters)
+        value: i64,
+    },
     PointerLike {
         kind: CppPointerLikeTypeKind,
         is_const: bool,
         target: Box<CppType>,
     },
+    /// Fixed-size array, like `char[32]`. Multidimensional arrays
+    /// are represented as nested arrays.
+    Array { element: Box<CppType>, len: usize },
 }
 
 impl CppBuiltInNumericType {
@@ -243,6 +259,30 @@ impl CppType {
         }
     }
 
+    /// Returns true if this is a fixed-size array.
+    pub fn is_array(&self) -> bool {
+        match *self {
+            CppType::Array { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if this is a value of a non-type template argument.
+    pub fn is_integral_constant(&self) -> bool {
+        match *self {
+            CppType::IntegralConstant { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if this is a pointer or a reference.
+    pub fn is_pointer_like(&self) -> bool {
+        match *self {
+            CppType::PointerLike { .. } => true,
+            _ => false,
+        }
+    }
+
     pub fn is_pointer(&self) -> bool {
         match self {
             CppType::PointerLike { kind, .. } => *kind == CppPointerLikeTypeKind::Pointer,
@@ -256,6 +296,7 @@ impl CppType {
         match self {
             CppType::TemplateParameter { .. } => true,
             CppType::PointerLike { target, .. } => target.is_or_contains_template_parameter(),
+            CppType::Array { element, .. } => element.is_or_contains_template_parameter(),
             CppType::FunctionPointer(type1) => {
                 type1.return_type.is_or_contains_template_parameter()
                     || type1
@@ -282,6 +323,7 @@ impl CppType {
                 self_params.nested_level == param.nested_level && self_params.index == param.index
             }
             CppType::PointerLike { target, .. } => target.contains_template_parameter(param),
+            CppType::Array { element, .. } => element.contains_template_parameter(param),
             CppType::FunctionPointer(type1) => {
                 type1.return_type.contains_template_parameter(param)
                     || type1
@@ -313,22 +355,49 @@ impl CppType {
             CppType::Enum { path }
             | CppType::SpecificNumeric(CppSpecificNumericType { path, .. })
             | CppType::PointerSizedInteger { path, .. } => path.to_cpp_code(),
-            CppType::Class(path) => path.to_cpp_code(),
+            CppType::Class(path) => {
+                if let Some(function_type) = self.as_std_function() {
+                    // the template argument is a function type, not a function pointer
+                    let mut arg_texts = function_type
+                        .arguments
+                        .iter()
+                        .map_if_ok(|arg| arg.to_cpp_code(None))?;
+                    if function_type.allows_variadic_arguments {
+                        arg_texts.push("...".to_string());
+                    }
+                    Ok(format!(
+                        "std::function< {}({}) >",
+                        function_type.return_type.to_cpp_code(None)?,
+                        arg_texts.join(", ")
+                    ))
+                } else {
+                    path.to_cpp_code()
+                }
+            }
             CppType::TemplateParameter { .. } => {
                 bail!("template parameters are not allowed in C++ code generator");
             }
+            CppType::IntegralConstant { value_type, value } => match &**value_type {
+                CppType::BuiltInNumeric(CppBuiltInNumericType::Bool) => {
+                    Ok(if *value == 0 { "false" } else { "true" }.to_string())
+                }
+                CppType::Enum { path } => {
+                    Ok(format!("static_cast<{}>({})", path.to_cpp_code()?, value))
+                }
+                _ => Ok(value.to_string()),
+            },
             CppType::FunctionPointer(CppFunctionPointerType {
                 return_type,
                 arguments,
                 allows_variadic_arguments,
             }) => {
-                if *allows_variadic_arguments {
-                    bail!("function pointers with variadic arguments are not supported");
-                }
                 let mut arg_texts = Vec::new();
                 for arg in arguments {
                     arg_texts.push(arg.to_cpp_code(None)?);
                 }
+                if *allows_variadic_arguments {
+                    arg_texts.push("...".to_string());
+                }
                 if let Some(function_pointer_inner_text) = function_pointer_inner_text {
                     Ok(format!(
                         "{} (*{})({})",
@@ -344,16 +413,49 @@ impl CppType {
                 kind,
                 is_const,
                 target,
-            } => Ok(format!(
-                "{}{} {}",
-                target.to_cpp_code(function_pointer_inner_text)?,
-                if *is_const { " const" } else { "" },
-                match *kind {
-                    CppPointerLikeTypeKind::Pointer => "*",
-                    CppPointerLikeTypeKind::Reference => "&",
-                    CppPointerLikeTypeKind::RValueReference => "&&",
+            } => {
+                if target.is_array() {
+                    // declarator syntax (`float (*)[4]`) can't be used in all contexts,
+                    // so type traits are used instead
+                    let mut target_code = target.to_cpp_code(None)?;
+                    if *is_const {
+                        target_code = format!("std::add_const< {} >::type", target_code);
+                    }
+                    return Ok(format!(
+                        "std::add_{}< {} >::type",
+                        match *kind {
+                            CppPointerLikeTypeKind::Pointer => "pointer",
+                            CppPointerLikeTypeKind::Reference => "lvalue_reference",
+                            CppPointerLikeTypeKind::RValueReference => "rvalue_reference",
+                        },
+                        target_code
+                    ));
                 }
-            )),
+                Ok(format!(
+                    "{}{} {}",
+                    target.to_cpp_code(function_pointer_inner_text)?,
+                    if *is_const { " const" } else { "" },
+                    match *kind {
+                        CppPointerLikeTypeKind::Pointer => "*",
+                        CppPointerLikeTypeKind::Reference => "&",
+                        CppPointerLikeTypeKind::RValueReference => "&&",
+                    }
+                ))
+            }
+            CppType::Array { element, len } => {
+                // sizes of nested arrays follow the innermost element type
+                let mut sizes = format!("[{}]", len);
+                let mut element = element;
+                while let CppType::Array {
+                    element: inner_element,
+                    len,
+                } = &**element
+                {
+                    sizes = format!("{}[{}]", sizes, len);
+                    element = inner_element;
+                }
+                Ok(format!("{}{}", element.to_cpp_code(None)?, sizes))
+            }
         }
     }
 
@@ -401,7 +503,9 @@ impl CppType {
             | CppType::Enum { path }
             | CppType::Class(path) => path.ascii_caption(),
             CppType::TemplateParameter(param) => param.name.to_string(),
+            CppType::IntegralConstant { value, .. } => value.to_string().replace('-', "minus_"),
             CppType::FunctionPointer(_) => "fn".into(),
+            CppType::Array { element, len } => format!("{}_array_{}", element.ascii_caption(), len),
             CppType::PointerLike {
                 kind,
                 is_const,
@@ -442,6 +546,23 @@ impl CppType {
             None
         }
     }
+
+    /// Returns the function type `R(Args...)` if this type is `std::function<R(Args...)>`.
+    pub fn as_std_function(&self) -> Option<&CppFunctionPointerType> {
+        if let CppType::Class(path) = self {
+            if path.items().len() == 2 && path.items()[0].name == "std" {
+                let last = path.last();
+                if last.name == "function" {
+                    if let Some(args) = &last.template_arguments {
+                        if args.len() == 1 {
+                            return args[0].as_function_pointer();
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
 }
 
 /// Context of usage for a C++ type
@@ -506,6 +627,10 @@ impl CppType {
                 is_const: *is_const,
                 target: Box::new(target.instantiate(nested_level, template_arguments1)?),
             }),
+            CppType::Array { element, len } => Ok(CppType::Array {
+                element: Box::new(element.instantiate(nested_level, template_arguments1)?),
+                len: *len,
+            }),
             _ => Ok(self.clone()),
         }
     }
@@ -0,0 +1,331 @@
+//! Generates C++ subclasses that allow to override virtual functions
+//! of the library's classes from Rust.
+
+use crate::cpp_data::{CppPath, CppTypeDeclarationKind, CppVisibility};
+use crate::cpp_ffi_data::{CppFfiItem, CppVirtualFunctionOverride, CppVirtualSubclass};
+use crate::cpp_ffi_generator::{callback_ffi_types, FfiNameProvider};
+use crate::cpp_function::CppFunction;
+use crate::cpp_type::{CppFunctionPointerType, CppPointerLikeTypeKind, CppType};
+use crate::processor::ProcessorData;
+use itertools::Itertools;
+use log::trace;
+use ritual_common::errors::{bail, err_msg, Result};
+use std::collections::{HashMap, HashSet};
+use std::iter::once;
+
+/// Returns true if `function` overrides `other` (or vice versa).
+fn is_same_virtual_function(function: &CppFunction, other: &CppFunction) -> bool {
+    function.path.last().name == other.path.last().name
+        && function
+            .arguments
+            .iter()
+            .map(|arg| &arg.argument_type)
+            .eq(other.arguments.iter().map(|arg| &arg.argument_type))
+        && function.member.as_ref().map(|m| m.is_const) == other.member.as_ref().map(|m| m.is_const)
+}
+
+/// Member functions and bases of classes, indexed by class path.
+#[derive(Default)]
+struct ClassIndex {
+    functions: HashMap<CppPath, Vec<CppFunction>>,
+    /// Public and protected bases
+    bases: HashMap<CppPath, Vec<CppPath>>,
+}
+
+impl ClassIndex {
+    fn new(data: &ProcessorData<'_>) -> Self {
+        let mut index = ClassIndex::default();
+        for item in data.db.all_cpp_items() {
+            if let Some(function) = item.item.as_function_ref() {
+                if let Ok(class_path) = function.class_path() {
+                    index
+                        .functions
+                        .entry(class_path)
+                        .or_default()
+                        .push(function.clone());
+                }
+            } else if let Some(base) = item.item.as_base_ref() {
+                if base.visibility != CppVisibility::Private {
+                    index
+                        .bases
+                        .entry(base.derived_class_type.clone())
+                        .or_default()
+                        .push(base.base_class_type.clone());
+                }
+            }
+        }
+        index
+    }
+
+    fn functions(&self, class_path: &CppPath) -> &[CppFunction] {
+        self.functions.get(class_path).map_or(&[], |v| &v[..])
+    }
+
+    fn bases(&self, class_path: &CppPath) -> &[CppPath] {
+        self.bases.get(class_path).map_or(&[], |v| &v[..])
+    }
+}
+
+/// Collects virtual functions of `class_path` and its bases,
+/// starting from the most derived class.
+fn collect_virtual_functions(
+    index: &ClassIndex,
+    class_path: &CppPath,
+    output: &mut Vec<CppFunction>,
+) {
+    let functions = index
+        .functions(class_path)
+        .iter()
+        .filter(|f| f.is_virtual() && !f.is_destructor());
+
+    for function in functions {
+        if !output.iter().any(|f| is_same_virtual_function(f, function)) {
+            output.push(function.clone());
+        }
+    }
+
+    for base in index.bases(class_path) {
+        collect_virtual_functions(index, base, output);
+    }
+}
+
+/// Returns names of `functions` used in the generated methods of the subclass.
+/// Names of overloaded functions are suffixed with captions of their argument types.
+fn override_names(functions: &[CppFunction]) -> Vec<String> {
+    let mut names = HashSet::new();
+    functions
+        .iter()
+        .map(|function| {
+            let name = &function.path.last().name;
+            let is_overloaded = functions
+                .iter()
+                .filter(|f| &f.path.last().name == name)
+                .count()
+                > 1;
+            let mut caption = name.clone();
+            if is_overloaded {
+                for arg in &function.arguments {
+                    caption.push('_');
+                    caption.push_str(&arg.argument_type.ascii_caption());
+                }
+                if function.member.as_ref().map_or(false, |m| m.is_const) {
+                    caption.push_str("_const");
+                }
+            }
+            let mut unique_name = caption.clone();
+            let mut number = 1;
+            while !names.insert(unique_name.clone()) {
+                number += 1;
+                unique_name = format!("{}{}", caption, number);
+            }
+            unique_name
+        })
+        .collect()
+}
+
+/// Generates information required to override `function` in a subclass.
+/// `name` is used in the names of the generated methods.
+pub fn generate_override(
+    function: &CppFunction,
+    name: String,
+) -> Result<CppVirtualFunctionOverride> {
+    let member = function
+        .member
+        .as_ref()
+        .ok_or_else(|| err_msg("not a class member"))?;
+    if member.visibility == CppVisibility::Private {
+        bail!("private virtual functions are not supported");
+    }
+    if function.is_operator() {
+        bail!("virtual operators are not supported");
+    }
+    if function.allows_variadic_arguments {
+        bail!("variadic virtual functions are not supported");
+    }
+    if function.path.last().template_arguments.is_some() {
+        bail!("template functions are not supported");
+    }
+
+    let argument_types = function
+        .arguments
+        .iter()
+        .map(|arg| arg.argument_type.clone())
+        .collect_vec();
+    let (arguments, return_type) = callback_ffi_types(&argument_types, &function.return_type)?;
+
+    let void_ptr = CppType::PointerLike {
+        is_const: false,
+        kind: CppPointerLikeTypeKind::Pointer,
+        target: Box::new(CppType::Void),
+    };
+    let function_type = CppFunctionPointerType {
+        return_type: Box::new(return_type.ffi_type().clone()),
+        arguments: once(void_ptr)
+            .chain(arguments.iter().map(|t| t.ffi_type().clone()))
+            .collect(),
+        allows_variadic_arguments: false,
+    };
+
+    Ok(CppVirtualFunctionOverride {
+        function: function.clone(),
+        arguments,
+        return_type,
+        function_type,
+        name,
+    })
+}
+
+/// Generates a subclass of `class_path` that allows to override its virtual functions.
+fn generate_virtual_subclass(
+    index: &ClassIndex,
+    class_path: &CppPath,
+    name_provider: &mut FfiNameProvider,
+) -> Result<CppVirtualSubclass> {
+    let class_functions = index.functions(class_path);
+
+    if class_functions
+        .iter()
+        .any(|f| f.is_destructor() && (f.is_private() || f.is_deleted))
+    {
+        bail!("destructor is private or deleted");
+    }
+
+    let constructors = class_functions
+        .iter()
+        .filter(|f| {
+            f.is_constructor() && !f.is_private() && !f.is_deleted && !f.is_copy_constructor()
+        })
+        .filter(|f| {
+            // move constructor
+            !(f.arguments.len() == 1
+                && f.arguments[0].argument_type
+                    == CppType::PointerLike {
+                        is_const: false,
+                        kind: CppPointerLikeTypeKind::RValueReference,
+                        target: Box::new(CppType::Class(class_path.clone())),
+                    })
+        })
+        .filter(|f| {
+            !f.arguments
+                .iter()
+                .any(|arg| arg.argument_type.is_or_contains_template_parameter())
+        })
+        .cloned()
+        .collect_vec();
+    if constructors.is_empty() {
+        bail!("no accessible constructors");
+    }
+
+    let mut functions = Vec::new();
+    collect_virtual_functions(index, class_path, &mut functions);
+    // `final` functions are collected to hide the functions they override,
+    // but they can't be overridden again
+    functions.retain(|f| !f.member.as_ref().map_or(false, |m| m.is_final));
+
+    let mut virtual_functions = Vec::new();
+    for (function, name) in functions.iter().zip(override_names(&functions)) {
+        match generate_override(function, name) {
+            Ok(r) => virtual_functions.push(r),
+            Err(err) => {
+                if function
+                    .member
+                    .as_ref()
+                    .map_or(false, |m| m.is_pure_virtual)
+                {
+                    bail!(
+                        "can't override pure virtual function {}: {}",
+                        function.short_text(),
+                        err
+                    );
+                }
+                trace!(
+                    "skipping virtual function {}: {}",
+                    function.short_text(),
+                    err
+                );
+            }
+        }
+    }
+    if virtual_functions.is_empty() {
+        bail!("no virtual functions");
+    }
+
+    let class_path_caption = CppType::Class(class_path.clone()).ascii_caption();
+    Ok(CppVirtualSubclass {
+        base_class_path: class_path.clone(),
+        class_path: name_provider.create_path(&format!("subclass_{}", class_path_caption)),
+        constructors,
+        virtual_functions,
+    })
+}
+
+pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
+    if !data.config.generate_virtual_subclasses() {
+        return Ok(());
+    }
+
+    let mut name_provider = FfiNameProvider::new(data);
+
+    let mut classes = Vec::new();
+    for item in data.db.cpp_items() {
+        let declaration = if let Some(item) = item.item.as_type_ref() {
+            item
+        } else {
+            continue;
+        };
+        if declaration.kind != CppTypeDeclarationKind::Class {
+            continue;
+        }
+        if declaration.path.last().template_arguments.is_some() {
+            // template instantiations are not supported yet
+            continue;
+        }
+        if declaration.is_final {
+            continue;
+        }
+        if data.db.source_ffi_item(&item.id)?.is_some() {
+            // don't subclass generated classes
+            continue;
+        }
+        classes.push(declaration.path.clone());
+    }
+
+    let index = ClassIndex::new(data);
+    let existing_subclasses = data
+        .db
+        .all_ffi_items()
+        .filter_map(|item| item.item.as_virtual_subclass_ref())
+        .map(|item| item.base_class_path.clone())
+        .collect::<HashSet<_>>();
+    for class_path in classes {
+        if existing_subclasses.contains(&class_path) {
+            trace!(
+                "virtual subclass already exists: {}",
+                class_path.to_cpp_pseudo_code()
+            );
+            continue;
+        }
+
+        match generate_virtual_subclass(&index, &class_path, &mut name_provider) {
+            Ok(subclass) => {
+                let id = data
+                    .db
+                    .add_ffi_item(None, CppFfiItem::VirtualSubclass(subclass))?;
+                if id.is_some() {
+                    trace!(
+                        "adding virtual subclass for {}",
+                        class_path.to_cpp_pseudo_code()
+                    );
+                }
+            }
+            Err(err) => {
+                trace!(
+                    "failed to add virtual subclass for {}: {}",
+                    class_path.to_cpp_pseudo_code(),
+                    err
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -274,8 +274,15 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
         &global_header_name,
     )?;
 
-    let file = create_file(c_lib_path.join("sized_types.cxx"))?;
-    generate_cpp_type_size_requester(data.db, data.config.include_directives(), file)?;
+    let has_sized_types = data
+        .db
+        .rust_items()
+        .filter_map(|item| item.item.as_struct_ref())
+        .any(|item| item.kind.is_sized_type());
+    if has_sized_types {
+        let file = create_file(c_lib_path.join("sized_types.cxx"))?;
+        generate_cpp_type_size_requester(data.db, data.config.include_directives(), file)?;
+    }
 
     rust_code_generator::generate(
         &data.db,
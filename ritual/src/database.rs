@@ -964,6 +964,19 @@ impl DatabaseClient {
         self.all_databases().flat_map(|d| d.db.ffi_items())
     }
 
+    pub fn all_rust_items(&self) -> impl Iterator<Item = DbItem<&RustItem>> {
+        self.all_databases().flat_map(|d| d.db.rust_items())
+    }
+
+    /// Returns C++ items with the specified path from the current database and its dependencies.
+    pub fn find_cpp_items_by_path<'a>(
+        &'a self,
+        cpp_path: &'a CppPath,
+    ) -> impl Iterator<Item = DbItem<&'a CppItem>> {
+        self.all_databases()
+            .flat_map(move |db| db.filter_by_cpp_path(cpp_path))
+    }
+
     pub fn find_rust_items_for_cpp_path(
         &self,
         cpp_path: &CppPath,
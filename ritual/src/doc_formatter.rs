@@ -3,17 +3,23 @@
 
 #![allow(dead_code)]
 
+use crate::cpp_data::CppItem;
 use crate::cpp_ffi_data::{CppFfiFunctionKind, CppFfiItem, CppFieldAccessorType};
+use crate::cpp_function::CppFunction;
+use crate::cpp_operator::CppOperator;
 use crate::cpp_type::CppType;
 use crate::database::{DatabaseClient, DbItem, DocItem};
 use crate::rust_code_generator::rust_type_to_code;
 use crate::rust_info::{
-    RustEnumValue, RustFunction, RustFunctionKind, RustModule, RustModuleKind, RustQtReceiverType,
-    RustSpecialModuleKind, RustStruct, RustStructKind, RustWrapperTypeKind,
+    RustConstant, RustEnumValue, RustFunction, RustFunctionKind, RustModule, RustModuleKind,
+    RustOptionsStructField, RustQtReceiverType, RustSpecialModuleKind, RustStruct, RustStructKind,
+    RustTrait, RustTypeAlias, RustWrapperTypeKind,
 };
+use crate::rust_type::RustPath;
 use itertools::Itertools;
 use ritual_common::errors::{bail, err_msg, Result};
 use std::fmt::Write;
+use std::iter::once;
 
 pub fn wrap_inline_cpp_code(code: &str) -> String {
     format!("<span style='color: green;'>```{}```</span>", code)
@@ -49,6 +55,9 @@ pub fn module_doc(module: DbItem<&RustModule>, database: &DatabaseClient) -> Res
             RustSpecialModuleKind::Ops => {
                 write!(output, "Functions that provide access to C++ operators")?;
             }
+            RustSpecialModuleKind::Macros => {
+                write!(output, "Constants defined by C++ preprocessor macros")?;
+            }
             RustSpecialModuleKind::SizedTypes => {
                 write!(
                     output,
@@ -95,6 +104,24 @@ fn first_phrase(html: &str) -> &str {
 pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Result<String> {
     let mut output = String::new();
 
+    if let RustStructKind::OptionsStruct(_) = &type1.item.kind {
+        // the source C++ function is documented in the `_with_options` function
+        let cpp_item = database
+            .source_cpp_item(&type1.id)?
+            .ok_or_else(|| err_msg("source cpp item not found"))?;
+        let cpp_function = cpp_item
+            .item
+            .as_function_ref()
+            .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+        write!(
+            output,
+            "Values of the arguments of C++ function {} that have default values.\n\n\
+             `Default` implementation of this struct uses the default values declared in C++.",
+            wrap_inline_cpp_code(&cpp_function.path.to_cpp_pseudo_code())
+        )?;
+        return Ok(output);
+    }
+
     let doc_item = database.find_doc_for(&type1.id)?;
     if let Some(doc_item) = &doc_item {
         if !doc_item.item.html.is_empty() {
@@ -115,7 +142,7 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
                 .to_cpp_pseudo_code();
 
             match kind {
-                RustWrapperTypeKind::EnumWrapper => {
+                RustWrapperTypeKind::EnumWrapper { .. } => {
                     writeln!(
                         output,
                         "C++ enum: {}.\n",
@@ -130,7 +157,13 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
                     )?;
                 }
                 RustWrapperTypeKind::MovableClassWrapper { .. } => {
-                    // not supported now
+                    writeln!(
+                        output,
+                        "C++ class: {}.\n\nObjects of this type are stored \
+                         inline (e.g. on the stack) and moved by value. \
+                         The C++ destructor is called when the object is dropped.\n",
+                        wrap_inline_cpp_code(&cpp_type_code)
+                    )?;
                 }
             }
 
@@ -168,7 +201,9 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
                     .item;
 
                 let cpp_signal_arguments = match ffi_item {
-                    CppFfiItem::Function(_) => bail!("invalid source ffi item type"),
+                    CppFfiItem::Function(_) | CppFfiItem::VirtualSubclass(_) => {
+                        bail!("invalid source ffi item type")
+                    }
                     CppFfiItem::QtSlotWrapper(w) => &w.signal_arguments,
                     CppFfiItem::QtSignalWrapper(w) => &w.signal_arguments,
                 };
@@ -220,6 +255,7 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
         }
         // private struct, no doc needed
         RustStructKind::SizedType(_) => {}
+        RustStructKind::OptionsStruct(_) => unreachable!(),
     };
 
     if let Some(doc_item) = doc_item {
@@ -228,6 +264,10 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
     Ok(output)
 }
 
+pub fn options_struct_field_doc(field: &RustOptionsStructField) -> String {
+    format!("Default: {}.", wrap_inline_cpp_code(&field.default_value))
+}
+
 pub fn enum_value_doc(value: DbItem<&RustEnumValue>, database: &DatabaseClient) -> Result<String> {
     let cpp_item = database
         .source_cpp_item(&value.id)?
@@ -250,6 +290,172 @@ pub fn enum_value_doc(value: DbItem<&RustEnumValue>, database: &DatabaseClient)
     Ok(doc)
 }
 
+pub fn constant_doc(constant: DbItem<&RustConstant>, database: &DatabaseClient) -> Result<String> {
+    let cpp_item = database
+        .source_cpp_item(&constant.id)?
+        .ok_or_else(|| err_msg("source cpp item not found"))?
+        .item;
+    let cpp_path = cpp_item
+        .path()
+        .ok_or_else(|| err_msg("cpp item expected to have path"))?;
+
+    let code = if cpp_item.as_macro_constant_ref().is_some() {
+        format!(
+            "#define {} {}",
+            cpp_path.to_cpp_pseudo_code(),
+            constant.item.value
+        )
+    } else {
+        format!(
+            "{} = {}",
+            cpp_path.to_cpp_pseudo_code(),
+            constant.item.value
+        )
+    };
+    let mut doc = format!("C++ constant: {}", wrap_inline_cpp_code(&code));
+    if let Some(doc_item) = database.find_doc_for(&constant.id)? {
+        doc = format!("{} ({})", doc_item.item.html, doc);
+    }
+    Ok(doc)
+}
+
+pub fn type_alias_doc(alias: DbItem<&RustTypeAlias>, database: &DatabaseClient) -> Result<String> {
+    let cpp_item = database
+        .source_cpp_item(&alias.id)?
+        .ok_or_else(|| err_msg("source cpp item not found"))?
+        .item
+        .as_type_alias_ref()
+        .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+
+    let mut output = String::new();
+    let doc_item = database.find_doc_for(&alias.id)?;
+    if let Some(doc_item) = &doc_item {
+        if !doc_item.item.html.is_empty() {
+            writeln!(output, "{}\n", first_phrase(&doc_item.item.html))?;
+        }
+    }
+    writeln!(
+        output,
+        "C++ type alias: {}.\n",
+        wrap_inline_cpp_code(&format!(
+            "{} = {}",
+            cpp_item.path.to_cpp_pseudo_code(),
+            cpp_item.target_type.to_cpp_pseudo_code()
+        ))
+    )?;
+    if let Some(doc_item) = doc_item {
+        write!(output, "{}", format_doc_item(doc_item.item))?;
+    }
+    Ok(output)
+}
+
+pub fn trait_doc(rust_trait: DbItem<&RustTrait>, database: &DatabaseClient) -> Result<String> {
+    let cpp_item = database
+        .source_cpp_item(&rust_trait.id)?
+        .ok_or_else(|| err_msg("source cpp item not found"))?;
+    let is_from_other_crate = cpp_item.id.crate_name() != database.crate_name();
+
+    if let Some(subclass) = database
+        .source_ffi_item(&cpp_item.id)?
+        .and_then(|item| item.item.as_virtual_subclass_ref())
+    {
+        return Ok(format!(
+            "Virtual functions of C++ class {} that can be overridden in a subclass.\n\n\
+             Pass an implementation of this trait to `set_overrides` of the subclass \
+             to override the functions. Functions that are not implemented \
+             call the base class implementation.",
+            wrap_inline_cpp_code(&subclass.base_class_path.to_cpp_pseudo_code())
+        ));
+    }
+
+    match cpp_item.item {
+        CppItem::Function(cpp_function)
+            if cpp_function.operator == Some(CppOperator::FunctionCall) =>
+        {
+            Ok(format!(
+                "Argument types accepted by C++ {}.\n\n\
+                 A single argument is passed as is, and multiple arguments are passed as a tuple.",
+                wrap_inline_cpp_code(&format!(
+                    "{}::operator()",
+                    cpp_function.class_path()?.to_cpp_pseudo_code()
+                ))
+            ))
+        }
+        CppItem::Function(cpp_function) => Ok(format!(
+            "Types that can be used as the template argument of C++ function {}.",
+            wrap_inline_cpp_code(&cpp_function.short_text())
+        )),
+        CppItem::Type(declaration) if is_from_other_crate => Ok(format!(
+            "Methods of C++ class {} that are added by this crate.\n\n\
+             These methods are instantiations of member function templates of the class \
+             that use types of this crate.",
+            wrap_inline_cpp_code(&declaration.path.to_cpp_pseudo_code())
+        )),
+        CppItem::Type(declaration) => Ok(format!(
+            "Protected methods and fields of C++ class {}.\n\n\
+             Protected members are intended to be used only by the class and its subclasses. \
+             Calling them from other places may break invariants of the class.",
+            wrap_inline_cpp_code(&declaration.path.to_cpp_pseudo_code())
+        )),
+        _ => bail!("invalid source cpp item type"),
+    }
+}
+
+/// Returns relative URL of the rustdoc page of `alias` for a page
+/// of an item located in the module `module_path`.
+fn type_alias_url(alias: &RustTypeAlias, module_path: &RustPath) -> Result<String> {
+    let alias_module_path = alias.path.parent()?;
+    let common_len = module_path
+        .parts()
+        .iter()
+        .zip(alias_module_path.parts())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut url = "../".repeat(module_path.parts().len() - common_len);
+    for part in &alias_module_path.parts()[common_len..] {
+        write!(url, "{}/", part)?;
+    }
+    write!(url, "type.{}.html", alias.path.last())?;
+    Ok(url)
+}
+
+/// Returns links to the Rust type aliases corresponding to the C++ type aliases
+/// used in the signature of `cpp_function`. Only aliases of the current crate are included.
+fn type_alias_links(
+    cpp_function: &CppFunction,
+    function: &RustFunction,
+    database: &DatabaseClient,
+) -> Result<Vec<String>> {
+    // methods are documented on the page of their struct
+    let mut module_path = function.path.parent()?;
+    if database
+        .find_rust_item(&module_path)
+        .map_or(false, |item| item.item.as_struct_ref().is_some())
+    {
+        module_path = module_path.parent()?;
+    }
+
+    let cpp_aliases = once(&cpp_function.return_type_alias)
+        .chain(cpp_function.arguments.iter().map(|arg| &arg.type_alias))
+        .filter_map(Option::as_ref)
+        .unique();
+    let mut links = Vec::new();
+    for cpp_alias in cpp_aliases {
+        let alias = database
+            .find_rust_items_for_cpp_path(cpp_alias, false)
+            .ok()
+            .and_then(|mut items| items.find_map(|item| item.item.as_type_alias_ref()));
+        if let Some(alias) = alias {
+            links.push(format!(
+                "[`{}`]({})",
+                alias.path.last(),
+                type_alias_url(alias, &module_path)?
+            ));
+        }
+    }
+    Ok(links)
+}
+
 fn format_maybe_link(url: &Option<String>, text: &str) -> String {
     if let Some(url) = url {
         format!("<a href=\"{}\">{}</a>", url, text)
@@ -369,50 +575,83 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
 
             match &cpp_ffi_function.kind {
                 CppFfiFunctionKind::Function => {
-                    let cpp_item = cpp_item
+                    let cpp_function = cpp_item
                         .item
                         .as_function_ref()
                         .ok_or_else(|| err_msg("invalid source cpp item type"))?;
                     write!(
                         output,
                         "Calls C++ function: {}.\n\n",
-                        wrap_inline_cpp_code(&cpp_item.short_text())
+                        wrap_inline_cpp_code(&cpp_function.short_text())
                     )?;
 
-                    // TODO: detect omitted arguments using source_id
-                    /*if let Some(arguments_before_omitting) =
-                        &cpp_function.doc.arguments_before_omitting
-                    {
-                        // TODO: handle singular/plural form
-                        doc.push(format!(
-                            "This version of the function omits some arguments ({}).\n\n",
-                            arguments_before_omitting.len() - cpp_function.arguments.len()
-                        ));
-                    }*/
+                    let alias_links = if is_trait_impl {
+                        // trait impls don't use the aliases
+                        Vec::new()
+                    } else {
+                        type_alias_links(cpp_function, function.item, database)?
+                    };
+                    if !alias_links.is_empty() {
+                        write!(
+                            output,
+                            "Type aliases used in the C++ signature: {}.\n\n",
+                            alias_links.join(", ")
+                        )?;
+                    }
+
+                    if let Some(source_id) = &cpp_item.source_id {
+                        let source_function = database
+                            .cpp_item(source_id)?
+                            .item
+                            .as_function_ref()
+                            .filter(|f| {
+                                f.path == cpp_function.path
+                                    && f.arguments.len() > cpp_function.arguments.len()
+                            })
+                            .cloned();
+                        if let Some(source_function) = source_function {
+                            let omitted = source_function.arguments[cpp_function.arguments.len()..]
+                                .iter()
+                                .map(|arg| {
+                                    let value = arg
+                                        .default_value
+                                        .as_ref()
+                                        .map_or("…", |value| value.as_str());
+                                    wrap_inline_cpp_code(&format!("{} = {}", arg.name, value))
+                                })
+                                .join(", ");
+                            write!(
+                                output,
+                                "This version of the function uses default values \
+                                 of the omitted arguments: {}.\n\n",
+                                omitted
+                            )?;
+                        }
+                    }
                 }
                 CppFfiFunctionKind::FieldAccessor { accessor_type } => {
-                    let cpp_item = cpp_item
-                        .item
-                        .as_field_ref()
-                        .ok_or_else(|| err_msg("invalid source cpp item type"))?;
-                    let field_text =
-                        wrap_inline_cpp_code(&cpp_item.path.last().to_cpp_pseudo_code());
+                    let (path, kind_text) = match &cpp_item.item {
+                        CppItem::ClassField(field) => (&field.path, "field"),
+                        CppItem::Variable(variable) => (&variable.path, "variable"),
+                        _ => bail!("invalid source cpp item type"),
+                    };
+                    let field_text = format!(
+                        "{} {}",
+                        wrap_inline_cpp_code(&path.last().to_cpp_pseudo_code()),
+                        kind_text
+                    );
                     match *accessor_type {
                         CppFieldAccessorType::CopyGetter => {
-                            write!(output, "Returns the value of the {} field.", field_text)?;
+                            write!(output, "Returns the value of the {}.", field_text)?;
                         }
                         CppFieldAccessorType::ConstRefGetter => {
-                            write!(output, "Returns a reference to the {} field.", field_text)?;
+                            write!(output, "Returns a reference to the {}.", field_text)?;
                         }
                         CppFieldAccessorType::MutRefGetter => {
-                            write!(
-                                output,
-                                "Returns a mutable reference to the {} field.",
-                                field_text
-                            )?;
+                            write!(output, "Returns a mutable reference to the {}.", field_text)?;
                         }
                         CppFieldAccessorType::Setter => {
-                            write!(output, "Sets the value of the {} field.", field_text)?;
+                            write!(output, "Sets the value of the {}.", field_text)?;
                         }
                     };
                 }
@@ -442,6 +681,111 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
         }
         // FFI functions are private
         RustFunctionKind::FfiFunction => {}
+        RustFunctionKind::OptionsWrapper(data) => {
+            let cpp_function = cpp_item
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            let options_path = &function
+                .item
+                .arguments
+                .last()
+                .ok_or_else(|| err_msg("options argument not found"))?
+                .argument_type
+                .api_type()
+                .as_common()?
+                .path;
+            write!(
+                output,
+                "Calls C++ function: {}.\n\n\
+                 Same as `{}`, but the arguments that have default values are \
+                 taken from `options`. Use `{}::default()` and set only the fields \
+                 you need to change.\n\n",
+                wrap_inline_cpp_code(&cpp_function.short_text()),
+                data.function_path.last(),
+                options_path.last(),
+            )?;
+        }
+        RustFunctionKind::TemplateWrapper(data) => {
+            let cpp_function = cpp_item
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            if cpp_function.operator == Some(CppOperator::FunctionCall) {
+                write!(
+                    output,
+                    "Calls C++ {}.\n\n\
+                     `args` is the only argument or a tuple of arguments of the chosen overload. \
+                     See `{}` implementations for the list of supported overloads.\n\n",
+                    wrap_inline_cpp_code(&format!(
+                        "{}::operator()",
+                        cpp_function.class_path()?.to_cpp_pseudo_code()
+                    )),
+                    data.trait_path.last(),
+                )?;
+            } else {
+                write!(
+                    output,
+                    "Calls C++ function template: {}.\n\n\
+                     The template argument is specified by `T`. \
+                     See `{}` implementations for the list of supported types.\n\n",
+                    wrap_inline_cpp_code(&cpp_function.short_text()),
+                    data.trait_path.last(),
+                )?;
+            }
+        }
+        RustFunctionKind::OverloadWrapper(_) => {
+            let cpp_function = cpp_item
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            write!(
+                output,
+                "Calls C++ function: {}.\n\n",
+                wrap_inline_cpp_code(&cpp_function.short_text())
+            )?;
+        }
+        RustFunctionKind::VirtualFunction(data) => {
+            let setter = cpp_item
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            let virtual_function = database
+                .source_ffi_item(&cpp_item.id)?
+                .and_then(|item| item.item.as_virtual_subclass_ref())
+                .and_then(|subclass| subclass.find_override(setter))
+                .ok_or_else(|| err_msg("virtual function not found"))?;
+            write!(
+                output,
+                "Called when C++ virtual function {} is called on `object`.\n\n",
+                wrap_inline_cpp_code(&virtual_function.function.short_text())
+            )?;
+            if data.base_function_path.is_some() {
+                write!(
+                    output,
+                    "The default implementation calls the base class implementation.\n\n"
+                )?;
+            } else {
+                write!(
+                    output,
+                    "The function is pure virtual in C++, so it must be implemented.\n\n"
+                )?;
+            }
+        }
+        RustFunctionKind::OverridesSetter(data) => {
+            let subclass = database
+                .source_ffi_item(&cpp_item.id)?
+                .and_then(|item| item.item.as_virtual_subclass_ref())
+                .ok_or_else(|| err_msg("virtual subclass not found"))?;
+            write!(
+                output,
+                "Overrides virtual functions of C++ class {} with the functions \
+                 of `{}` implemented by `overrides`.\n\n\
+                 `overrides` is dropped when the object is deleted.\n\n",
+                wrap_inline_cpp_code(&subclass.base_class_path.to_cpp_pseudo_code()),
+                data.trait_path.last()
+            )?;
+        }
     }
     if let Some(doc_item) = database.find_doc_for(&function.id)? {
         write!(output, "{}", format_doc_item(doc_item.item))?;
@@ -26,6 +26,7 @@ mod cpp_operator;
 pub mod cpp_parser;
 pub mod cpp_template_instantiator;
 pub mod cpp_type;
+mod cpp_virtual_subclasses;
 mod crate_writer;
 pub mod database;
 mod doc_formatter;
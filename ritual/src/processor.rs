@@ -5,7 +5,7 @@ use crate::database::{DatabaseClient, ItemId};
 use crate::workspace::Workspace;
 use crate::{
     cpp_casts, cpp_checker, cpp_ffi_generator, cpp_implicit_methods, cpp_omitting_arguments,
-    cpp_parser, cpp_template_instantiator, crate_writer, rust_generator,
+    cpp_parser, cpp_template_instantiator, cpp_virtual_subclasses, crate_writer, rust_generator,
 };
 use itertools::Itertools;
 use log::debug;
@@ -105,6 +105,10 @@ impl Default for ProcessingSteps {
                 &format!("instantiate_templates{}", suffix),
                 cpp_template_instantiator::instantiate_templates,
             );
+            s.push(
+                &format!("instantiate_template_functions{}", suffix),
+                cpp_template_instantiator::instantiate_template_functions,
+            );
             s.push(
                 &format!("omitting_arguments{}", suffix),
                 cpp_omitting_arguments::run,
@@ -114,12 +118,17 @@ impl Default for ProcessingSteps {
                 &format!("cpp_ffi_generator{}", suffix),
                 cpp_ffi_generator::run,
             );
+            s.push(
+                &format!("add_virtual_subclasses{}", suffix),
+                cpp_virtual_subclasses::run,
+            );
             s.push(&format!("cpp_checker{}", suffix), |data| {
                 cpp_checker::run(data, false)
             });
         };
 
         s.push("cpp_parser", cpp_parser::run);
+        s.push("cpp_macros", cpp_parser::parse_macros);
         push_cpp_post_processing(&mut s, "");
         s.push("cpp_parser_stage2", cpp_parser::parse_generated_items);
         push_cpp_post_processing(&mut s, "_stage2");
@@ -1,16 +1,17 @@
 //! Types and functions used for Rust code generation.
 
 use crate::cpp_checks::Condition;
+use crate::cpp_data::{CppDeprecation, CppItem};
 use crate::cpp_ffi_data::{CppFfiArgumentMeaning, CppFfiItem};
 use crate::cpp_function::CppFunction;
 use crate::database::{DatabaseClient, DbItem, ItemId};
 use crate::doc_formatter;
 use crate::rust_generator::qt_core_path;
 use crate::rust_info::{
-    RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData, RustFunction,
-    RustFunctionArgument, RustFunctionKind, RustItem, RustModule, RustModuleKind,
-    RustQtReceiverType, RustSpecialModuleKind, RustStruct, RustStructKind, RustTraitImpl,
-    RustWrapperTypeKind,
+    RustConstant, RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData,
+    RustFunction, RustFunctionArgument, RustFunctionKind, RustItem, RustModule, RustModuleKind,
+    RustOverridesSetterData, RustQtReceiverType, RustSpecialModuleKind, RustStruct, RustStructKind,
+    RustTrait, RustTraitImpl, RustTypeAlias, RustWrapperTypeKind,
 };
 use crate::rust_type::{
     RustClosureToCallbackConversion, RustCommonType, RustFinalType, RustPath,
@@ -21,9 +22,11 @@ use ritual_common::errors::{bail, err_msg, format_err, Result};
 use ritual_common::file_utils::{create_dir_all, create_file, file_to_string, File};
 use ritual_common::string_utils::trim_slice;
 use ritual_common::utils::MapIfOk;
+use std::collections::HashSet;
 use std::fmt::Write as _;
 use std::fs;
 use std::io::{self, BufWriter, Write};
+use std::iter::once;
 use std::path::{Path, PathBuf};
 
 fn wrap_unsafe(in_unsafe_context: bool, content: &str) -> String {
@@ -91,12 +94,17 @@ pub fn rust_type_to_code(rust_type: &RustType, current_crate: Option<&str>) -> S
         }
         RustType::Common(common) => rust_common_type_to_code(common, current_crate),
         RustType::FunctionPointer(function) => format!(
-            "extern \"C\" fn({}){}",
+            "extern \"C\" fn({}{}){}",
             function
                 .arguments
                 .iter()
                 .map(|arg| rust_type_to_code(arg, current_crate))
                 .join(", "),
+            if function.allows_variadic_arguments {
+                ", ..."
+            } else {
+                ""
+            },
             if function.return_type.is_unit() {
                 String::new()
             } else {
@@ -110,6 +118,9 @@ pub fn rust_type_to_code(rust_type: &RustType, current_crate: Option<&str>) -> S
             "impl {}",
             rust_common_type_to_code(trait_type, current_crate)
         ),
+        RustType::Array { element, len } => {
+            format!("[{}; {}]", rust_type_to_code(element, current_crate), len)
+        }
     }
 }
 
@@ -234,11 +245,9 @@ impl Generator<'_> {
             .expect("generator: too much pop_file");
     }
 
-    fn generate_item(
-        &mut self,
-        item: DbItem<&RustItem>,
-        self_type: Option<&RustType>,
-    ) -> Result<()> {
+    /// Returns attributes that enable `item` only under the conditions
+    /// its source FFI item is available in.
+    fn condition_texts(&self, item: DbItem<&RustItem>) -> Result<ConditionTexts> {
         let mut item_for_condition = item.clone();
         if let RustItem::Function(function) = &item.item {
             if function.kind.is_signal_or_slot_getter() {
@@ -303,15 +312,31 @@ impl Generator<'_> {
                 // format!("\n\nThis item is available if `{}`.", expression);
             }
         }
+        Ok(condition_texts)
+    }
+
+    fn generate_item(
+        &mut self,
+        item: DbItem<&RustItem>,
+        self_type: Option<&RustType>,
+    ) -> Result<()> {
+        let condition_texts = self.condition_texts(item.clone())?;
 
         match &item.item {
             RustItem::Module(_) => self.generate_module(item.map(|i| i.as_module_ref().unwrap())),
             RustItem::Struct(_) => {
                 self.generate_struct(item.map(|i| i.as_struct_ref().unwrap()), &condition_texts)
             }
+            RustItem::TypeAlias(_) => {
+                self.generate_type_alias(item.map(|i| i.as_type_alias_ref().unwrap()))
+            }
             RustItem::EnumValue(_) => {
                 self.generate_enum_value(item.map(|i| i.as_enum_value_ref().unwrap()))
             }
+            RustItem::Constant(_) => {
+                self.generate_constant(item.map(|i| i.as_constant_ref().unwrap()))
+            }
+            RustItem::Trait(_) => self.generate_trait(item.map(|i| i.as_trait_ref().unwrap())),
             RustItem::TraitImpl(_) => self.generate_trait_impl(
                 item.map(|i| i.as_trait_impl_ref().unwrap()),
                 &condition_texts,
@@ -329,7 +354,8 @@ impl Generator<'_> {
             RustItem::Reexport(reexport) => {
                 writeln!(
                     self,
-                    "pub use {} as {};",
+                    "{}pub use {} as {};",
+                    condition_texts.attribute,
                     self.rust_path_to_string(&reexport.target),
                     reexport.path.last()
                 )?;
@@ -372,6 +398,8 @@ impl Generator<'_> {
                 self,
                 "#![cfg_attr(feature = \"ritual_rustdoc_nightly\", feature(doc_cfg))]"
             )?;
+            // generated wrappers of deprecated C++ items may refer to each other
+            writeln!(self, "#![allow(deprecated)]")?;
 
             if let Some(crate_template_src_path) = &self.crate_template_src_path {
                 let template_path = self.module_path(&module.item.path, crate_template_src_path)?;
@@ -413,6 +441,7 @@ impl Generator<'_> {
             }
             RustModuleKind::Special(RustSpecialModuleKind::CrateRoot)
             | RustModuleKind::Special(RustSpecialModuleKind::Ops)
+            | RustModuleKind::Special(RustSpecialModuleKind::Macros)
             | RustModuleKind::CppNamespace { .. }
             | RustModuleKind::CppNestedTypes { .. } => {
                 self.generate_children(&module.item.path, None)?;
@@ -456,9 +485,15 @@ impl Generator<'_> {
         rust_struct: DbItem<&RustStruct>,
         condition_texts: &ConditionTexts,
     ) -> Result<()> {
+        if rust_struct.item.kind.is_sized_type() {
+            // sized types are defined in `sized_types.rs` generated by the build script
+            return Ok(());
+        }
         let doc = doc_formatter::struct_doc(rust_struct.clone(), self.current_database)?
             + &condition_texts.doc_text;
         write!(self, "{}", format_doc(&doc))?;
+        let deprecated = self.deprecated_attribute(&rust_struct.id)?;
+        write!(self, "{}", deprecated)?;
 
         let visibility = if rust_struct.item.is_public {
             "pub "
@@ -467,13 +502,63 @@ impl Generator<'_> {
         };
         match &rust_struct.item.kind {
             RustStructKind::WrapperType(kind) => match kind {
-                RustWrapperTypeKind::EnumWrapper => {
-                    writeln!(
-                        self,
-                        include_str!("../templates/crate/enum_wrapper.rs.in"),
-                        vis = visibility,
-                        name = rust_struct.item.path.last()
-                    )?;
+                RustWrapperTypeKind::EnumWrapper {
+                    underlying_type,
+                    exhaustive_repr,
+                } => {
+                    let underlying_type = self.rust_type_to_code(underlying_type);
+                    let values = self
+                        .current_database
+                        .rust_children(&rust_struct.item.path)
+                        .filter_map(|item| item.filter_map(|item| item.as_enum_value_ref()))
+                        .collect_vec();
+                    match exhaustive_repr {
+                        // Rust doesn't allow `repr` on enums without variants
+                        Some(repr) if !values.is_empty() => {
+                            let mut variants = String::new();
+                            let mut match_arms = String::new();
+                            let mut used_values = HashSet::new();
+                            for value in values {
+                                if !used_values.insert(&value.item.value) {
+                                    // generated as an associated constant
+                                    continue;
+                                }
+                                let doc = doc_formatter::enum_value_doc(
+                                    value.clone(),
+                                    self.current_database,
+                                )?;
+                                let name = value.item.path.last();
+                                write!(variants, "{}", format_doc(&doc))?;
+                                writeln!(variants, "{} = {},", name, value.item.value)?;
+                                writeln!(
+                                    match_arms,
+                                    "{} => Ok({}::{}),",
+                                    value.item.value,
+                                    rust_struct.item.path.last(),
+                                    name
+                                )?;
+                            }
+                            writeln!(
+                                self,
+                                include_str!("../templates/crate/exhaustive_enum.rs.in"),
+                                vis = visibility,
+                                name = rust_struct.item.path.last(),
+                                repr = repr,
+                                underlying_type = underlying_type,
+                                variants = variants,
+                                match_arms = match_arms,
+                            )?;
+                        }
+                        _ => {
+                            writeln!(
+                                self,
+                                include_str!("../templates/crate/enum_wrapper.rs.in"),
+                                vis = visibility,
+                                name = rust_struct.item.path.last(),
+                                underlying_type = underlying_type,
+                            )?;
+                        }
+                    }
                 }
                 RustWrapperTypeKind::ImmovableClassWrapper => {
                     writeln!(self, "#[repr(C)]")?;
@@ -499,8 +584,41 @@ impl Generator<'_> {
             RustStructKind::QtSlotWrapper(_) => {
                 bail!("RustStructKind::QtSlotWrapper is deprecated");
             }
-            RustStructKind::SizedType(_) => {
-                bail!("sized struct can't be generated with rust code generator")
+            RustStructKind::SizedType(_) => {}
+            RustStructKind::OptionsStruct(options) => {
+                let mut fields = String::new();
+                let mut field_values = String::new();
+                for field in &options.fields {
+                    write!(
+                        fields,
+                        "{}",
+                        format_doc(&doc_formatter::options_struct_field_doc(field))
+                    )?;
+                    writeln!(
+                        fields,
+                        "pub {}: {},",
+                        field.name,
+                        self.rust_type_to_code(field.field_type.api_type())
+                    )?;
+                    let ffi_call = format!(
+                        "{}()",
+                        self.rust_path_to_string(&field.default_function_path)
+                    );
+                    writeln!(
+                        field_values,
+                        "{}: {},",
+                        field.name,
+                        self.convert_type_from_ffi(&field.field_type, ffi_call, true, false)?
+                    )?;
+                }
+                writeln!(
+                    self,
+                    include_str!("../templates/crate/options_struct.rs.in"),
+                    vis = visibility,
+                    name = rust_struct.item.path.last(),
+                    fields = fields,
+                    field_values = field_values,
+                )?;
             }
         }
 
@@ -524,7 +642,39 @@ impl Generator<'_> {
         Ok(())
     }
 
+    /// Returns the path of the enum variant representing the same value as `value`
+    /// if `value` belongs to an exhaustive enum.
+    fn exhaustive_enum_variant(&self, value: &RustEnumValue) -> Result<Option<RustPath>> {
+        let enum_path = value.path.parent()?;
+        let is_exhaustive = self
+            .current_database
+            .find_rust_item(&enum_path)
+            .and_then(|item| item.item.as_struct_ref())
+            .map_or(false, |rust_struct| match &rust_struct.kind {
+                RustStructKind::WrapperType(RustWrapperTypeKind::EnumWrapper {
+                    exhaustive_repr,
+                    ..
+                }) => exhaustive_repr.is_some(),
+                _ => false,
+            });
+        if !is_exhaustive {
+            return Ok(None);
+        }
+        let variant = self
+            .current_database
+            .rust_children(&enum_path)
+            .filter_map(|item| item.item.as_enum_value_ref())
+            .find(|variant| variant.value == value.value)
+            .ok_or_else(|| err_msg("enum value not found"))?;
+        Ok(Some(variant.path.clone()))
+    }
+
     fn generate_enum_value(&mut self, value: DbItem<&RustEnumValue>) -> Result<()> {
+        let variant = self.exhaustive_enum_variant(value.item)?;
+        if variant.as_ref() == Some(&value.item.path) {
+            // generated as a variant of the enum
+            return Ok(());
+        }
         write!(
             self,
             "{}",
@@ -540,13 +690,150 @@ impl Generator<'_> {
                 .parent()
                 .expect("enum value must have parent"),
         );
+        let value_code = if let Some(variant) = &variant {
+            self.rust_path_to_string(variant)
+        } else {
+            format!("{}({})", struct_path, value.item.value)
+        };
         writeln!(self, "#[allow(non_upper_case_globals)]")?;
         writeln!(
             self,
-            "pub const {value_name}: {struct_path} = {struct_path}({value});",
-            value_name = value.item.path.last(),
-            struct_path = struct_path,
-            value = value.item.value
+            "pub const {}: {} = {};",
+            value.item.path.last(),
+            struct_path,
+            value_code
+        )?;
+        Ok(())
+    }
+
+    fn generate_constant(&mut self, constant: DbItem<&RustConstant>) -> Result<()> {
+        write!(
+            self,
+            "{}",
+            format_doc(&doc_formatter::constant_doc(
+                constant.clone(),
+                self.current_database
+            )?)
+        )?;
+        writeln!(
+            self,
+            "pub const {}: {} = {};",
+            constant.item.path.last(),
+            self.rust_type_to_code(&constant.item.value_type),
+            constant.item.value
+        )?;
+        Ok(())
+    }
+
+    fn generate_trait(&mut self, rust_trait: DbItem<&RustTrait>) -> Result<()> {
+        write!(
+            self,
+            "{}",
+            format_doc(&doc_formatter::trait_doc(
+                rust_trait.clone(),
+                self.current_database
+            )?)
+        )?;
+        let vis = if rust_trait.item.is_public {
+            "pub "
+        } else {
+            ""
+        };
+        let self_type = rust_trait.item.implemented_for.as_ref();
+        let children = self
+            .current_database
+            .rust_children(&rust_trait.item.path)
+            .filter(|item| item.item.as_function_ref().is_some())
+            .collect_vec();
+
+        writeln!(self, "{}trait {} {{", vis, rust_trait.item.path.last())?;
+        for name in &rust_trait.item.associated_types {
+            writeln!(self, "type {};", name)?;
+        }
+        for func in &rust_trait.item.functions {
+            // these functions are only called by the generic wrapper
+            writeln!(self, "#[doc(hidden)]")?;
+            self.generate_function_prototype(func, self_type)?;
+        }
+        for item in &children {
+            let condition_texts = self.condition_texts(item.clone())?;
+            let func = item.clone().map(|i| i.as_function_ref().unwrap());
+            if self_type.is_none() {
+                // functions of a trait that is not implemented here
+                // are its default implementations
+                let self_type = RustType::GenericParameter("Self".into());
+                self.generate_function(func, true, Some(&self_type), &condition_texts)?;
+                continue;
+            }
+            let doc = doc_formatter::function_doc(func.clone(), self.current_database)?;
+            write!(self, "{}{}", format_doc(&doc), condition_texts.attribute)?;
+            self.generate_function_prototype(func.item, self_type)?;
+        }
+        writeln!(self, "}}\n")?;
+
+        if let Some(self_type) = self_type {
+            writeln!(
+                self,
+                "impl {} for {} {{",
+                rust_trait.item.path.last(),
+                self.rust_type_to_code(self_type)
+            )?;
+            for item in children {
+                let condition_texts = self.condition_texts(item.clone())?;
+                self.generate_function(
+                    item.map(|i| i.as_function_ref().unwrap()),
+                    true,
+                    Some(self_type),
+                    &condition_texts,
+                )?;
+            }
+            writeln!(self, "}}\n")?;
+        }
+        Ok(())
+    }
+
+    /// Generates a declaration of a trait function without a body.
+    fn generate_function_prototype(
+        &mut self,
+        func: &RustFunction,
+        self_type: Option<&RustType>,
+    ) -> Result<()> {
+        let maybe_unsafe = if func.is_unsafe { "unsafe " } else { "" };
+        let return_type = if func.return_type.api_type().is_unit() {
+            String::new()
+        } else {
+            format!(
+                " -> {}",
+                self.rust_type_to_code(func.return_type.api_type())
+            )
+        };
+        writeln!(
+            self,
+            "{}fn {}{}({}){};\n",
+            maybe_unsafe,
+            func.path.last(),
+            self.generic_args_text(func),
+            self.arg_texts(&func.arguments, None, self_type)?.join(", "),
+            return_type
+        )?;
+        Ok(())
+    }
+
+    fn generate_type_alias(&mut self, alias: DbItem<&RustTypeAlias>) -> Result<()> {
+        write!(
+            self,
+            "{}",
+            format_doc(&doc_formatter::type_alias_doc(
+                alias.clone(),
+                self.current_database
+            )?)
+        )?;
+        writeln!(self, "#[allow(non_camel_case_types)]")?;
+        writeln!(
+            self,
+            "pub type {} = {};",
+            alias.item.path.last(),
+            self.rust_type_to_code(&alias.item.target_type)
         )?;
         Ok(())
     }
@@ -693,6 +980,9 @@ impl Generator<'_> {
             RustToFfiTypeConversion::ClosureToCallback { .. } => {
                 bail!("ClosureToCallback is not convertable from FFI type");
             }
+            RustToFfiTypeConversion::ExceptionToResult(_) => {
+                bail!("ExceptionToResult must be handled in generate_ffi_call");
+            }
         };
         Ok(code1 + &code2)
     }
@@ -725,9 +1015,9 @@ impl Generator<'_> {
                     self.rust_type_to_code(type1.ffi_type())
                 )
             }
-            RustToFfiTypeConversion::CppBoxToPtr | RustToFfiTypeConversion::QBoxToPtr => {
-                format!("{}.into_raw_ptr()", expr)
-            }
+            // the box is dropped after the call, deleting the moved-from object
+            RustToFfiTypeConversion::CppBoxToPtr => format!("{}.as_mut_raw_ptr()", expr),
+            RustToFfiTypeConversion::QBoxToPtr => format!("{}.into_raw_ptr()", expr),
             RustToFfiTypeConversion::UtilsPtrToPtr { .. }
             | RustToFfiTypeConversion::UtilsRefToPtr { .. }
             | RustToFfiTypeConversion::QPtrToPtr { .. } => {
@@ -781,7 +1071,12 @@ impl Generator<'_> {
                 self.convert_type_to_ffi(&intermediate_expr, &intermediate)?
             }
             RustToFfiTypeConversion::ClosureToCallback { .. } => {
-                "Some(ffi_callback::<T>), Some(deleter::<T>), data".to_string()
+                "Some(ritual_ffi_callback::<T>), Some(ritual_callback_deleter::<T>), \
+                 ritual_callback_data"
+                    .to_string()
+            }
+            RustToFfiTypeConversion::ExceptionToResult(_) => {
+                bail!("ExceptionToResult is not possible to use in argument position");
             }
         };
         Ok(code)
@@ -805,27 +1100,44 @@ impl Generator<'_> {
         format!("T: FnMut({}){} + 'static", args_text, return_type_text)
     }
 
-    fn callback_glue_code(&self, conversion: &RustClosureToCallbackConversion) -> Result<String> {
+    /// Generates the functions and the data pointer that are passed to FFI
+    /// in place of the closure argument `closure_name`.
+    /// Prefixed names are used to avoid conflicts with the function's arguments.
+    fn callback_glue_code(
+        &self,
+        closure_name: &str,
+        conversion: &RustClosureToCallbackConversion,
+    ) -> Result<String> {
         let mut code = String::new();
         writeln!(
             code,
-            "extern \"C\" fn deleter<T>(data: *mut ::std::ffi::c_void) {{
-                unsafe {{
+            "extern \"C\" fn ritual_callback_deleter<T>(data: *mut ::std::ffi::c_void) {{
+                let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| unsafe {{
                     let _ = Box::from_raw(data as *mut T);
+                }}));
+                if let Err(payload) = result {{
+                    ::cpp_core::handle_callback_panic(payload);
                 }}
             }}"
         )?;
 
+        let return_type = &conversion.closure_return_type;
+        let return_type_text = if return_type.api_type().is_unit() {
+            String::new()
+        } else {
+            format!(" -> {}", self.rust_type_to_code(return_type.ffi_type()))
+        };
         writeln!(
             code,
-            "extern \"C\" fn ffi_callback<{}>(data: *mut ::std::ffi::c_void, {}) {{",
+            "extern \"C\" fn ritual_ffi_callback<{}>(data: *mut ::std::ffi::c_void, {}){} {{",
             self.callback_bound_code(conversion),
             conversion
                 .closure_arguments
                 .iter()
                 .enumerate()
                 .map(|(i, arg)| format!("arg{}: {}", i, self.rust_type_to_code(arg.ffi_type())))
-                .join(", ")
+                .join(", "),
+            return_type_text
         )?;
 
         let func_args = conversion
@@ -837,15 +1149,38 @@ impl Generator<'_> {
             })?
             .join(", ");
 
-        code.push_str(&wrap_unsafe(
-            false,
-            &format!("(*(data as *mut T))({})", func_args),
-        ));
+        let call = format!("(*(data as *mut T))({})", func_args);
+        let body = if return_type.api_type().is_unit() {
+            wrap_unsafe(false, &call)
+        } else {
+            let result = match return_type.conversion() {
+                // ownership of the returned object is transferred to C++
+                RustToFfiTypeConversion::CppBoxToPtr => "result.into_raw_ptr()".to_string(),
+                _ => self.convert_type_to_ffi("result", return_type)?,
+            };
+            wrap_unsafe(false, &format!("let result = {};\n{}", call, result))
+        };
+        // a panic must not unwind into C++ code
+        let panic_handler = if return_type.api_type().is_unit() {
+            "::cpp_core::handle_callback_panic(payload)"
+        } else {
+            "::cpp_core::abort_on_callback_panic(payload)"
+        };
+        writeln!(
+            code,
+            "let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {{ {} }}));
+            match result {{
+                Ok(value) => value,
+                Err(payload) => {},
+            }}",
+            body, panic_handler
+        )?;
 
         writeln!(code, "}}")?;
         writeln!(
             code,
-            "let data = Box::into_raw(Box::new(callback)) as *mut ::std::ffi::c_void;"
+            "let ritual_callback_data = Box::into_raw(Box::new({})) as *mut ::std::ffi::c_void;",
+            closure_name
         )?;
 
         Ok(code)
@@ -869,14 +1204,27 @@ impl Generator<'_> {
             final_args[arg.ffi_index] = Some(code);
         }
 
+        let (return_type, catches_exceptions) = if let RustToFfiTypeConversion::ExceptionToResult(
+            conversion,
+        ) = return_type.conversion()
+        {
+            let inner = RustFinalType::new(return_type.ffi_type().clone(), (**conversion).clone())?;
+            (inner, true)
+        } else {
+            (return_type.clone(), false)
+        };
+
         let mut result = Vec::new();
 
-        if let Some(conversion) = arguments
+        if let Some((name, conversion)) = arguments
             .iter()
-            .filter_map(|x| x.argument_type.conversion().as_callback_ref())
+            .filter_map(|x| {
+                let conversion = x.argument_type.conversion().as_callback_ref()?;
+                Some((&x.name, conversion))
+            })
             .next()
         {
-            result.push(self.callback_glue_code(conversion)?);
+            result.push(self.callback_glue_code(name, conversion)?);
         }
 
         let mut maybe_result_var_name = None;
@@ -933,6 +1281,24 @@ impl Generator<'_> {
             final_args[i] = Some(format!("&mut {}", return_var_name));
             maybe_result_var_name = Some(return_var_name);
         }
+
+        let mut exception_var_name = None;
+        if catches_exceptions {
+            let i = ffi_item
+                .arguments
+                .iter()
+                .position(|arg| arg.meaning == CppFfiArgumentMeaning::ExceptionMessage)
+                .ok_or_else(|| err_msg("exception argument not found"))?;
+            let mut var_name = "exception".to_string();
+            let mut ii = 1;
+            while arguments.iter().any(|x| x.name == var_name) {
+                ii += 1;
+                var_name = format!("exception{}", ii);
+            }
+            final_args.resize(i + 1, None);
+            final_args[i] = Some(format!("&mut {}", var_name));
+            exception_var_name = Some(var_name);
+        }
         let final_args = final_args
             .into_iter()
             .map_if_ok(|x| x.ok_or_else(|| err_msg("ffi argument is missing")))?;
@@ -954,7 +1320,47 @@ impl Generator<'_> {
             result.push(format!("{}\n}}", name));
         }
         let code = result.join("");
-        if maybe_result_var_name.is_none() {
+        if let Some(exception_var_name) = exception_var_name {
+            // the exception must be checked before converting the returned value
+            let converted = if return_type.conversion() == &RustToFfiTypeConversion::UnitToAnything
+            {
+                "()".to_string()
+            } else if maybe_result_var_name.is_none() {
+                self.convert_type_from_ffi(
+                    &return_type,
+                    "ffi_result".to_string(),
+                    in_unsafe_context,
+                    false,
+                )?
+            } else {
+                "ffi_result".to_string()
+            };
+            let exception = wrap_unsafe(
+                in_unsafe_context,
+                &format!(
+                    "::cpp_core::CppException::from_message_ptr({})",
+                    exception_var_name
+                ),
+            );
+            Ok(format!(
+                "let mut {var}: *const ::std::os::raw::c_char = ::std::ptr::null();
+                let ffi_result = {{ {code} }};
+                if !{var}.is_null() {{
+                    {forget}return Err({exception});
+                }}
+                Ok({converted})",
+                var = exception_var_name,
+                code = code,
+                forget = if maybe_result_var_name.is_some() {
+                    // the output value is not initialized
+                    "::std::mem::forget(ffi_result);\n"
+                } else {
+                    ""
+                },
+                exception = exception,
+                converted = converted,
+            ))
+        } else if maybe_result_var_name.is_none() {
             self.convert_type_from_ffi(&return_type, code, in_unsafe_context, true)
         } else {
             Ok(code)
@@ -1019,6 +1425,88 @@ impl Generator<'_> {
         })
     }
 
+    /// Returns `#[deprecated]` attribute for the Rust item `id`
+    /// if its source C++ item is deprecated, or an empty string otherwise.
+    fn deprecated_attribute(&self, id: &ItemId) -> Result<String> {
+        let cpp_item = match self.current_database.source_cpp_item(id)? {
+            Some(item) => item,
+            None => return Ok(String::new()),
+        };
+        let deprecation = match &cpp_item.item {
+            CppItem::Function(function) => &function.deprecation,
+            CppItem::Type(type1) => &type1.deprecation,
+            _ => return Ok(String::new()),
+        };
+        let text = match deprecation {
+            Some(CppDeprecation {
+                message: Some(message),
+            }) => format!("#[deprecated(note = {:?})]\n", message),
+            Some(CppDeprecation { message: None }) => "#[deprecated]\n".to_string(),
+            None => String::new(),
+        };
+        Ok(text)
+    }
+
+    /// Generates the list of generic parameters of `func` (including angle brackets)
+    /// or an empty string if it has no generic parameters.
+    fn generic_args_text(&self, func: &RustFunction) -> String {
+        let mut generic_args = func
+            .arguments
+            .iter()
+            .filter_map(|x| {
+                if let Some(lifetime) = x.argument_type.api_type().lifetime() {
+                    Some(format!("'{}", lifetime))
+                } else if let RustToFfiTypeConversion::ClosureToCallback(conversion) =
+                    x.argument_type.conversion()
+                {
+                    Some(self.callback_bound_code(conversion))
+                } else {
+                    None
+                }
+            })
+            .collect_vec();
+        if let RustFunctionKind::TemplateWrapper(data) = &func.kind {
+            generic_args.push(format!("T: {}", self.rust_path_to_string(&data.trait_path)));
+        }
+        if let RustFunctionKind::OverridesSetter(data) = &func.kind {
+            generic_args.push(format!(
+                "T: {} + 'static",
+                self.rust_path_to_string(&data.trait_path)
+            ));
+        }
+
+        if generic_args.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", generic_args.join(", "))
+        }
+    }
+
+    /// Generates the body of a method that sets a callback for each virtual function
+    /// that calls the corresponding function of `overrides`.
+    fn overrides_setter_body(&self, data: &RustOverridesSetterData) -> String {
+        let mut code = "let overrides = ::std::rc::Rc::new(overrides);\n\
+                        let object: *const Self = self;\n"
+            .to_string();
+        for item in &data.overrides {
+            let args = item.argument_names.join(", ");
+            let call_args = once("&*object".to_string())
+                .chain(item.argument_names.iter().cloned())
+                .join(", ");
+            code.push_str(&format!(
+                "{{\n\
+                 let overrides = ::std::rc::Rc::clone(&overrides);\n\
+                 {}(self, move |{}| overrides.{}({}));\n\
+                 }}\n",
+                self.rust_path_to_string(&item.setter_path),
+                args,
+                item.function_name,
+                call_args
+            ));
+        }
+        code
+    }
+
     /// Generates complete code of a Rust wrapper function.
     fn generate_function(
         &mut self,
@@ -1062,6 +1550,35 @@ impl Generator<'_> {
                 Some(wrap_unsafe(func.item.is_unsafe, &call))
             }
             RustFunctionKind::FfiFunction => None,
+            RustFunctionKind::OptionsWrapper(data) => {
+                let call = format!(
+                    "{}({})",
+                    self.rust_path_to_string(&data.function_path),
+                    data.call_arguments.join(", ")
+                );
+                // the wrapper is unsafe if and only if the called function is unsafe
+                Some(call)
+            }
+            RustFunctionKind::TemplateWrapper(data) => Some(format!(
+                "T::{}({})",
+                func.item.path.last(),
+                data.call_arguments.join(", ")
+            )),
+            RustFunctionKind::OverloadWrapper(data) => Some(format!(
+                "{}({})",
+                self.rust_path_to_string(&data.function_path),
+                data.call_arguments.join(", ")
+            )),
+            RustFunctionKind::VirtualFunction(data) => {
+                data.base_function_path.as_ref().map(|path| {
+                    format!(
+                        "{}({})",
+                        self.rust_path_to_string(path),
+                        data.call_arguments.join(", ")
+                    )
+                })
+            }
+            RustFunctionKind::OverridesSetter(data) => Some(self.overrides_setter_body(data)),
         };
 
         let maybe_body = match &body {
@@ -1077,38 +1594,39 @@ impl Generator<'_> {
                 self.rust_type_to_code(func.item.return_type.api_type())
             )
         };
-        let generic_args = func
-            .item
-            .arguments
-            .iter()
-            .filter_map(|x| {
-                if let Some(lifetime) = x.argument_type.api_type().lifetime() {
-                    Some(format!("'{}", lifetime))
-                } else if let RustToFfiTypeConversion::ClosureToCallback(conversion) =
-                    x.argument_type.conversion()
-                {
-                    Some(self.callback_bound_code(conversion))
-                } else {
-                    None
-                }
-            })
-            .collect_vec();
+        let generic_args_text = self.generic_args_text(func.item);
 
-        let generic_args_text = if generic_args.is_empty() {
-            String::new()
-        } else {
-            format!("<{}>", generic_args.join(", "))
-        };
+        let mut args_text = self
+            .arg_texts(&func.item.arguments, None, self_type)?
+            .join(", ");
+        if func.item.kind.is_ffi_function() {
+            let ffi_item = self
+                .current_database
+                .source_ffi_item(&func.id)?
+                .ok_or_else(|| err_msg("source ffi item not found"))?;
+            if let CppFfiItem::Function(ffi_function) = ffi_item.item {
+                if ffi_function.allows_variadic_arguments {
+                    args_text += ", ...";
+                }
+            }
+        }
 
         // TODO: move condition texts to doc parser
         let doc = doc_formatter::function_doc(func.clone(), self.current_database)?
             + &condition_texts.doc_text;
+        // `#[deprecated]` has no effect on trait impl items
+        let deprecated = if is_in_trait_context || func.item.kind.is_ffi_function() {
+            String::new()
+        } else {
+            self.deprecated_attribute(&func.id)?
+        };
         writeln!(
             self,
-            "{doc}{maybe_inline}{condition}{maybe_pub}{maybe_unsafe} \
+            "{doc}{deprecated}{maybe_inline}{condition}{maybe_pub}{maybe_unsafe} \
              fn {name}{generic_args_text}({args}){return_type} \
              {maybe_body}\n\n",
             doc = format_doc(&doc),
+            deprecated = deprecated,
             maybe_inline = if body.is_some() {
                 "#[inline(always)]\n"
             } else {
@@ -1119,9 +1637,7 @@ impl Generator<'_> {
             maybe_unsafe = maybe_unsafe,
             generic_args_text = generic_args_text,
             name = func.item.path.last(),
-            args = self
-                .arg_texts(&func.item.arguments, None, self_type)?
-                .join(", "),
+            args = args_text,
             return_type = return_type_for_signature,
             maybe_body = maybe_body
         )?;
@@ -1195,7 +1711,9 @@ impl Generator<'_> {
                     .item;
 
                 let receiver_id = match wrapper {
-                    CppFfiItem::Function(_) => bail!("invalid source ffi item type"),
+                    CppFfiItem::Function(_) | CppFfiItem::VirtualSubclass(_) => {
+                        bail!("invalid source ffi item type")
+                    }
                     CppFfiItem::QtSlotWrapper(w) => CppFunction::receiver_id_from_data(
                         RustQtReceiverType::Slot,
                         "slot_",
@@ -1,14 +1,16 @@
 use crate::config::CrateDependencyKind;
 use crate::cpp_checks::CppChecks;
 use crate::cpp_data::{
-    inherits, CppItem, CppPath, CppPathItem, CppTypeDeclaration, CppTypeDeclarationKind,
+    inherits, CppConstantValue, CppItem, CppPath, CppPathItem, CppTypeDeclaration,
+    CppTypeDeclarationKind, CppVisibility,
 };
 use crate::cpp_ffi_data::{
     CppCast, CppFfiArgumentMeaning, CppFfiFunction, CppFfiFunctionKind, CppFfiItem, CppFfiType,
-    CppFieldAccessorType, CppToFfiTypeConversion,
+    CppFieldAccessorType, CppToFfiTypeConversion, CppVirtualSubclass,
 };
 use crate::cpp_ffi_generator::ffi_type;
 use crate::cpp_function::{CppFunction, CppOperator, ReturnValueAllocationPlace};
+use crate::cpp_template_instantiator::member_function_template_parameter;
 use crate::cpp_type::{
     is_qflags, CppBuiltInNumericType, CppFunctionPointerType, CppPointerLikeTypeKind,
     CppSpecificNumericType, CppSpecificNumericTypeKind, CppType, CppTypeRole,
@@ -16,25 +18,29 @@ use crate::cpp_type::{
 use crate::database::{DbItem, ItemId, ItemWithSource};
 use crate::processor::ProcessorData;
 use crate::rust_info::{
-    NameType, RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData,
+    NameType, RustConstant, RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData,
     RustFlagEnumImpl, RustFunction, RustFunctionArgument, RustFunctionCaptionStrategy,
-    RustFunctionKind, RustFunctionSelfArgKind, RustItem, RustModule, RustModuleKind, RustPathScope,
-    RustQtReceiverData, RustQtReceiverImpl, RustQtReceiverType, RustReexport, RustReexportSource,
+    RustFunctionKind, RustFunctionSelfArgKind, RustItem, RustModule, RustModuleKind,
+    RustOptionsStruct, RustOptionsStructField, RustOptionsWrapperData, RustOverloadWrapperData,
+    RustOverrideData, RustOverridesSetterData, RustPathScope, RustQtReceiverData,
+    RustQtReceiverImpl, RustQtReceiverType, RustReexport, RustReexportSource,
     RustSignalOrSlotGetter, RustSizedType, RustSpecialModuleKind, RustStruct, RustStructKind,
-    RustTraitAssociatedType, RustTraitImpl, RustTraitImplExtraKind, RustTypeCaptionStrategy,
+    RustTemplateWrapperData, RustTrait, RustTraitAssociatedType, RustTraitImpl,
+    RustTraitImplExtraKind, RustTypeAlias, RustTypeCaptionStrategy, RustVirtualFunctionData,
     RustWrapperTypeKind, UnnamedRustFunction,
 };
 use crate::rust_type::{
     RustClosureToCallbackConversion, RustCommonType, RustFinalType, RustFunctionPointerType,
     RustPath, RustPointerLikeTypeKind, RustToFfiTypeConversion, RustType,
 };
+use crate::type_allocation_places::is_movable;
 use itertools::Itertools;
 use log::{debug, trace};
 use ritual_common::errors::{bail, err_msg, format_err, print_trace, Result};
 use ritual_common::string_utils::CaseOperations;
 use ritual_common::utils::MapIfOk;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
-use std::iter::Iterator;
+use std::iter::{once, Iterator};
 use std::ops::Deref;
 
 pub fn qt_core_path(crate_name: &str) -> RustPath {
@@ -78,17 +84,44 @@ struct FunctionWithDesiredPath {
     desired_path: RustPath,
 }
 
+/// Overloads of a class's `operator()` that are available through the generic `call` method.
+struct CallOperatorOverloads {
+    /// Path of the trait implemented for the argument types of the overloads
+    trait_path: RustPath,
+    /// Source IDs of the overloads and the types implementing the trait
+    overloads: Vec<(ItemId, RustType)>,
+}
+
 enum ProcessedFfiItem {
     Item(RustItem),
     Function(FunctionWithDesiredPath),
+    /// Instantiation of a member function template that is exposed
+    /// as a generic method.
+    TemplateInstantiation {
+        template_id: ItemId,
+        function: FunctionWithDesiredPath,
+    },
 }
 
+/// Functions grouped by the desired path.
+type GroupedFunctions = BTreeMap<RustPath, Vec<ItemWithSource<FunctionWithDesiredPath>>>;
+
+/// Instantiations of member function templates grouped by the ID of the template.
+type TemplateInstantiations = BTreeMap<ItemId, Vec<ItemWithSource<FunctionWithDesiredPath>>>;
+
 #[derive(Debug, Clone, Copy)]
 enum ReturnTypeConstraint {
     Bool,
     Usize,
+    U64,
     #[allow(dead_code)]
     Unit,
+    /// `CppBox` of the `Self` type.
+    CppBox,
+    /// `Self` type returned by value.
+    Value,
+    /// `Ref` to any type.
+    Ref,
     Any,
 }
 
@@ -381,10 +414,113 @@ impl TraitImplInfo {
         })
     }
 
-    fn new(function: &CppFunction) -> Option<TraitImplInfo> {
+    /// Returns information for `operator[]`. Only operators returning a reference
+    /// are supported because `cpp_core` implements `std::ops::Index` on top of these traits.
+    fn from_subscript_operator(is_const: bool) -> TraitImplInfo {
+        let (trait_path, function_name) = if is_const {
+            ("cpp_core::ops::Index", "index")
+        } else {
+            ("cpp_core::ops::IndexMut", "index_mut")
+        };
+        TraitImplInfo {
+            trait_path,
+            function_name,
+            is_unsafe: true,
+            is_inherent: true,
+            self_arg_kind: RustFunctionSelfArgKind::ConstRef,
+            has_output_associated_type: true,
+            trait_arg_is_second_arg_type: true,
+            second_arg_is_reference: false,
+            return_type_constraint: ReturnTypeConstraint::Ref,
+            target_is_reference: false,
+        }
+    }
+
+    /// Returns information for a copy constructor or a default constructor.
+    /// Stack allocated types implement the standard traits directly,
+    /// and heap allocated types implement `cpp_core` traits that provide
+    /// the standard traits for `CppBox`.
+    fn from_constructor(
+        function: &CppFunction,
+        allocation_place: ReturnValueAllocationPlace,
+    ) -> Option<TraitImplInfo> {
+        let is_stack = allocation_place == ReturnValueAllocationPlace::Stack;
+        let return_type_constraint = if is_stack {
+            ReturnTypeConstraint::Value
+        } else {
+            ReturnTypeConstraint::CppBox
+        };
+        if function.is_copy_constructor() {
+            Some(TraitImplInfo {
+                trait_path: if is_stack {
+                    "std::clone::Clone"
+                } else {
+                    "cpp_core::CppClone"
+                },
+                function_name: "clone",
+                is_unsafe: !is_stack,
+                is_inherent: true,
+                self_arg_kind: RustFunctionSelfArgKind::ConstRef,
+                has_output_associated_type: false,
+                trait_arg_is_second_arg_type: false,
+                second_arg_is_reference: false,
+                return_type_constraint,
+                target_is_reference: false,
+            })
+        } else if function.arguments.is_empty() {
+            Some(TraitImplInfo {
+                trait_path: if is_stack {
+                    "std::default::Default"
+                } else {
+                    "cpp_core::CppDefault"
+                },
+                function_name: "default",
+                is_unsafe: !is_stack,
+                is_inherent: true,
+                self_arg_kind: RustFunctionSelfArgKind::None,
+                has_output_associated_type: false,
+                trait_arg_is_second_arg_type: false,
+                second_arg_is_reference: false,
+                return_type_constraint,
+                target_is_reference: false,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn new(
+        function: &CppFunction,
+        allocation_place: ReturnValueAllocationPlace,
+        hash_functions: &[String],
+    ) -> Option<TraitImplInfo> {
         if let Some(operator) = &function.operator {
+            if let (CppOperator::Subscript, Some(member)) = (operator, &function.member) {
+                return Some(Self::from_subscript_operator(member.is_const));
+            }
             return Self::from_operator(operator);
         }
+        if function.is_constructor() {
+            return Self::from_constructor(function, allocation_place);
+        }
+        if function.member.is_none()
+            && function.arguments.len() == 1
+            && function.path.last().template_arguments.is_none()
+            && hash_functions.contains(&function.path.last().name)
+        {
+            return Some(TraitImplInfo {
+                trait_path: "cpp_core::CppHash",
+                function_name: "hash_value",
+                is_unsafe: true,
+                is_inherent: true,
+                self_arg_kind: RustFunctionSelfArgKind::ConstRef,
+                has_output_associated_type: false,
+                trait_arg_is_second_arg_type: false,
+                second_arg_is_reference: false,
+                return_type_constraint: ReturnTypeConstraint::U64,
+                target_is_reference: false,
+            });
+        }
         if let Some(member) = &function.member {
             if !member.is_static
                 && function.arguments.is_empty()
@@ -520,6 +656,16 @@ impl From<&RustTraitImpl> for TraitTypes {
     }
 }
 
+/// Returns the parent path of `rust_type` if it's a type of the current crate.
+fn local_type_parent_path(rust_type: &RustType, crate_name: &str) -> Result<Option<RustPath>> {
+    if let RustType::Common(RustCommonType { path, .. }) = rust_type {
+        if path.crate_name() == crate_name {
+            return Ok(Some(path.parent()?));
+        }
+    }
+    Ok(None)
+}
+
 fn check_trait_impl_uniqueness(
     trait_types: &[TraitTypes],
     target_type: &RustType,
@@ -544,9 +690,73 @@ fn check_trait_impl_uniqueness(
     Ok(())
 }
 
+/// Returns the primitive Rust type that has the same representation as
+/// the C++ integer type `cpp_type` on all platforms.
+fn enum_repr_type(cpp_type: &CppType) -> Option<String> {
+    match cpp_type {
+        CppType::BuiltInNumeric(numeric) => {
+            let name = match numeric {
+                CppBuiltInNumericType::SChar => "i8",
+                CppBuiltInNumericType::UChar => "u8",
+                CppBuiltInNumericType::Short => "i16",
+                CppBuiltInNumericType::UShort => "u16",
+                CppBuiltInNumericType::Int => "i32",
+                CppBuiltInNumericType::UInt => "u32",
+                CppBuiltInNumericType::LongLong => "i64",
+                CppBuiltInNumericType::ULongLong => "u64",
+                // signedness of `char` and size of `long` depend on the platform
+                _ => return None,
+            };
+            Some(name.to_string())
+        }
+        CppType::SpecificNumeric(CppSpecificNumericType {
+            bits,
+            kind: CppSpecificNumericTypeKind::Integer { is_signed },
+            ..
+        }) => Some(format!("{}{}", if *is_signed { "i" } else { "u" }, bits)),
+        CppType::PointerSizedInteger { is_signed, .. } => {
+            Some(if *is_signed { "isize" } else { "usize" }.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Returns Rust literal for the enum `value` represented with `underlying_type`.
+fn enum_value_literal(value: i64, underlying_type: &CppType) -> String {
+    let is_unsigned = match underlying_type {
+        CppType::BuiltInNumeric(numeric) => numeric.is_unsigned_integer(),
+        CppType::SpecificNumeric(CppSpecificNumericType {
+            kind: CppSpecificNumericTypeKind::Integer { is_signed },
+            ..
+        })
+        | CppType::PointerSizedInteger { is_signed, .. } => !is_signed,
+        _ => false,
+    };
+    if is_unsigned {
+        (value as u64).to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[test]
+fn enum_value_literal_test() {
+    let int = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    let u64_type = CppType::BuiltInNumeric(CppBuiltInNumericType::ULongLong);
+    assert_eq!(enum_value_literal(-1, &int), "-1");
+    assert_eq!(enum_value_literal(-1, &u64_type), "18446744073709551615");
+    assert_eq!(enum_repr_type(&int), Some("i32".to_string()));
+    assert_eq!(
+        enum_repr_type(&CppType::BuiltInNumeric(CppBuiltInNumericType::Long)),
+        None
+    );
+}
+
 struct State<'b, 'a> {
     data: &'b mut ProcessorData<'a>,
     special_module_paths: HashMap<RustSpecialModuleKind, RustPath>,
+    /// Paths of classes that need the protected members trait
+    classes_with_protected_members: HashSet<CppPath>,
 }
 
 impl State<'_, '_> {
@@ -662,8 +872,8 @@ impl State<'_, '_> {
                 arguments,
                 allows_variadic_arguments,
             }) => {
-                if *allows_variadic_arguments {
-                    bail!("function pointers with variadic arguments are not supported");
+                if *allows_variadic_arguments && arguments.is_empty() {
+                    bail!("variadic function pointers must have at least one named argument");
                 }
                 let rust_args = arguments
                     .iter()
@@ -672,13 +882,29 @@ impl State<'_, '_> {
                 let pointer = RustType::FunctionPointer(RustFunctionPointerType {
                     arguments: rust_args,
                     return_type: Box::new(rust_return_type),
+                    allows_variadic_arguments: *allows_variadic_arguments,
                 });
                 RustType::Common(RustCommonType {
                     path: RustPath::from_good_str("std::option::Option"),
                     generic_arguments: Some(vec![pointer]),
                 })
             }
-            CppType::TemplateParameter { .. } => bail!("invalid cpp type"),
+            CppType::Array { element, len } => {
+                match **element {
+                    CppType::BuiltInNumeric(_)
+                    | CppType::SpecificNumeric(_)
+                    | CppType::PointerSizedInteger { .. }
+                    | CppType::Array { .. } => {}
+                    _ => bail!("only arrays of numeric types are supported"),
+                }
+                RustType::Array {
+                    element: Box::new(self.ffi_type_to_rust_ffi_type(element)?),
+                    len: *len,
+                }
+            }
+            CppType::TemplateParameter { .. } | CppType::IntegralConstant { .. } => {
+                bail!("invalid cpp type")
+            }
         };
 
         Ok(rust_type)
@@ -768,7 +994,15 @@ impl State<'_, '_> {
                 false
             };
 
-            if let CppToFfiTypeConversion::ValueToPointer { .. } = cpp_ffi_type.conversion() {
+            if cpp_ffi_type.conversion() == &CppToFfiTypeConversion::ArrayToPointer {
+                api_to_ffi_conversion = if argument_meaning == &CppFfiArgumentMeaning::ReturnValue {
+                    // the array is copied to a local variable
+                    RustToFfiTypeConversion::ValueToPtr
+                } else {
+                    RustToFfiTypeConversion::RefToPtr { lifetime: None }
+                };
+            } else if let CppToFfiTypeConversion::ValueToPointer { .. } = cpp_ffi_type.conversion()
+            {
                 if argument_meaning == &CppFfiArgumentMeaning::ReturnValue {
                     match allocation_place {
                         ReturnValueAllocationPlace::Stack => {
@@ -824,18 +1058,21 @@ impl State<'_, '_> {
                 } else {
                     // argument
                     if target.is_class() {
-                        api_to_ffi_conversion =
-                            if let CppToFfiTypeConversion::ReferenceToPointer { .. } =
-                                cpp_ffi_type.conversion()
-                            {
+                        api_to_ffi_conversion = match cpp_ffi_type.conversion() {
+                            CppToFfiTypeConversion::ReferenceToPointer => {
                                 RustToFfiTypeConversion::ImplCastInto(Box::new(
                                     RustToFfiTypeConversion::UtilsRefToPtr {},
                                 ))
-                            } else {
-                                RustToFfiTypeConversion::ImplCastInto(Box::new(
-                                    RustToFfiTypeConversion::UtilsPtrToPtr {},
-                                ))
-                            };
+                            }
+                            // the object is moved from, so the caller has to give up
+                            // ownership of it
+                            CppToFfiTypeConversion::RValueReferenceToPointer => {
+                                RustToFfiTypeConversion::CppBoxToPtr
+                            }
+                            _ => RustToFfiTypeConversion::ImplCastInto(Box::new(
+                                RustToFfiTypeConversion::UtilsPtrToPtr {},
+                            )),
+                        };
                     } else {
                         api_to_ffi_conversion = RustToFfiTypeConversion::None;
                     }
@@ -949,22 +1186,40 @@ impl State<'_, '_> {
     ) -> Result<RustTraitImpl> {
         let trait_path = RustPath::from_good_str(operator_info.trait_path);
 
-        let self_type = unnamed_function
+        // a free operator may have a primitive first operand (e.g. `2.0 * point`)
+        let primitive_self_type = unnamed_function
             .arguments
             .get(0)
-            .ok_or_else(|| err_msg("no arguments"))?
-            .argument_type
-            .ffi_type()
-            .clone();
-
-        let self_value_type = self_type.pointer_like_to_target()?;
+            .map(|arg| arg.argument_type.api_type())
+            .filter(|t| operator_info.target_is_reference && t.is_primitive())
+            .cloned();
+
+        let self_value_type = if operator_info.self_arg_kind == RustFunctionSelfArgKind::None {
+            // the function creates a new object of the type
+            unnamed_function
+                .return_type
+                .ffi_type()
+                .pointer_like_to_target()?
+        } else if let Some(primitive_self_type) = &primitive_self_type {
+            primitive_self_type.clone()
+        } else {
+            unnamed_function
+                .arguments
+                .get(0)
+                .ok_or_else(|| err_msg("no arguments"))?
+                .argument_type
+                .ffi_type()
+                .pointer_like_to_target()?
+        };
 
-        let target_type = if operator_info.target_is_reference {
+        let target_type = if operator_info.target_is_reference && primitive_self_type.is_none() {
             RustType::new_reference(true, self_value_type.clone())
         } else {
             self_value_type.clone()
         };
 
+        let self_parent_path = local_type_parent_path(&self_value_type, crate_name)?;
+        let mut other_parent_path = None;
         let trait_args;
         let other_type;
 
@@ -980,7 +1235,24 @@ impl State<'_, '_> {
                 other_type1 =
                     RustFinalType::new(other_type1.ffi_type().clone(), (**conversion).clone())?;
             }
-            trait_args = Some(vec![other_type1.api_type().clone()]);
+            if self_parent_path.is_some() {
+                trait_args = Some(vec![other_type1.api_type().clone()]);
+            } else {
+                // Orphan rules only allow the impl if the trait arguments contain a type
+                // of the current crate, and `Ref<T>` is not considered local,
+                // so the other operand is taken as `&T`.
+                let other_value_type = other_type1.ffi_type().pointer_like_to_target()?;
+                other_parent_path = local_type_parent_path(&other_value_type, crate_name)?;
+                other_type1 = RustFinalType::new(
+                    other_type1.ffi_type().clone(),
+                    RustToFfiTypeConversion::RefToPtr { lifetime: None },
+                )?;
+                trait_args = Some(vec![if operator_info.second_arg_is_reference {
+                    other_value_type
+                } else {
+                    other_type1.api_type().clone()
+                }]);
+            }
             other_type = Some(other_type1);
         } else {
             other_type = None;
@@ -992,17 +1264,12 @@ impl State<'_, '_> {
             generic_arguments: trait_args,
         };
 
-        check_trait_impl_uniqueness(trait_types, &target_type, &trait_type)?;
+        let other_is_local_reference = other_parent_path.is_some();
+        let parent_path = self_parent_path
+            .or(other_parent_path)
+            .ok_or_else(|| err_msg("operand types are outside current crate"))?;
 
-        let parent_path = if let RustType::Common(RustCommonType { path, .. }) = self_value_type {
-            let type_crate_name = path.crate_name();
-            if type_crate_name != crate_name {
-                bail!("self type is outside current crate");
-            }
-            path.parent()?
-        } else {
-            bail!("self type is not Common");
-        };
+        check_trait_impl_uniqueness(trait_types, &target_type, &trait_type)?;
 
         let associated_types = if operator_info.has_output_associated_type {
             let output = RustTraitAssociatedType {
@@ -1016,16 +1283,20 @@ impl State<'_, '_> {
 
         let mut function = unnamed_function.with_path(trait_path.join(operator_info.function_name));
         function.is_unsafe = operator_info.is_unsafe;
-        function.arguments[0].argument_type = RustFinalType::new(
-            function.arguments[0].argument_type.ffi_type().clone(),
-            RustToFfiTypeConversion::RefToPtr { lifetime: None },
-        )?;
-        function.arguments[0].name = "self".to_string();
+        if operator_info.self_arg_kind != RustFunctionSelfArgKind::None {
+            if primitive_self_type.is_none() {
+                function.arguments[0].argument_type = RustFinalType::new(
+                    function.arguments[0].argument_type.ffi_type().clone(),
+                    RustToFfiTypeConversion::RefToPtr { lifetime: None },
+                )?;
+            }
+            function.arguments[0].name = "self".to_string();
+        }
         if let Some(other_type) = other_type {
             function.arguments[1].argument_type = other_type;
         }
 
-        if operator_info.second_arg_is_reference {
+        if operator_info.second_arg_is_reference && !other_is_local_reference {
             let other_arg = &mut function.arguments[1].argument_type;
             *other_arg = RustFinalType::new(
                 other_arg.ffi_type().clone(),
@@ -1053,11 +1324,36 @@ impl State<'_, '_> {
                     )?;
                 }
             }
+            ReturnTypeConstraint::U64 => {
+                if function.return_type.api_type() != &RustType::Primitive("u64".into()) {
+                    function.return_type = RustFinalType::new(
+                        function.return_type.ffi_type().clone(),
+                        RustToFfiTypeConversion::AsCast {
+                            api_type: RustType::Primitive("u64".into()),
+                        },
+                    )?;
+                }
+            }
             ReturnTypeConstraint::Bool => {
                 if function.return_type.api_type() != &RustType::bool() {
                     bail!("return type is not bool");
                 }
             }
+            ReturnTypeConstraint::CppBox => {
+                if function.return_type.conversion() != &RustToFfiTypeConversion::CppBoxToPtr {
+                    bail!("return type is not CppBox");
+                }
+            }
+            ReturnTypeConstraint::Value => {
+                if function.return_type.conversion() != &RustToFfiTypeConversion::ValueToPtr {
+                    bail!("return type is not a value");
+                }
+            }
+            ReturnTypeConstraint::Ref => {
+                if !function.return_type.conversion().is_utils_ref_to_ptr() {
+                    bail!("return type is not Ref");
+                }
+            }
         }
 
         Ok(RustTraitImpl {
@@ -1070,6 +1366,56 @@ impl State<'_, '_> {
         })
     }
 
+    /// Generates a `CastFrom<&T>` impl for a conversion operator of `T`
+    /// that returns a value. The operator is still available as a `to_*` method.
+    fn process_conversion_operator_as_cast_from(
+        unnamed_function: UnnamedRustFunction,
+        crate_name: &str,
+        trait_types: &[TraitTypes],
+    ) -> Result<RustTraitImpl> {
+        let trait_path = RustPath::from_good_str("cpp_core::CastFrom");
+        if unnamed_function.arguments.len() != 1 {
+            bail!("1 argument expected");
+        }
+        let self_type = unnamed_function.arguments[0].argument_type.ffi_type();
+        let self_value_type = self_type.pointer_like_to_target()?;
+        let parent_path = if let RustType::Common(RustCommonType { path, .. }) = &self_value_type {
+            if path.crate_name() != crate_name {
+                bail!("self type is outside current crate");
+            }
+            path.parent()?
+        } else {
+            bail!("self type is not Common");
+        };
+
+        let mut function = unnamed_function.with_path(trait_path.join("cast_from"));
+        function.is_unsafe = true;
+        function.arguments[0].name = "value".to_string();
+        function.arguments[0].argument_type = RustFinalType::new(
+            function.arguments[0].argument_type.ffi_type().clone(),
+            RustToFfiTypeConversion::RefToPtr { lifetime: None },
+        )?;
+
+        let target_type = function.return_type.api_type().clone();
+        if target_type == RustType::unit() {
+            bail!("conversion to unit is not supported");
+        }
+        let trait_type = RustCommonType {
+            path: trait_path,
+            generic_arguments: Some(vec![function.arguments[0].argument_type.api_type().clone()]),
+        };
+        check_trait_impl_uniqueness(trait_types, &target_type, &trait_type)?;
+
+        Ok(RustTraitImpl {
+            target_type,
+            parent_path,
+            trait_type,
+            associated_types: Vec::new(),
+            functions: vec![function],
+            extra_kind: RustTraitImplExtraKind::Normal,
+        })
+    }
+
     fn process_destructor(
         unnamed_function: UnnamedRustFunction,
         allocation_place: ReturnValueAllocationPlace,
@@ -1238,6 +1584,7 @@ impl State<'_, '_> {
     fn convert_callbacks_to_closure(
         &self,
         id: &ItemId,
+        ffi_function: &CppFfiFunction,
         function: &mut UnnamedRustFunction,
         checks: &CppChecks,
     ) -> Result<()> {
@@ -1245,16 +1592,54 @@ impl State<'_, '_> {
             return Ok(());
         };
 
-        let wrapper = self
-            .data
-            .db
-            .source_ffi_item(id)?
-            .ok_or_else(|| err_msg("source ffi item not found"))?
-            .item
-            .as_slot_wrapper_ref()
-            .ok_or_else(|| err_msg("invalid source ffi item type"))?;
+        // a `std::function` argument is converted to callback arguments by the FFI function itself
+        let std_function_callback = ffi_function.arguments.iter().find_map(|arg| {
+            if let CppToFfiTypeConversion::FunctionToCallback {
+                arguments,
+                return_type,
+            } = arg.argument_type.conversion()
+            {
+                Some((arguments, Some(&**return_type)))
+            } else {
+                None
+            }
+        });
+
+        let (callback_arguments, callback_return_type) =
+            if let Some(callback) = std_function_callback {
+                callback
+            } else {
+                let source_ffi_item = self
+                    .data
+                    .db
+                    .source_ffi_item(id)?
+                    .ok_or_else(|| err_msg("source ffi item not found"))?
+                    .item;
+
+                match source_ffi_item {
+                    CppFfiItem::QtSlotWrapper(wrapper) => (&wrapper.arguments, None),
+                    CppFfiItem::VirtualSubclass(subclass) => {
+                        let setter = self
+                            .data
+                            .db
+                            .source_cpp_item(id)?
+                            .ok_or_else(|| err_msg("source cpp item not found"))?
+                            .item
+                            .as_function_ref()
+                            .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+                        let virtual_function = subclass.find_override(setter).ok_or_else(|| {
+                            err_msg("virtual function not found for callback setter")
+                        })?;
+                        (
+                            &virtual_function.arguments,
+                            Some(&virtual_function.return_type),
+                        )
+                    }
+                    _ => bail!("invalid source ffi item type"),
+                }
+            };
 
-        let closure_arguments = wrapper.arguments.iter().map_if_ok(|arg| {
+        let closure_arguments = callback_arguments.iter().map_if_ok(|arg| {
             self.rust_final_type(
                 arg,
                 // closure argument should be handled in the same way
@@ -1264,19 +1649,59 @@ impl State<'_, '_> {
                 Some(&checks),
             )
         })?;
-        let closure_return_type = self.rust_final_type(
-            &CppFfiType::void(),
-            // TODO: not sure about the meaning.
-            &CppFfiArgumentMeaning::Argument(0),
-            ReturnValueAllocationPlace::NotApplicable,
-            Some(&checks),
-        )?;
+        let closure_return_type = if let Some(return_type) = callback_return_type {
+            let allocation_place = match return_type.conversion() {
+                CppToFfiTypeConversion::ValueToPointer { .. } => ReturnValueAllocationPlace::Heap,
+                _ => ReturnValueAllocationPlace::NotApplicable,
+            };
+            // closure return value is passed back to C++ (value is produced
+            // on the Rust side and ownership is transferred to the callee)
+            self.rust_final_type(
+                return_type,
+                &CppFfiArgumentMeaning::ReturnValue,
+                allocation_place,
+                Some(&checks),
+            )?
+        } else {
+            self.rust_final_type(
+                &CppFfiType::void(),
+                // TODO: not sure about the meaning.
+                &CppFfiArgumentMeaning::Argument(0),
+                ReturnValueAllocationPlace::NotApplicable,
+                Some(&checks),
+            )?
+        };
+
+        // the closure replaces the `std::function` argument, so it gets its name
+        let std_function_name = if std_function_callback.is_some() {
+            let ffi_index = function.arguments[function.arguments.len() - 3].ffi_index;
+            let cpp_function = self
+                .data
+                .db
+                .source_cpp_item(id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            match &ffi_function.arguments[ffi_index].meaning {
+                CppFfiArgumentMeaning::Argument(index) => Some(sanitize_rust_identifier(
+                    &cpp_function.arguments[*index].name.to_snake_case(),
+                    false,
+                )),
+                _ => bail!("invalid meaning of callback argument"),
+            }
+        } else {
+            None
+        };
 
         function.arguments.drain(function.arguments.len() - 2..);
         let arg = function
             .arguments
             .last_mut()
             .expect("function must have enough args");
+        if let Some(name) = std_function_name {
+            arg.name = name;
+        }
         arg.argument_type = RustFinalType::new(
             arg.argument_type.ffi_type().clone(),
             RustToFfiTypeConversion::ClosureToCallback(Box::new(RustClosureToCallbackConversion {
@@ -1301,10 +1726,36 @@ impl State<'_, '_> {
         let mut results = vec![ProcessedFfiItem::Item(RustItem::Function(
             rust_ffi_function,
         ))];
+        if function.allows_variadic_arguments {
+            // Rust can't define variadic functions, so the FFI declaration
+            // is re-exported as the public API function
+            let cpp_item = self
+                .data
+                .db
+                .source_cpp_item(&item.id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?
+                .item;
+            let cpp_path = cpp_item
+                .path()
+                .ok_or_else(|| err_msg("cpp function expected to have a path"))?;
+            let path = self.generate_rust_path(cpp_path, NameType::ApiFunction(item.clone()))?;
+            results.push(ProcessedFfiItem::Item(RustItem::Reexport(RustReexport {
+                path: self.data.db.make_unique_rust_path(&path),
+                target: ffi_function_path,
+                source: RustReexportSource::FfiFunction,
+            })));
+            return Ok(results);
+        }
+        if function.kind.is_default_argument() {
+            // default values are only used by options structs
+            return Ok(results);
+        }
 
         let mut arguments = Vec::new();
         for (arg_index, arg) in function.arguments.iter().enumerate() {
-            if arg.meaning != CppFfiArgumentMeaning::ReturnValue {
+            if arg.meaning != CppFfiArgumentMeaning::ReturnValue
+                && arg.meaning != CppFfiArgumentMeaning::ExceptionMessage
+            {
                 let arg_type = self.rust_final_type(
                     &arg.argument_type,
                     &arg.meaning,
@@ -1381,6 +1832,14 @@ impl State<'_, '_> {
                 return_type = return_type.with_lifetime(return_lifetime)?;
             }
         }
+        if function.catches_exceptions() {
+            return_type = RustFinalType::new(
+                return_type.ffi_type().clone(),
+                RustToFfiTypeConversion::ExceptionToResult(Box::new(
+                    return_type.conversion().clone(),
+                )),
+            )?;
+        }
 
         let mut unnamed_function = UnnamedRustFunction {
             is_public: true,
@@ -1389,7 +1848,7 @@ impl State<'_, '_> {
             kind: RustFunctionKind::FfiWrapper(RustFfiWrapperData { ffi_function_path }),
             is_unsafe: true,
         };
-        self.convert_callbacks_to_closure(&item.id, &mut unnamed_function, checks)?;
+        self.convert_callbacks_to_closure(&item.id, function, &mut unnamed_function, checks)?;
 
         let cpp_item = self
             .data
@@ -1420,7 +1879,49 @@ impl State<'_, '_> {
             if cpp_function.operator.as_ref() == Some(&CppOperator::NotEqualTo) {
                 bail!("NotEqualTo is not needed in public API because PartialEq is used");
             }
-            if let Some(operator_info) = TraitImplInfo::new(cpp_function) {
+            if let Some(CppOperator::Conversion(type1)) = &cpp_function.operator {
+                // conversions to pointers and references are only available
+                // as methods because they don't produce a new value,
+                // and `cast_from` can't report an exception
+                if !type1.is_pointer_like()
+                    && !cpp_function.is_protected()
+                    && !function.catches_exceptions()
+                {
+                    match State::process_conversion_operator_as_cast_from(
+                        unnamed_function.clone(),
+                        self.data.db.crate_name(),
+                        trait_types,
+                    ) {
+                        Ok(item) => {
+                            results.push(ProcessedFfiItem::Item(RustItem::TraitImpl(item)));
+                        }
+                        Err(err) => {
+                            debug!("failed to convert conversion operator to CastFrom: {}", err);
+                            debug!("function: {} {:?}", item.id, function);
+                        }
+                    }
+                }
+            }
+            // protected operators are only available in the protected members trait,
+            // and operators that catch exceptions are only available as methods
+            // because trait functions can't return `Result`
+            let operator_info = TraitImplInfo::new(
+                cpp_function,
+                function.allocation_place,
+                self.data.config.hash_functions(),
+            )
+            .filter(|_| !cpp_function.is_protected() && !function.catches_exceptions());
+            let mut operator_infos = operator_info.into_iter().collect_vec();
+            if operator_infos
+                .iter()
+                .any(|info| info.trait_path == "cpp_core::ops::IndexMut")
+                && !self.has_const_overload(cpp_function)
+            {
+                // `std::ops::IndexMut` requires `std::ops::Index`, so a non-const `operator[]`
+                // without a const overload (e.g. `std::map::operator[]`) implements both
+                operator_infos.push(TraitImplInfo::from_subscript_operator(true));
+            }
+            for operator_info in operator_infos {
                 match State::process_operator_as_trait_impl(
                     unnamed_function.clone(),
                     operator_info,
@@ -1482,35 +1983,180 @@ impl State<'_, '_> {
             }
         }
 
-        let desired_path = self.generate_rust_path(cpp_path, NameType::ApiFunction(item))?;
-        results.push(ProcessedFfiItem::Function(FunctionWithDesiredPath {
+        let template_id = self.generic_function_template_id(&item.id)?;
+        let mut desired_path = self.generate_rust_path(cpp_path, NameType::ApiFunction(item))?;
+        if cpp_item.is_protected_member() {
+            let trait_path = self
+                .data
+                .db
+                .find_rust_items_for_cpp_path(&cpp_path.parent()?, false)?
+                .filter_map(|item| item.item.as_trait_ref())
+                .map(|item| item.path.clone())
+                .next()
+                .ok_or_else(|| err_msg("protected members trait not found"))?;
+            desired_path = trait_path.join(desired_path.last());
+        }
+        let function = FunctionWithDesiredPath {
             function: unnamed_function,
             desired_path,
-        }));
+        };
+        if let Some(template_id) = template_id {
+            results.push(ProcessedFfiItem::TemplateInstantiation {
+                template_id,
+                function,
+            });
+        } else {
+            results.push(ProcessedFfiItem::Function(function));
+        }
         Ok(results)
     }
 
-    fn find_wrapper_type(&self, cpp_path: &CppPath) -> Result<DbItem<&RustItem>> {
+    /// Returns ID of the member function template if the FFI function `ffi_id`
+    /// is its instantiation and the template can be exposed as a generic method.
+    /// This is only possible if the template parameter can't be inferred from the arguments.
+    fn generic_function_template_id(&self, ffi_id: &ItemId) -> Result<Option<ItemId>> {
+        if !self.data.config.instantiate_template_functions() {
+            return Ok(None);
+        }
+        let cpp_item = self
+            .data
+            .db
+            .source_cpp_item(ffi_id)?
+            .ok_or_else(|| err_msg("source cpp item not found"))?;
+        let template_id = if let Some(id) = &cpp_item.source_id {
+            id
+        } else {
+            return Ok(None);
+        };
+        let template =
+            if let Some(function) = self.data.db.cpp_item(template_id)?.item.as_function_ref() {
+                function
+            } else {
+                return Ok(None);
+            };
+        if member_function_template_parameter(template).is_none()
+            || template
+                .arguments
+                .iter()
+                .any(|arg| arg.argument_type.is_or_contains_template_parameter())
+        {
+            return Ok(None);
+        }
+        Ok(Some(template_id.clone()))
+    }
+
+    /// Returns true if the non-const member function `function` has
+    /// a const overload with the same argument types.
+    fn has_const_overload(&self, function: &CppFunction) -> bool {
         self.data
             .db
-            .find_rust_items_for_cpp_path(cpp_path, true)?
-            .find(|item| item.item.is_wrapper_type())
-            .ok_or_else(|| {
-                format_err!("no Rust type wrapper for {}", cpp_path.to_cpp_pseudo_code())
+            .find_cpp_items_by_path(&function.path)
+            .filter_map(|item| item.item.as_function_ref())
+            .any(|other| {
+                other
+                    .member
+                    .as_ref()
+                    .map_or(false, |member| member.is_const)
+                    && other.argument_types_equal(function)
             })
     }
 
-    fn get_path_scope(
+    /// Uses the Rust type aliases corresponding to the C++ type aliases spelled
+    /// in the signature of the source function of the FFI function `ffi_id`
+    /// in the API types of `arguments` and `return_type`.
+    ///
+    /// Aliases are only applied to final signatures. Overload disambiguation and
+    /// trait impl uniqueness checks use the resolved types, so that names of functions
+    /// don't depend on the aliases and different spellings of a type are not
+    /// considered different types.
+    fn apply_type_aliases(
         &self,
-        parent_path: &CppPath,
-        name_type: NameType<'_>,
-    ) -> Result<RustPathScope> {
-        trace!("get_path_scope({:?}, {:?})", parent_path, name_type);
-        if let Some(hook) = self.data.config.rust_path_scope_hook() {
-            if let Some(strategy) = hook(parent_path)? {
-                return Ok(strategy);
-            }
-        }
+        ffi_id: &ItemId,
+        arguments: &mut [RustFunctionArgument],
+        return_type: &mut RustFinalType,
+    ) -> Result<()> {
+        let ffi_function =
+            if let Some(function) = self.data.db.ffi_item(ffi_id)?.item.as_function_ref() {
+                function
+            } else {
+                return Ok(());
+            };
+        let cpp_item = self
+            .data
+            .db
+            .source_cpp_item(ffi_id)?
+            .ok_or_else(|| err_msg("source cpp item not found"))?
+            .item;
+        let cpp_function = if let Some(function) = cpp_item.as_function_ref() {
+            function
+        } else {
+            return Ok(());
+        };
+        for arg in arguments {
+            let ffi_arg = ffi_function
+                .arguments
+                .get(arg.ffi_index)
+                .ok_or_else(|| err_msg("invalid ffi argument index"))?;
+            if let CppFfiArgumentMeaning::Argument(index) = ffi_arg.meaning {
+                arg.argument_type = self.use_type_alias(
+                    &arg.argument_type,
+                    cpp_function
+                        .arguments
+                        .get(index)
+                        .and_then(|arg| arg.type_alias.as_ref()),
+                );
+            }
+        }
+        *return_type = self.use_type_alias(return_type, cpp_function.return_type_alias.as_ref());
+        Ok(())
+    }
+
+    /// Uses the Rust type alias corresponding to the C++ type alias `alias_path`
+    /// in the API type of `rust_type`, if the alias is available.
+    fn use_type_alias(
+        &self,
+        rust_type: &RustFinalType,
+        alias_path: Option<&CppPath>,
+    ) -> RustFinalType {
+        let alias = alias_path.and_then(|path| {
+            self.data
+                .db
+                .find_rust_items_for_cpp_path(path, true)
+                .ok()?
+                .find_map(|item| item.item.as_type_alias_ref())
+        });
+        if let Some(alias) = alias {
+            let alias_type = RustType::Common(RustCommonType {
+                path: alias.path.clone(),
+                generic_arguments: None,
+            });
+            rust_type.with_api_type_alias(&alias.target_type, &alias_type)
+        } else {
+            rust_type.clone()
+        }
+    }
+
+    fn find_wrapper_type(&self, cpp_path: &CppPath) -> Result<DbItem<&RustItem>> {
+        self.data
+            .db
+            .find_rust_items_for_cpp_path(cpp_path, true)?
+            .find(|item| item.item.is_wrapper_type())
+            .ok_or_else(|| {
+                format_err!("no Rust type wrapper for {}", cpp_path.to_cpp_pseudo_code())
+            })
+    }
+
+    fn get_path_scope(
+        &self,
+        parent_path: &CppPath,
+        name_type: NameType<'_>,
+    ) -> Result<RustPathScope> {
+        trace!("get_path_scope({:?}, {:?})", parent_path, name_type);
+        if let Some(hook) = self.data.config.rust_path_scope_hook() {
+            if let Some(strategy) = hook(parent_path)? {
+                return Ok(strategy);
+            }
+        }
 
         let allow_module_for_nested;
         let allow_wrapper_type;
@@ -1524,11 +2170,17 @@ impl State<'_, '_> {
                 allow_wrapper_type = true;
             }
         };
+        // instantiations of member function templates can belong to a class
+        // of a dependency; they are moved to an extension trait later
+        let allow_dependencies = match name_type {
+            NameType::ApiFunction(_) => true,
+            _ => false,
+        };
 
         let mut rust_items = match self
             .data
             .db
-            .find_rust_items_for_cpp_path(parent_path, false)
+            .find_rust_items_for_cpp_path(parent_path, allow_dependencies)
         {
             Ok(r) => r,
             Err(err) => match name_type {
@@ -1582,6 +2234,12 @@ impl State<'_, '_> {
     fn type_list_caption(&self, types: &[CppType], context: &RustPath) -> Result<String> {
         let mut captions = Vec::new();
         for arg in types {
+            if arg.is_integral_constant() {
+                // values of non-type template arguments are included as is,
+                // e.g. `std::array<int, 8>` becomes `ArrayOfI328`
+                captions.push(arg.ascii_caption());
+                continue;
+            }
             let rust_type = self.rust_final_type(
                 &ffi_type(arg, CppTypeRole::NotReturnType)?,
                 &CppFfiArgumentMeaning::Argument(0),
@@ -1649,11 +2307,11 @@ impl State<'_, '_> {
                 }
             }
             CppFfiFunctionKind::FieldAccessor { accessor_type } => {
-                let field = cpp_item
-                    .as_field_ref()
+                let path = cpp_item
+                    .path()
                     .ok_or_else(|| err_msg("invalid source cpp item type"))?;
 
-                let name = &field.path.last().name;
+                let name = &path.last().name;
                 let function_name = match accessor_type {
                     CppFieldAccessorType::CopyGetter | CppFieldAccessorType::ConstRefGetter => {
                         name.to_string()
@@ -1663,6 +2321,7 @@ impl State<'_, '_> {
                 };
                 Some(function_name)
             }
+            CppFfiFunctionKind::DefaultArgument { .. } => None,
         };
 
         Ok(r)
@@ -1686,6 +2345,17 @@ impl State<'_, '_> {
         }
     }
 
+    /// Returns Rust path of the wrapper of the class subclassed by a generated
+    /// virtual subclass.
+    fn virtual_subclass_base_rust_path(&self, base_class_path: &CppPath) -> Result<RustPath> {
+        let base_type = self.find_wrapper_type(base_class_path)?;
+        base_type
+            .item
+            .path()
+            .cloned()
+            .ok_or_else(|| err_msg("wrapper type must have path"))
+    }
+
     fn generate_rust_path(&self, cpp_path: &CppPath, name_type: NameType<'_>) -> Result<RustPath> {
         if let Some(hook) = self.data.config.rust_path_hook() {
             if let Some(path) = hook(cpp_path, name_type.clone(), &self.data)? {
@@ -1701,13 +2371,26 @@ impl State<'_, '_> {
                 path: self.special_module_paths[&RustSpecialModuleKind::SizedTypes].clone(),
                 prefix: None,
             },
+            NameType::MacroConstant => RustPathScope {
+                path: self.special_module_paths[&RustSpecialModuleKind::Macros].clone(),
+                prefix: None,
+            },
             NameType::QtSlotWrapper { .. } | NameType::QtSignalWrapper { .. } => {
                 // crate root
                 self.default_path_scope()
             }
+            NameType::VirtualSubclass { base_class_path } => {
+                // same module as the base class
+                let base_path = self.virtual_subclass_base_rust_path(base_class_path)?;
+                RustPathScope {
+                    path: base_path.parent()?,
+                    prefix: None,
+                }
+            }
             NameType::Type { .. }
             | NameType::Module { .. }
             | NameType::EnumValue
+            | NameType::Constant
             | NameType::ApiFunction { .. }
             | NameType::ReceiverFunction { .. } => {
                 if let Ok(parent) = cpp_path.parent() {
@@ -1787,6 +2470,10 @@ impl State<'_, '_> {
             NameType::Module { .. } => self
                 .cpp_path_item_to_name(&cpp_path.last(), &scope.path, &name_type)?
                 .to_snake_case(),
+            NameType::Constant => self
+                .cpp_path_item_to_name(&cpp_path.last(), &scope.path, &name_type)?
+                .to_upper_case_words(),
+            NameType::MacroConstant => cpp_path.last().name.clone(),
             NameType::FfiFunction => cpp_path.last().name.clone(),
             NameType::QtSlotWrapper { signal_arguments } => {
                 if signal_arguments.is_empty() {
@@ -1804,6 +2491,10 @@ impl State<'_, '_> {
                     format!("SignalOf_{}", captions).to_class_case()
                 }
             }
+            NameType::VirtualSubclass { base_class_path } => {
+                let base_path = self.virtual_subclass_base_rust_path(base_class_path)?;
+                format!("{}Subclass", base_path.last())
+            }
         };
 
         if name_type == NameType::FfiFunction {
@@ -1842,6 +2533,9 @@ impl State<'_, '_> {
             CppFfiItem::QtSignalWrapper(_) => {
                 bail!("signal wrappers do not need to be processed here");
             }
+            CppFfiItem::VirtualSubclass(_) => {
+                bail!("virtual subclasses do not need to be processed here");
+            }
         }
     }
 
@@ -1873,14 +2567,20 @@ impl State<'_, '_> {
 
         let mut qt_slot_wrapper = None;
         let mut qt_signal_wrapper = None;
+        let mut virtual_subclass = None;
         if let Some(source_ffi_item) = self.data.db.source_ffi_item(&item.id)? {
             if let Some(item) = source_ffi_item
                 .clone()
                 .filter_map(|i| i.as_slot_wrapper_ref())
             {
                 qt_slot_wrapper = Some(item);
-            } else if let Some(item) = source_ffi_item.filter_map(|i| i.as_signal_wrapper_ref()) {
+            } else if let Some(item) = source_ffi_item
+                .clone()
+                .filter_map(|i| i.as_signal_wrapper_ref())
+            {
                 qt_signal_wrapper = Some(item);
+            } else if let Some(item) = source_ffi_item.filter_map(|i| i.as_virtual_subclass_ref()) {
+                virtual_subclass = Some(item);
             }
         }
 
@@ -1896,6 +2596,10 @@ impl State<'_, '_> {
             NameType::QtSignalWrapper {
                 signal_arguments: &wrapper.item.signal_arguments,
             }
+        } else if let Some(subclass) = &virtual_subclass {
+            NameType::VirtualSubclass {
+                base_class_path: &subclass.item.base_class_path,
+            }
         } else {
             NameType::Type {
                 is_from_other_crate,
@@ -1906,10 +2610,8 @@ impl State<'_, '_> {
 
         let mut rust_items = Vec::new();
 
-        let is_movable = false;
-
         let wrapper_kind;
-        if is_movable {
+        if is_movable(self.data, &data.path)? {
             let internal_path = self.generate_rust_path(&data.path, NameType::SizedItem)?;
 
             if internal_path == public_path {
@@ -1999,6 +2701,24 @@ impl State<'_, '_> {
             qt_receiver_data = None;
         }
 
+        if self.classes_with_protected_members.contains(&data.path) {
+            let trait_path = self.data.db.make_unique_rust_path(
+                &public_path
+                    .parent()?
+                    .join(format!("{}Protected", public_path.last())),
+            );
+            rust_items.push(RustItem::Trait(RustTrait {
+                path: trait_path,
+                is_public: true,
+                associated_types: Vec::new(),
+                functions: Vec::new(),
+                implemented_for: Some(RustType::Common(RustCommonType {
+                    path: public_path.clone(),
+                    generic_arguments: None,
+                })),
+            }));
+        }
+
         let public_rust_item = RustItem::Struct(RustStruct {
             path: public_path,
             kind: RustStructKind::WrapperType(wrapper_kind),
@@ -2031,20 +2751,38 @@ impl State<'_, '_> {
                 });
                 Ok(vec![rust_item])
             }
+            CppItem::Type(data) if data.is_deleted => bail!("type is unavailable"),
             CppItem::Type(data) => match data.kind {
-                CppTypeDeclarationKind::Class { .. } => {
+                CppTypeDeclarationKind::Class { .. } | CppTypeDeclarationKind::Union => {
                     self.process_cpp_class(cpp_item.map(|v| v.as_type_ref().unwrap()))
                 }
-                CppTypeDeclarationKind::Enum => {
+                CppTypeDeclarationKind::Enum { underlying_type } => {
                     let rust_path = self.generate_rust_path(
                         &data.path,
                         NameType::Type {
                             is_from_other_crate: false,
                         },
                     )?;
+                    let exhaustive_repr =
+                        if self.data.config.exhaustive_enums().contains(&data.path) {
+                            let repr = enum_repr_type(underlying_type);
+                            if repr.is_none() {
+                                debug!(
+                                    "can't generate exhaustive enum for {} with underlying type {}",
+                                    data.path.to_cpp_pseudo_code(),
+                                    underlying_type.to_cpp_pseudo_code()
+                                );
+                            }
+                            repr
+                        } else {
+                            None
+                        };
                     let rust_item = RustItem::Struct(RustStruct {
                         path: rust_path,
-                        kind: RustStructKind::WrapperType(RustWrapperTypeKind::EnumWrapper),
+                        kind: RustStructKind::WrapperType(RustWrapperTypeKind::EnumWrapper {
+                            underlying_type: self.ffi_type_to_rust_ffi_type(underlying_type)?,
+                            exhaustive_repr,
+                        }),
                         is_public: true,
                         qt_receiver_data: None,
                     });
@@ -2052,12 +2790,56 @@ impl State<'_, '_> {
                     Ok(vec![rust_item])
                 }
             },
+            CppItem::TypeAlias(alias) => {
+                let target_type = match &alias.target_type {
+                    CppType::Class(path) if is_qflags(path) => {
+                        let enum_path = match &path.last().template_arguments.as_ref().unwrap()[0] {
+                            CppType::Enum { path } => path,
+                            _ => bail!("invalid QFlags argument type"),
+                        };
+                        let rust_enum_type = self.find_wrapper_type(enum_path)?;
+                        let rust_enum_path = rust_enum_type
+                            .item
+                            .path()
+                            .ok_or_else(|| err_msg("Rust enum type has no path"))?;
+                        self.create_qflags(rust_enum_path)
+                    }
+                    target_type => self.ffi_type_to_rust_ffi_type(target_type)?,
+                };
+                let rust_path = self.generate_rust_path(
+                    &alias.path,
+                    NameType::Type {
+                        is_from_other_crate: false,
+                    },
+                )?;
+                let rust_item = RustItem::TypeAlias(RustTypeAlias {
+                    path: rust_path,
+                    target_type,
+                });
+                Ok(vec![rust_item])
+            }
             CppItem::EnumValue(value) => {
                 let rust_path = self.generate_rust_path(&value.path, NameType::EnumValue)?;
+                let enum_path = value.path.parent()?;
+                let underlying_type = self
+                    .data
+                    .db
+                    .find_cpp_items_by_path(&enum_path)
+                    .filter_map(|item| item.item.as_type_ref())
+                    .find_map(|declaration| match &declaration.kind {
+                        CppTypeDeclarationKind::Enum { underlying_type } => Some(underlying_type),
+                        _ => None,
+                    })
+                    .ok_or_else(|| {
+                        format_err!(
+                            "enum declaration not found: {}",
+                            enum_path.to_cpp_pseudo_code()
+                        )
+                    })?;
 
                 let rust_item = RustItem::EnumValue(RustEnumValue {
                     path: rust_path,
-                    value: value.value,
+                    value: enum_value_literal(value.value, underlying_type),
                 });
 
                 Ok(vec![rust_item])
@@ -2156,13 +2938,73 @@ impl State<'_, '_> {
                 };
                 Ok(vec![RustItem::Function(rust_function)])
             }
-            CppItem::ClassField(_) | CppItem::ClassBase(_) => {
+            CppItem::ClassField(field) => {
+                if let Some(value) = &field.value {
+                    if field.visibility != CppVisibility::Public {
+                        bail!("field is not public");
+                    }
+                    self.process_constant(&field.path, &field.field_type, value, NameType::Constant)
+                } else {
+                    // only need to process FFI items
+                    Ok(Vec::new())
+                }
+            }
+            CppItem::Variable(variable) => {
+                if let Some(value) = &variable.value {
+                    self.process_constant(
+                        &variable.path,
+                        &variable.variable_type,
+                        value,
+                        NameType::Constant,
+                    )
+                } else {
+                    // only need to process FFI items
+                    Ok(Vec::new())
+                }
+            }
+            CppItem::MacroConstant(constant) => self.process_constant(
+                &constant.path,
+                &constant.value_type,
+                &constant.value,
+                NameType::MacroConstant,
+            ),
+            CppItem::ClassBase(_) => {
                 // only need to process FFI items
                 Ok(Vec::new())
             }
         }
     }
 
+    /// Generates a Rust constant for a C++ constant evaluated by the parser.
+    fn process_constant(
+        &self,
+        cpp_path: &CppPath,
+        cpp_type: &CppType,
+        value: &CppConstantValue,
+        name_type: NameType<'_>,
+    ) -> Result<Vec<RustItem>> {
+        let value_type = self.ffi_type_to_rust_ffi_type(cpp_type)?;
+        let value = match (cpp_type, value) {
+            (CppType::BuiltInNumeric(CppBuiltInNumericType::Bool), value) => {
+                let is_true = match value {
+                    CppConstantValue::SignedInteger(v) => *v != 0,
+                    CppConstantValue::UnsignedInteger(v) => *v != 0,
+                    CppConstantValue::FloatingPoint(_) => bail!("invalid bool value"),
+                };
+                is_true.to_string()
+            }
+            (_, CppConstantValue::SignedInteger(v)) => v.to_string(),
+            (_, CppConstantValue::UnsignedInteger(v)) => v.to_string(),
+            (_, CppConstantValue::FloatingPoint(v)) => v.clone(),
+        };
+        let path = self.generate_rust_path(cpp_path, name_type)?;
+        Ok(vec![RustItem::Constant(RustConstant {
+            path,
+            value_type,
+            value,
+        })])
+    }
+
     fn generate_crate_reexport(&mut self, crate_name: &str) -> Result<()> {
         let path = RustPath::from_parts(vec![
             self.data.config.crate_properties().name().to_string(),
@@ -2211,12 +3053,15 @@ impl State<'_, '_> {
             RustSpecialModuleKind::Ffi => vec![crate_name, "__ffi".to_string()],
             RustSpecialModuleKind::Ops => vec![crate_name, "ops".to_string()],
             RustSpecialModuleKind::SizedTypes => vec![crate_name, "__sized_types".to_string()],
+            RustSpecialModuleKind::Macros => vec![crate_name, "macros".to_string()],
         };
         let rust_path = RustPath::from_parts(rust_path_parts);
 
         let rust_item = RustItem::Module(RustModule {
             is_public: match kind {
-                RustSpecialModuleKind::CrateRoot | RustSpecialModuleKind::Ops => true,
+                RustSpecialModuleKind::CrateRoot
+                | RustSpecialModuleKind::Ops
+                | RustSpecialModuleKind::Macros => true,
                 RustSpecialModuleKind::Ffi | RustSpecialModuleKind::SizedTypes => false,
             },
             path: rust_path.clone(),
@@ -2277,14 +3122,15 @@ impl State<'_, '_> {
         Ok(())
     }
 
-    fn process_ffi_items(
-        &mut self,
-    ) -> Result<BTreeMap<RustPath, Vec<ItemWithSource<FunctionWithDesiredPath>>>> {
-        let mut grouped_functions = BTreeMap::<_, Vec<_>>::new();
+    fn process_ffi_items(&mut self) -> Result<(GroupedFunctions, TemplateInstantiations)> {
+        let mut grouped_functions = GroupedFunctions::new();
+        let mut template_instantiations = TemplateInstantiations::new();
+        // impls of dependencies are also checked because operator impls
+        // can be added for their types
         let mut trait_types = self
             .data
             .db
-            .rust_items()
+            .all_rust_items()
             .filter_map(|item| item.item.as_trait_impl_ref())
             .map(TraitTypes::from)
             .collect_vec();
@@ -2304,19 +3150,37 @@ impl State<'_, '_> {
                 Ok(results) => {
                     for item in results {
                         match item {
-                            ProcessedFfiItem::Item(rust_item) => {
-                                if let RustItem::TraitImpl(trait_impl) = &rust_item {
-                                    trait_types.push(trait_impl.into());
+                            ProcessedFfiItem::Item(mut rust_item) => {
+                                if let RustItem::TraitImpl(trait_impl) = &mut rust_item {
+                                    trait_types.push(TraitTypes::from(&*trait_impl));
+                                    for function in &mut trait_impl.functions {
+                                        self.apply_type_aliases(
+                                            &ffi_item_id,
+                                            &mut function.arguments,
+                                            &mut function.return_type,
+                                        )?;
+                                    }
                                 }
 
                                 self.add_rust_item(Some(ffi_item_id.clone()), rust_item)?;
                             }
-                            ProcessedFfiItem::Function(function) => {
+                            ProcessedFfiItem::Function(mut function) => {
+                                function.desired_path =
+                                    self.extension_trait_function_path(&function.desired_path)?;
                                 let entry = grouped_functions
                                     .entry(function.desired_path.clone())
                                     .or_default();
                                 entry.push(ItemWithSource::new(&ffi_item_id, function));
                             }
+                            ProcessedFfiItem::TemplateInstantiation {
+                                template_id,
+                                function,
+                            } => {
+                                template_instantiations
+                                    .entry(template_id)
+                                    .or_default()
+                                    .push(ItemWithSource::new(&ffi_item_id, function));
+                            }
                         }
                     }
                 }
@@ -2331,7 +3195,7 @@ impl State<'_, '_> {
                 }
             }
         }
-        Ok(grouped_functions)
+        Ok((grouped_functions, template_instantiations))
     }
 
     fn try_caption_strategy(
@@ -2354,10 +3218,7 @@ impl State<'_, '_> {
         Ok(())
     }
 
-    fn finalize_functions(
-        &mut self,
-        grouped_functions: BTreeMap<RustPath, Vec<ItemWithSource<FunctionWithDesiredPath>>>,
-    ) -> Result<()> {
+    fn finalize_functions(&mut self, grouped_functions: GroupedFunctions) -> Result<()> {
         let all_strategies = RustFunctionCaptionStrategy::all();
 
         for (_group_path, functions) in grouped_functions {
@@ -2398,96 +3259,1265 @@ impl State<'_, '_> {
                     function.item.desired_path
                 };
                 let final_path = self.data.db.make_unique_rust_path(&path);
-                let item = RustItem::Function(function.item.function.with_path(final_path));
-                self.add_rust_item(Some(function.source_id), item)?;
+                let mut rust_function = function.item.function.with_path(final_path);
+                self.apply_type_aliases(
+                    &function.source_id,
+                    &mut rust_function.arguments,
+                    &mut rust_function.return_type,
+                )?;
+                self.add_rust_item(Some(function.source_id), RustItem::Function(rust_function))?;
             }
         }
         Ok(())
     }
-}
 
-pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
-    let mut state = State {
-        data,
-        special_module_paths: HashMap::new(),
-    };
-    for &module in &[
-        RustSpecialModuleKind::CrateRoot,
-        RustSpecialModuleKind::Ffi,
-        RustSpecialModuleKind::Ops,
-        RustSpecialModuleKind::SizedTypes,
-    ] {
-        state.generate_special_module(module)?;
+    /// Generates generic methods for member function templates.
+    /// Instantiations of templates that can't be exposed as a generic method
+    /// are added to `grouped_functions` and become regular methods.
+    fn generate_template_functions(
+        &mut self,
+        template_instantiations: TemplateInstantiations,
+        grouped_functions: &mut GroupedFunctions,
+    ) -> Result<()> {
+        for (template_id, functions) in template_instantiations {
+            if let Err(err) = self.generate_template_function(&template_id, &functions) {
+                debug!(
+                    "failed to generate generic function for {}: {}",
+                    template_id, err
+                );
+                for mut function in functions {
+                    function.item.desired_path =
+                        self.extension_trait_function_path(&function.item.desired_path)?;
+                    grouped_functions
+                        .entry(function.item.desired_path.clone())
+                        .or_default()
+                        .push(function);
+                }
+            }
+        }
+        Ok(())
     }
 
-    state.generate_crate_reexport("cpp_core")?;
-    let dependencies = state
-        .data
-        .config
-        .crate_properties()
-        .dependencies()
-        .iter()
-        .filter(|dep| dep.kind() == CrateDependencyKind::Ritual);
-    for dependency in dependencies {
-        state.generate_crate_reexport(dependency.name())?;
+    /// Returns the path of the function that would have `desired_path`
+    /// if the Rust struct containing it belonged to the current crate.
+    /// Methods of structs of dependencies can't be added to the structs,
+    /// so they are placed in an extension trait implemented for the struct.
+    /// The trait is created if it doesn't exist yet.
+    fn extension_trait_function_path(&mut self, desired_path: &RustPath) -> Result<RustPath> {
+        let struct_path = desired_path.parent()?;
+        if struct_path.crate_name() == self.data.db.crate_name() {
+            return Ok(desired_path.clone());
+        }
+        let struct_type = RustType::Common(RustCommonType {
+            path: struct_path.clone(),
+            generic_arguments: None,
+        });
+        let existing_trait_path = self
+            .data
+            .db
+            .rust_items()
+            .filter_map(|item| item.item.as_trait_ref())
+            .find(|item| item.implemented_for.as_ref() == Some(&struct_type))
+            .map(|item| item.path.clone());
+        let trait_path = if let Some(path) = existing_trait_path {
+            path
+        } else {
+            let struct_source_id = self
+                .data
+                .db
+                .all_rust_items()
+                .find(|item| item.item.is_wrapper_type() && item.item.path() == Some(&struct_path))
+                .map(|item| item.source_id.clone())
+                .ok_or_else(|| format_err!("struct not found: {}", struct_path.full_name(None)))?;
+            let trait_path = self.data.db.make_unique_rust_path(
+                &self
+                    .default_path_scope()
+                    .path
+                    .join(format!("{}Ext", struct_path.last())),
+            );
+            let rust_trait = RustTrait {
+                path: trait_path.clone(),
+                is_public: true,
+                associated_types: Vec::new(),
+                functions: Vec::new(),
+                implemented_for: Some(struct_type),
+            };
+            self.add_rust_item(struct_source_id, RustItem::Trait(rust_trait))?;
+            trait_path
+        };
+        Ok(trait_path.join(desired_path.last()))
     }
 
-    state.process_cpp_items()?;
-    let grouped_functions = state.process_ffi_items()?;
-    state.finalize_functions(grouped_functions)?;
-
-    Ok(())
-}
+    /// Generates a trait implemented for all supported template arguments
+    /// of the member function template `template_id` (unless a dependency
+    /// already has it), a generic method that calls the trait's function,
+    /// and trait implementations that call the instantiations.
+    fn generate_template_function(
+        &mut self,
+        template_id: &ItemId,
+        functions: &[ItemWithSource<FunctionWithDesiredPath>],
+    ) -> Result<()> {
+        let template = self
+            .data
+            .db
+            .cpp_item(template_id)?
+            .item
+            .as_function_ref()
+            .ok_or_else(|| err_msg("invalid template item type"))?
+            .clone();
 
-fn detect_callback_function(function: &UnnamedRustFunction) -> Option<&RustFunctionPointerType> {
-    if function.arguments.len() < 3 {
-        return None;
-    }
-    let args = &function.arguments[function.arguments.len() - 3..];
-    let void_ptr = RustType::new_pointer(
-        false,
-        RustType::Common(RustCommonType {
-            path: RustPath::from_good_str("std::ffi::c_void"),
-            generic_arguments: None,
-        }),
-    );
-    if args[0].name != "callback" {
-        return None;
-    }
-    let callback_type;
-    if let RustType::Common(common) = &args[0].argument_type.ffi_type() {
-        if common.path != "std::option::Option" {
-            return None;
-        }
-        if let Some(args) = &common.generic_arguments {
-            if args.len() == 1 {
-                if let RustType::FunctionPointer(t) = &args[0] {
-                    callback_type = t;
-                } else {
-                    return None;
+        // if both `T` and `T*` are supported, only `T` is used
+        let mut chosen = Vec::<(CppPath, bool, &ItemWithSource<FunctionWithDesiredPath>)>::new();
+        for function in functions {
+            let cpp_function = self
+                .data
+                .db
+                .source_cpp_item(&function.source_id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            let template_argument = cpp_function
+                .path
+                .last()
+                .template_arguments
+                .as_ref()
+                .and_then(|args| args.get(0));
+            let (class_path, is_pointer) = match template_argument {
+                Some(CppType::Class(path)) => (path.clone(), false),
+                Some(CppType::PointerLike {
+                    kind: CppPointerLikeTypeKind::Pointer,
+                    is_const: false,
+                    target,
+                }) => match &**target {
+                    CppType::Class(path) => (path.clone(), true),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let rust_function = &function.item.function;
+            if rust_function.return_type.api_type().lifetime().is_some()
+                || rust_function.arguments.iter().any(|arg| {
+                    arg.argument_type.api_type().lifetime().is_some()
+                        || arg.argument_type.conversion().as_callback_ref().is_some()
+                })
+            {
+                bail!("references and callbacks are not supported");
+            }
+            if let Some(existing) = chosen.iter_mut().find(|item| item.0 == class_path) {
+                if existing.1 && !is_pointer {
+                    *existing = (class_path, is_pointer, function);
                 }
             } else {
-                return None;
+                chosen.push((class_path, is_pointer, function));
             }
+        }
+        let first_function = &chosen
+            .get(0)
+            .ok_or_else(|| err_msg("no supported instantiations"))?
+            .2
+            .item;
+
+        let method_name =
+            sanitize_rust_identifier(&template.path.last().name.to_snake_case(), false);
+        let call_arguments = first_function
+            .function
+            .arguments
+            .iter()
+            .map(|arg| arg.name.clone())
+            .collect_vec();
+        let trait_arguments = first_function
+            .function
+            .arguments
+            .iter()
+            .map(|arg| {
+                let mut arg = arg.clone();
+                if arg.name == "self" {
+                    arg.name = "object".into();
+                }
+                arg
+            })
+            .collect_vec();
+
+        // the trait of a template of a dependency is reused if the dependency has it
+        let dependency_trait_path = if template_id.crate_name() == self.data.db.crate_name() {
+            None
         } else {
-            return None;
+            self.data
+                .db
+                .all_rust_items()
+                .filter(|item| item.source_id.as_ref() == Some(template_id))
+                .filter_map(|item| item.item.as_trait_ref())
+                .map(|item| item.path.clone())
+                .next()
+        };
+
+        let trait_path = if let Some(trait_path) = dependency_trait_path {
+            trait_path
+        } else {
+            let struct_path = first_function.desired_path.parent()?;
+            let wrapper_path =
+                self.extension_trait_function_path(&struct_path.join(method_name.clone()))?;
+            let trait_name = format!(
+                "{}{}Arg",
+                struct_path.last(),
+                template.path.last().name.to_class_case()
+            );
+            let trait_path = self
+                .data
+                .db
+                .make_unique_rust_path(&wrapper_path.parent()?.parent()?.join(trait_name));
+            let trait_function = RustFunction {
+                path: trait_path.join(method_name.clone()),
+                is_public: true,
+                is_unsafe: true,
+                kind: first_function.function.kind.clone(),
+                arguments: trait_arguments.clone(),
+                return_type: RustFinalType::new(
+                    RustType::GenericParameter("Self::Output".into()),
+                    RustToFfiTypeConversion::None,
+                )?,
+            };
+            let rust_trait = RustTrait {
+                path: trait_path.clone(),
+                is_public: true,
+                associated_types: vec!["Output".into()],
+                functions: vec![trait_function],
+                implemented_for: None,
+            };
+            self.add_rust_item(Some(template_id.clone()), RustItem::Trait(rust_trait))?;
+
+            let wrapper = RustFunction {
+                path: self.data.db.make_unique_rust_path(&wrapper_path),
+                is_public: true,
+                is_unsafe: true,
+                kind: RustFunctionKind::TemplateWrapper(RustTemplateWrapperData {
+                    trait_path: trait_path.clone(),
+                    call_arguments,
+                }),
+                arguments: first_function.function.arguments.clone(),
+                return_type: RustFinalType::new(
+                    RustType::GenericParameter("T::Output".into()),
+                    RustToFfiTypeConversion::None,
+                )?,
+            };
+            self.add_rust_item(Some(template_id.clone()), RustItem::Function(wrapper))?;
+            trait_path
+        };
+
+        for (class_path, _, function) in chosen {
+            let target_type = self.ffi_type_to_rust_ffi_type(&CppType::Class(class_path))?;
+            let parent_path = if trait_path.crate_name() == self.data.db.crate_name() {
+                trait_path.parent()?
+            } else {
+                target_type.as_common()?.path.parent()?
+            };
+            let mut rust_function = function
+                .item
+                .function
+                .clone()
+                .with_path(trait_path.join(method_name.clone()));
+            rust_function.arguments = trait_arguments.clone();
+            let trait_impl = RustTraitImpl {
+                parent_path,
+                target_type,
+                trait_type: RustCommonType {
+                    path: trait_path.clone(),
+                    generic_arguments: None,
+                },
+                associated_types: vec![RustTraitAssociatedType {
+                    name: "Output".into(),
+                    value: rust_function.return_type.api_type().clone(),
+                }],
+                functions: vec![rust_function],
+                extra_kind: RustTraitImplExtraKind::Normal,
+            };
+            self.add_rust_item(
+                Some(function.source_id.clone()),
+                RustItem::TraitImpl(trait_impl),
+            )?;
         }
-    } else {
-        return None;
+        Ok(())
     }
-    if args[1].name != "deleter" {
-        return None;
+
+    /// Generates a trait for overriding virtual functions of the base class
+    /// of each virtual subclass, and a method of the subclass that overrides
+    /// its virtual functions with an implementation of the trait.
+    fn generate_virtual_subclass_traits(&mut self) -> Result<()> {
+        let subclasses = self
+            .data
+            .db
+            .ffi_items()
+            .filter_map(|item| item.item.as_virtual_subclass_ref())
+            .cloned()
+            .collect_vec();
+        for subclass in subclasses {
+            if let Err(err) = self.generate_virtual_subclass_trait(&subclass) {
+                debug!(
+                    "failed to generate trait for virtual subclass of {}: {}",
+                    subclass.base_class_path.to_cpp_pseudo_code(),
+                    err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_virtual_subclass_trait(&mut self, subclass: &CppVirtualSubclass) -> Result<()> {
+        let struct_item = self.find_wrapper_type(&subclass.class_path)?;
+        let struct_source_id = struct_item.source_id.clone();
+        let struct_path = struct_item
+            .item
+            .path()
+            .cloned()
+            .ok_or_else(|| err_msg("wrapper type must have path"))?;
+        let base_path = self.virtual_subclass_base_rust_path(&subclass.base_class_path)?;
+
+        // generated methods of the subclass (setters and base class implementations)
+        let mut methods = HashMap::new();
+        for item in self.data.db.rust_items() {
+            let function = match item.item.as_function_ref() {
+                Some(function) if function.kind.is_ffi_wrapper() => function,
+                _ => continue,
+            };
+            let source_id = match &item.source_id {
+                Some(id) => id,
+                None => continue,
+            };
+            let cpp_path = match self.data.db.source_cpp_item(&item.id)? {
+                Some(cpp_item) => cpp_item.item.path().cloned(),
+                None => None,
+            };
+            if let Some(cpp_path) = cpp_path {
+                if cpp_path.parent().ok().as_ref() == Some(&subclass.class_path) {
+                    methods.insert(
+                        cpp_path.last().name.clone(),
+                        ItemWithSource::new(source_id, function.clone()),
+                    );
+                }
+            }
+        }
+
+        let mut trait_functions = Vec::new();
+        let mut self_arg = None;
+        for virtual_function in &subclass.virtual_functions {
+            let setter = if let Some(setter) = methods.get(&virtual_function.setter_name()) {
+                setter
+            } else {
+                trace!(
+                    "callback setter not found for {}",
+                    virtual_function.function.short_text()
+                );
+                continue;
+            };
+            let conversion = setter
+                .item
+                .arguments
+                .iter()
+                .find_map(|arg| arg.argument_type.conversion().as_callback_ref())
+                .ok_or_else(|| err_msg("callback setter must accept a closure"))?;
+            let object_arg = setter
+                .item
+                .arguments
+                .iter()
+                .find(|arg| arg.name == "self")
+                .ok_or_else(|| err_msg("callback setter must have self argument"))?;
+            self_arg = Some(object_arg.clone());
+
+            let argument_names = virtual_function
+                .function
+                .arguments
+                .iter()
+                .enumerate()
+                .map(|(index, arg)| {
+                    let name = sanitize_rust_identifier(&arg.name.to_snake_case(), false);
+                    if name.is_empty() || name == "object" {
+                        format!("arg{}", index)
+                    } else {
+                        name
+                    }
+                })
+                .collect_vec();
+            let mut arguments = vec![
+                RustFunctionArgument {
+                    name: "self".into(),
+                    argument_type: RustFinalType::new(
+                        RustType::PointerLike {
+                            kind: RustPointerLikeTypeKind::Reference { lifetime: None },
+                            is_const: true,
+                            target: Box::new(RustType::GenericParameter("Self".into())),
+                        },
+                        RustToFfiTypeConversion::None,
+                    )?,
+                    ffi_index: 0,
+                },
+                RustFunctionArgument {
+                    name: "object".into(),
+                    ..object_arg.clone()
+                },
+            ];
+            for (index, (name, argument_type)) in argument_names
+                .iter()
+                .zip(&conversion.closure_arguments)
+                .enumerate()
+            {
+                arguments.push(RustFunctionArgument {
+                    name: name.clone(),
+                    argument_type: argument_type.clone(),
+                    ffi_index: index + 1,
+                });
+            }
+
+            let base_function_path = methods
+                .get(&virtual_function.base_function_name())
+                .map(|function| function.item.path.clone());
+            let name = sanitize_rust_identifier(&virtual_function.name.to_snake_case(), false);
+            let function = UnnamedRustFunction {
+                is_public: true,
+                is_unsafe: true,
+                kind: RustFunctionKind::VirtualFunction(RustVirtualFunctionData {
+                    base_function_path,
+                    call_arguments: once("object".to_string()).chain(argument_names).collect(),
+                }),
+                arguments,
+                return_type: conversion.closure_return_type.clone(),
+            };
+            trait_functions.push((name, setter, function));
+        }
+        let self_arg = self_arg.ok_or_else(|| err_msg("no virtual functions can be overridden"))?;
+
+        let trait_path = self.data.db.make_unique_rust_path(
+            &struct_path
+                .parent()?
+                .join(format!("{}Overrides", base_path.last())),
+        );
+        let rust_trait = RustTrait {
+            path: trait_path.clone(),
+            is_public: true,
+            associated_types: Vec::new(),
+            functions: Vec::new(),
+            implemented_for: None,
+        };
+        self.add_rust_item(struct_source_id.clone(), RustItem::Trait(rust_trait))?;
+
+        let mut overrides = Vec::new();
+        for (name, setter, function) in trait_functions {
+            let path = self.data.db.make_unique_rust_path(&trait_path.join(name));
+            overrides.push(RustOverrideData {
+                setter_path: setter.item.path.clone(),
+                function_name: path.last().to_string(),
+                // names of the closure arguments can't conflict with
+                // the variables used in the setter's body
+                argument_names: (0..function.arguments.len() - 2)
+                    .map(|index| format!("arg{}", index))
+                    .collect(),
+            });
+            let function = function.with_path(path);
+            self.add_rust_item(Some(setter.source_id.clone()), RustItem::Function(function))?;
+        }
+
+        let setter = RustFunction {
+            path: self
+                .data
+                .db
+                .make_unique_rust_path(&struct_path.join("set_overrides")),
+            is_public: true,
+            is_unsafe: true,
+            kind: RustFunctionKind::OverridesSetter(RustOverridesSetterData {
+                trait_path,
+                overrides,
+            }),
+            arguments: vec![
+                self_arg,
+                RustFunctionArgument {
+                    name: "overrides".into(),
+                    argument_type: RustFinalType::new(
+                        RustType::GenericParameter("T".into()),
+                        RustToFfiTypeConversion::None,
+                    )?,
+                    ffi_index: 0,
+                },
+            ],
+            return_type: RustFinalType::new(RustType::unit(), RustToFfiTypeConversion::None)?,
+        };
+        self.add_rust_item(struct_source_id, RustItem::Function(setter))?;
+        Ok(())
+    }
+
+    /// Generates a generic `call` method and a trait for its arguments
+    /// for each class that has multiple overloads of `operator()`.
+    /// The overloads are still available as separate methods. The trait is implemented
+    /// by `generate_call_operator_impls` when the final paths of these methods are known.
+    fn generate_call_operators(
+        &mut self,
+        grouped_functions: &GroupedFunctions,
+    ) -> Result<Vec<CallOperatorOverloads>> {
+        let mut results = Vec::new();
+        for (group_path, functions) in grouped_functions {
+            if functions.len() < 2 || !self.is_call_operator_group(functions)? {
+                continue;
+            }
+            match self.generate_call_operator(functions) {
+                Ok(r) => results.push(r),
+                Err(err) => {
+                    debug!(
+                        "failed to generate generic call method for {}: {}",
+                        group_path.full_name(None),
+                        err
+                    );
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Returns true if all `functions` are wrappers of C++ `operator()`.
+    fn is_call_operator_group(
+        &self,
+        functions: &[ItemWithSource<FunctionWithDesiredPath>],
+    ) -> Result<bool> {
+        for function in functions {
+            let cpp_item = self
+                .data
+                .db
+                .source_cpp_item(&function.source_id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?;
+            let is_call_operator = cpp_item.item.as_function_ref().map_or(false, |function| {
+                function.operator == Some(CppOperator::FunctionCall)
+            });
+            if !is_call_operator {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn generate_call_operator(
+        &mut self,
+        functions: &[ItemWithSource<FunctionWithDesiredPath>],
+    ) -> Result<CallOperatorOverloads> {
+        let mut overloads = Vec::new();
+        let mut first_function = None;
+        for function in functions {
+            let cpp_function = self
+                .data
+                .db
+                .source_cpp_item(&function.source_id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            if cpp_function.operator != Some(CppOperator::FunctionCall)
+                || cpp_function.is_static_member()
+                || cpp_function.is_protected()
+            {
+                bail!("unexpected function: {}", cpp_function.short_text());
+            }
+
+            let rust_function = &function.item.function;
+            if rust_function.return_type.api_type().lifetime().is_some()
+                || rust_function.arguments.iter().any(|arg| {
+                    arg.argument_type.api_type().lifetime().is_some()
+                        || arg.argument_type.conversion().as_callback_ref().is_some()
+                })
+            {
+                continue;
+            }
+            let mut argument_types =
+                rust_function
+                    .arguments
+                    .iter()
+                    .skip(1)
+                    .map_if_ok(|arg| -> Result<_> {
+                        let t = &arg.argument_type;
+                        if let RustToFfiTypeConversion::ImplCastInto(conversion) = t.conversion() {
+                            let t =
+                                RustFinalType::new(t.ffi_type().clone(), (**conversion).clone())?;
+                            Ok(t.api_type().clone())
+                        } else {
+                            Ok(t.api_type().clone())
+                        }
+                    })?;
+            let args_type = if argument_types.len() == 1 {
+                argument_types.remove(0)
+            } else {
+                RustType::Tuple(argument_types)
+            };
+            // const and non-const overloads may have the same argument types
+            if overloads.iter().any(|(_, t)| t == &args_type) {
+                continue;
+            }
+            first_function.get_or_insert(&function.item);
+            overloads.push((function.source_id.clone(), args_type));
+        }
+        if overloads.len() < 2 {
+            bail!("less than 2 overloads are supported");
+        }
+        let first_function = first_function.expect("overloads are not empty");
+        let self_arg = first_function
+            .function
+            .arguments
+            .get(0)
+            .filter(|arg| arg.name == "self")
+            .ok_or_else(|| err_msg("self argument expected"))?;
+        let mut object_arg = self_arg.clone();
+        object_arg.name = "object".into();
+
+        let struct_path = first_function.desired_path.parent()?;
+        let trait_path = self.data.db.make_unique_rust_path(
+            &struct_path
+                .parent()?
+                .join(format!("{}CallArgs", struct_path.last())),
+        );
+        let trait_function = RustFunction {
+            path: trait_path.join("call"),
+            is_public: true,
+            is_unsafe: true,
+            kind: first_function.function.kind.clone(),
+            arguments: vec![
+                object_arg,
+                RustFunctionArgument {
+                    name: "args".into(),
+                    argument_type: RustFinalType::new(
+                        RustType::GenericParameter("Self".into()),
+                        RustToFfiTypeConversion::None,
+                    )?,
+                    ffi_index: 0,
+                },
+            ],
+            return_type: RustFinalType::new(
+                RustType::GenericParameter("Self::Output".into()),
+                RustToFfiTypeConversion::None,
+            )?,
+        };
+        let rust_trait = RustTrait {
+            path: trait_path.clone(),
+            is_public: true,
+            associated_types: vec!["Output".into()],
+            functions: vec![trait_function],
+            implemented_for: None,
+        };
+        let source_id = &overloads[0].0;
+        self.add_rust_item(Some(source_id.clone()), RustItem::Trait(rust_trait))?;
+
+        let wrapper = RustFunction {
+            path: self
+                .data
+                .db
+                .make_unique_rust_path(&struct_path.join("call")),
+            is_public: true,
+            is_unsafe: true,
+            kind: RustFunctionKind::TemplateWrapper(RustTemplateWrapperData {
+                trait_path: trait_path.clone(),
+                call_arguments: vec!["self".into(), "args".into()],
+            }),
+            arguments: vec![
+                self_arg.clone(),
+                RustFunctionArgument {
+                    name: "args".into(),
+                    argument_type: RustFinalType::new(
+                        RustType::GenericParameter("T".into()),
+                        RustToFfiTypeConversion::None,
+                    )?,
+                    ffi_index: 0,
+                },
+            ],
+            return_type: RustFinalType::new(
+                RustType::GenericParameter("T::Output".into()),
+                RustToFfiTypeConversion::None,
+            )?,
+        };
+        self.add_rust_item(Some(source_id.clone()), RustItem::Function(wrapper))?;
+
+        Ok(CallOperatorOverloads {
+            trait_path,
+            overloads,
+        })
+    }
+
+    /// Implements the argument traits of the generic `call` methods
+    /// by calling the corresponding overloads of `operator()`.
+    fn generate_call_operator_impls(
+        &mut self,
+        call_operators: Vec<CallOperatorOverloads>,
+    ) -> Result<()> {
+        for call_operator in call_operators {
+            let trait_path = call_operator.trait_path;
+            for (source_id, args_type) in call_operator.overloads {
+                let function = self
+                    .data
+                    .db
+                    .rust_items()
+                    .filter(|item| item.source_id.as_ref() == Some(&source_id))
+                    .filter_map(|item| item.item.as_function_ref())
+                    .find(|function| function.kind.is_ffi_wrapper())
+                    .ok_or_else(|| err_msg("rust function for operator() not found"))?
+                    .clone();
+
+                let mut object_arg = function.arguments[0].clone();
+                object_arg.name = "object".into();
+                let arguments_count = function.arguments.len() - 1;
+                let mut call_arguments = vec!["object".to_string()];
+                if arguments_count == 1 {
+                    call_arguments.push("args".into());
+                } else {
+                    call_arguments.extend((0..arguments_count).map(|i| format!("args.{}", i)));
+                }
+
+                let impl_function = RustFunction {
+                    path: trait_path.join("call"),
+                    is_public: true,
+                    is_unsafe: true,
+                    kind: RustFunctionKind::OverloadWrapper(RustOverloadWrapperData {
+                        function_path: function.path.clone(),
+                        call_arguments,
+                    }),
+                    arguments: vec![
+                        object_arg,
+                        RustFunctionArgument {
+                            name: "args".into(),
+                            argument_type: RustFinalType::new(
+                                args_type.clone(),
+                                RustToFfiTypeConversion::None,
+                            )?,
+                            ffi_index: 0,
+                        },
+                    ],
+                    return_type: function.return_type.clone(),
+                };
+                let trait_impl = RustTraitImpl {
+                    parent_path: trait_path.parent()?,
+                    target_type: args_type,
+                    trait_type: RustCommonType {
+                        path: trait_path.clone(),
+                        generic_arguments: None,
+                    },
+                    associated_types: vec![RustTraitAssociatedType {
+                        name: "Output".into(),
+                        value: function.return_type.api_type().clone(),
+                    }],
+                    functions: vec![impl_function],
+                    extra_kind: RustTraitImplExtraKind::Normal,
+                };
+                self.add_rust_item(Some(source_id), RustItem::TraitImpl(trait_impl))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Implements `PartialEq<CppBox<T>>` for heap allocated class types
+    /// that can be compared with themselves.
+    ///
+    /// The C++ operators are only trusted to define an equivalence relation
+    /// or a total order for types registered with `Config::add_total_order_type`.
+    /// Such types also get `cpp_core::cmp::TotalEq` if they have `operator<`
+    /// or a hash function, and `cpp_core::cmp::TotalOrd` if they have `operator<`,
+    /// so that `CppBox<T>` implements `Eq` and `Ord`.
+    fn generate_total_order_impls(&mut self) -> Result<()> {
+        let trait_impls = self
+            .data
+            .db
+            .rust_items()
+            .filter_map(|item| {
+                item.item
+                    .as_trait_impl_ref()
+                    .map(|trait_impl| (item.source_id.clone(), trait_impl.clone()))
+            })
+            .collect_vec();
+
+        let find_impl = |target_type: &RustType, trait_path: &str, is_comparison: bool| {
+            let generic_arguments = if is_comparison {
+                Some(vec![RustType::Common(RustCommonType {
+                    path: RustPath::from_good_str("cpp_core::Ref"),
+                    generic_arguments: Some(vec![target_type.clone()]),
+                })])
+            } else {
+                None
+            };
+            trait_impls.iter().find(|(_, trait_impl)| {
+                &trait_impl.target_type == target_type
+                    && trait_impl.trait_type.path == RustPath::from_good_str(trait_path)
+                    && trait_impl.trait_type.generic_arguments == generic_arguments
+            })
+        };
+
+        let mut new_impls = Vec::new();
+        for (deletable_source_id, deletable_impl) in &trait_impls {
+            if deletable_impl.trait_type.path != RustPath::from_good_str("cpp_core::CppDeletable") {
+                continue;
+            }
+            let target_type = &deletable_impl.target_type;
+            let (eq_source_id, eq_impl) =
+                if let Some(item) = find_impl(target_type, "std::cmp::PartialEq", true) {
+                    item
+                } else {
+                    continue;
+                };
+
+            let mut box_eq_impl = eq_impl.clone();
+            box_eq_impl.trait_type.generic_arguments =
+                Some(vec![RustType::Common(RustCommonType {
+                    path: RustPath::from_good_str("cpp_core::CppBox"),
+                    generic_arguments: Some(vec![target_type.clone()]),
+                })]);
+            let other_arg = &mut box_eq_impl.functions[0].arguments[1].argument_type;
+            *other_arg = RustFinalType::new(
+                other_arg.ffi_type().clone(),
+                RustToFfiTypeConversion::RefTo(Box::new(RustToFfiTypeConversion::CppBoxToPtr)),
+            )?;
+            new_impls.push((eq_source_id.clone(), box_eq_impl));
+
+            // the C++ operators are only known to define a total order
+            // if the config says so
+            let destructor = if let Some(source_id) = deletable_source_id {
+                self.data.db.source_cpp_item(source_id)?
+            } else {
+                None
+            };
+            let class_path = match destructor
+                .and_then(|destructor| destructor.item.path())
+                .map(CppPath::parent)
+            {
+                Some(Ok(path)) => path,
+                _ => {
+                    trace!(
+                        "skipping total order impls for {:?}: destructor not found",
+                        target_type
+                    );
+                    continue;
+                }
+            };
+            if !self.data.config.total_order_types().contains(&class_path) {
+                continue;
+            }
+
+            let lt_impl = find_impl(target_type, "cpp_core::cmp::Lt", true);
+            let hash_impl = find_impl(target_type, "cpp_core::CppHash", false);
+            if lt_impl.is_none() && hash_impl.is_none() {
+                continue;
+            }
+            let marker_impl = |trait_path: &str| RustTraitImpl {
+                target_type: target_type.clone(),
+                parent_path: eq_impl.parent_path.clone(),
+                trait_type: RustCommonType {
+                    path: RustPath::from_good_str(trait_path),
+                    generic_arguments: None,
+                },
+                associated_types: Vec::new(),
+                functions: Vec::new(),
+                extra_kind: RustTraitImplExtraKind::Normal,
+            };
+            new_impls.push((eq_source_id.clone(), marker_impl("cpp_core::cmp::TotalEq")));
+            if let Some((lt_source_id, _)) = lt_impl {
+                new_impls.push((lt_source_id.clone(), marker_impl("cpp_core::cmp::TotalOrd")));
+            }
+        }
+
+        for (source_id, trait_impl) in new_impls {
+            self.add_rust_item(source_id, RustItem::TraitImpl(trait_impl))?;
+        }
+        Ok(())
+    }
+
+    /// Generates options structs and `_with_options` functions for C++ functions
+    /// that have FFI getters of their default argument values.
+    fn generate_options_structs(&mut self) -> Result<()> {
+        if self
+            .data
+            .config
+            .options_struct_min_default_arguments()
+            .is_none()
+        {
+            return Ok(());
+        }
+
+        let mut getters = BTreeMap::<ItemId, Vec<ItemId>>::new();
+        let mut ffi_functions = HashMap::new();
+        for ffi_item in self.data.db.ffi_items() {
+            if let (Some(function), Some(source_id)) =
+                (ffi_item.item.as_function_ref(), &ffi_item.source_id)
+            {
+                match &function.kind {
+                    CppFfiFunctionKind::DefaultArgument { .. } => {
+                        getters
+                            .entry(source_id.clone())
+                            .or_default()
+                            .push(ffi_item.id.clone());
+                    }
+                    CppFfiFunctionKind::Function => {
+                        ffi_functions.insert(source_id.clone(), ffi_item.id.clone());
+                    }
+                    CppFfiFunctionKind::FieldAccessor { .. } => {}
+                }
+            }
+        }
+
+        let mut rust_functions = HashMap::<ItemId, Vec<RustFunction>>::new();
+        for rust_item in self.data.db.rust_items() {
+            if let (Some(function), Some(source_id)) =
+                (rust_item.item.as_function_ref(), &rust_item.source_id)
+            {
+                rust_functions
+                    .entry(source_id.clone())
+                    .or_default()
+                    .push(function.clone());
+            }
+        }
+
+        for (cpp_item_id, getter_ids) in getters {
+            let ffi_function_id = if let Some(id) = ffi_functions.get(&cpp_item_id) {
+                id
+            } else {
+                continue;
+            };
+            if let Err(err) = self.generate_options_struct(
+                &cpp_item_id,
+                ffi_function_id,
+                &getter_ids,
+                &rust_functions,
+            ) {
+                debug!(
+                    "failed to generate options struct for {}: {}",
+                    cpp_item_id, err
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_options_struct(
+        &mut self,
+        cpp_item_id: &ItemId,
+        ffi_function_id: &ItemId,
+        getter_ids: &[ItemId],
+        rust_functions: &HashMap<ItemId, Vec<RustFunction>>,
+    ) -> Result<()> {
+        let cpp_function = self
+            .data
+            .db
+            .cpp_item(cpp_item_id)?
+            .item
+            .as_function_ref()
+            .ok_or_else(|| err_msg("invalid source cpp item type"))?
+            .clone();
+        let ffi_function = self
+            .data
+            .db
+            .ffi_item(ffi_function_id)?
+            .item
+            .as_function_ref()
+            .ok_or_else(|| err_msg("invalid ffi item type"))?
+            .clone();
+        let api_function = rust_functions
+            .get(ffi_function_id)
+            .and_then(|functions| {
+                functions.iter().find(|f| {
+                    if let RustFunctionKind::FfiWrapper(_) = f.kind {
+                        true
+                    } else {
+                        false
+                    }
+                })
+            })
+            .ok_or_else(|| err_msg("API function not found"))?;
+
+        let mut fields = BTreeMap::new();
+        for getter_id in getter_ids {
+            let getter = self
+                .data
+                .db
+                .ffi_item(getter_id)?
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid ffi item type"))?
+                .clone();
+            let index = if let CppFfiFunctionKind::DefaultArgument { index } = getter.kind {
+                index
+            } else {
+                bail!("invalid ffi function kind");
+            };
+            let getter_path = rust_functions
+                .get(getter_id)
+                .and_then(|functions| functions.iter().find(|f| f.kind.is_ffi_function()))
+                .ok_or_else(|| {
+                    format_err!(
+                        "default value of argument {} is not available",
+                        cpp_function.arguments[index].name
+                    )
+                })?
+                .path
+                .clone();
+            if getter
+                .arguments
+                .iter()
+                .any(|arg| arg.meaning == CppFfiArgumentMeaning::ReturnValue)
+            {
+                bail!("default values returned through an argument are not supported");
+            }
+            let checks = self.data.db.cpp_checks(getter_id)?;
+            let field_type = self.rust_final_type(
+                &getter.return_type,
+                &CppFfiArgumentMeaning::ReturnValue,
+                getter.allocation_place,
+                Some(&checks),
+            )?;
+            if field_type.api_type().lifetime().is_some() {
+                bail!("default values with lifetimes are not supported");
+            }
+            let argument = &cpp_function.arguments[index];
+            let field = RustOptionsStructField {
+                name: sanitize_rust_identifier(&argument.name.to_snake_case(), false),
+                field_type,
+                default_function_path: getter_path,
+                default_value: argument
+                    .default_value
+                    .clone()
+                    .ok_or_else(|| err_msg("default value is unknown"))?,
+            };
+            fields.insert(index, field);
+        }
+        let defaulted_count = cpp_function
+            .arguments
+            .iter()
+            .filter(|arg| arg.has_default_value)
+            .count();
+        if fields.len() != defaulted_count {
+            bail!("some of default argument getters are missing");
+        }
+
+        let api_path = &api_function.path;
+        let parent_path = api_path.parent()?;
+        let parent_is_struct = self
+            .data
+            .db
+            .find_rust_item(&parent_path)
+            .map_or(false, |item| item.item.as_struct_ref().is_some());
+        let (module_path, struct_name) = if parent_is_struct {
+            (
+                parent_path.parent()?,
+                format!(
+                    "{}{}Options",
+                    parent_path.last(),
+                    api_path.last().to_class_case()
+                ),
+            )
+        } else {
+            (
+                parent_path.clone(),
+                format!("{}Options", api_path.last().to_class_case()),
+            )
+        };
+        let struct_path = self
+            .data
+            .db
+            .make_unique_rust_path(&module_path.join(struct_name));
+
+        let options_type = RustType::Common(RustCommonType {
+            path: struct_path.clone(),
+            generic_arguments: None,
+        });
+        let mut arguments = Vec::new();
+        let mut call_arguments = Vec::new();
+        let mut options_ffi_index = None;
+        for arg in &api_function.arguments {
+            let field = match &ffi_function.arguments[arg.ffi_index].meaning {
+                CppFfiArgumentMeaning::Argument(index) => fields.get(index),
+                _ => None,
+            };
+            if let Some(field) = field {
+                if let RustToFfiTypeConversion::ClosureToCallback(_) =
+                    arg.argument_type.conversion()
+                {
+                    bail!("callbacks are not supported in options structs");
+                }
+                options_ffi_index.get_or_insert(arg.ffi_index);
+                let is_boxed = match field.field_type.conversion() {
+                    RustToFfiTypeConversion::CppBoxToPtr | RustToFfiTypeConversion::QBoxToPtr => {
+                        true
+                    }
+                    _ => false,
+                };
+                call_arguments.push(if is_boxed {
+                    format!("&options.{}", field.name)
+                } else {
+                    format!("options.{}", field.name)
+                });
+            } else {
+                call_arguments.push(arg.name.clone());
+                arguments.push(arg.clone());
+            }
+        }
+        arguments.push(RustFunctionArgument {
+            name: "options".to_string(),
+            argument_type: RustFinalType::new(options_type, RustToFfiTypeConversion::None)?,
+            ffi_index: options_ffi_index
+                .ok_or_else(|| err_msg("defaulted arguments are not used in API function"))?,
+        });
+
+        let options_struct = RustStruct {
+            path: struct_path,
+            kind: RustStructKind::OptionsStruct(RustOptionsStruct {
+                fields: fields.into_iter().map(|(_, field)| field).collect(),
+            }),
+            is_public: true,
+            qt_receiver_data: None,
+        };
+        if self
+            .add_rust_item(Some(cpp_item_id.clone()), RustItem::Struct(options_struct))?
+            .is_none()
+        {
+            // already generated
+            return Ok(());
+        }
+
+        let function_path = self
+            .data
+            .db
+            .make_unique_rust_path(&parent_path.join(format!("{}_with_options", api_path.last())));
+        let function = RustFunction {
+            path: function_path,
+            is_public: true,
+            is_unsafe: api_function.is_unsafe,
+            kind: RustFunctionKind::OptionsWrapper(RustOptionsWrapperData {
+                function_path: api_path.clone(),
+                call_arguments,
+            }),
+            arguments,
+            return_type: api_function.return_type.clone(),
+        };
+        self.add_rust_item(Some(ffi_function_id.clone()), RustItem::Function(function))?;
+        Ok(())
+    }
+}
+
+/// Returns paths of classes that have protected members with working FFI wrappers.
+/// Protected constructors and destructors don't have FFI wrappers, so they
+/// don't require the protected members trait.
+fn classes_with_protected_members(data: &ProcessorData<'_>) -> Result<HashSet<CppPath>> {
+    let mut paths = HashSet::new();
+    for ffi_item in data.db.ffi_items() {
+        let cpp_item = match data.db.source_cpp_item(&ffi_item.id)? {
+            Some(item) => item,
+            None => continue,
+        };
+        if !cpp_item.item.is_protected_member() || !data.db.cpp_checks(&ffi_item.id)?.any_success()
+        {
+            continue;
+        }
+        if let Some(path) = cpp_item.item.path() {
+            paths.insert(path.parent()?);
+        }
+    }
+    Ok(paths)
+}
+
+pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
+    let classes_with_protected_members = if data.config.expose_protected_members() {
+        classes_with_protected_members(data)?
+    } else {
+        HashSet::new()
+    };
+    let mut state = State {
+        data,
+        special_module_paths: HashMap::new(),
+        classes_with_protected_members,
+    };
+    for &module in &[
+        RustSpecialModuleKind::CrateRoot,
+        RustSpecialModuleKind::Ffi,
+        RustSpecialModuleKind::Ops,
+        RustSpecialModuleKind::SizedTypes,
+        RustSpecialModuleKind::Macros,
+    ] {
+        state.generate_special_module(module)?;
+    }
+
+    state.generate_crate_reexport("cpp_core")?;
+    let dependencies = state
+        .data
+        .config
+        .crate_properties()
+        .dependencies()
+        .iter()
+        .filter(|dep| dep.kind() == CrateDependencyKind::Ritual);
+    for dependency in dependencies {
+        state.generate_crate_reexport(dependency.name())?;
+    }
+
+    state.process_cpp_items()?;
+    let (mut grouped_functions, template_instantiations) = state.process_ffi_items()?;
+    state.generate_template_functions(template_instantiations, &mut grouped_functions)?;
+    let call_operators = state.generate_call_operators(&grouped_functions)?;
+    state.finalize_functions(grouped_functions)?;
+    state.generate_call_operator_impls(call_operators)?;
+    state.generate_virtual_subclass_traits()?;
+    state.generate_total_order_impls()?;
+    state.generate_options_structs()?;
+
+    Ok(())
+}
+
+/// Names of the callback, deleter and data arguments of FFI functions that
+/// accept a Rust callback. Slot wrappers and virtual subclasses use the short names,
+/// and `std::function` arguments use the prefixed names.
+const CALLBACK_ARGUMENT_NAMES: &[[&str; 3]] = &[
+    ["callback", "deleter", "data"],
+    [
+        "ritual_callback",
+        "ritual_callback_deleter",
+        "ritual_callback_data",
+    ],
+];
+
+fn detect_callback_function(function: &UnnamedRustFunction) -> Option<&RustFunctionPointerType> {
+    if function.arguments.len() < 3 {
+        return None;
+    }
+    let args = &function.arguments[function.arguments.len() - 3..];
+    let void_ptr = RustType::new_pointer(
+        false,
+        RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("std::ffi::c_void"),
+            generic_arguments: None,
+        }),
+    );
+    let names = [
+        args[0].name.as_str(),
+        args[1].name.as_str(),
+        args[2].name.as_str(),
+    ];
+    if !CALLBACK_ARGUMENT_NAMES.contains(&names) {
+        return None;
+    }
+    let callback_type;
+    if let RustType::Common(common) = &args[0].argument_type.ffi_type() {
+        if common.path != "std::option::Option" {
+            return None;
+        }
+        if let Some(args) = &common.generic_arguments {
+            if args.len() == 1 {
+                if let RustType::FunctionPointer(t) = &args[0] {
+                    callback_type = t;
+                } else {
+                    return None;
+                }
+            } else {
+                return None;
+            }
+        } else {
+            return None;
+        }
+    } else {
+        return None;
     }
     let deleter_type = RustType::new_option(RustType::FunctionPointer(RustFunctionPointerType {
         arguments: vec![void_ptr.clone()],
         return_type: Box::new(RustType::unit()),
+        allows_variadic_arguments: false,
     }));
     if args[1].argument_type.ffi_type() != &deleter_type {
         return None;
     }
-    if args[2].name != "data" {
-        return None;
-    }
     if args[2].argument_type.ffi_type() != &void_ptr {
         return None;
     }
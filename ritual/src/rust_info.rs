@@ -9,14 +9,44 @@ use crate::rust_type::{
     RustCommonType, RustFinalType, RustPath, RustPointerLikeTypeKind, RustType,
 };
 use ritual_common::errors::{bail, Result};
+use serde::{Deserialize, Deserializer};
 use serde_derive::{Deserialize, Serialize};
 
 /// One variant of a Rust enum
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct RustEnumValue {
     pub path: RustPath,
-    /// Corresponding value
-    pub value: i64,
+    /// Rust literal of the value
+    #[serde(deserialize_with = "deserialize_enum_value_literal")]
+    pub value: String,
+}
+
+/// Deserializes the literal of an enum value, also accepting the integer value
+/// stored in databases created before underlying types of enums were recorded.
+fn deserialize_enum_value_literal<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyValue {
+        Literal(String),
+        Old(i64),
+    }
+
+    Ok(match AnyValue::deserialize(deserializer)? {
+        AnyValue::Literal(literal) => literal,
+        AnyValue::Old(value) => value.to_string(),
+    })
+}
+
+/// Rust constant corresponding to a C++ constant variable or static field
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustConstant {
+    pub path: RustPath,
+    pub value_type: RustType,
+    /// Rust literal of the value
+    pub value: String,
 }
 
 /// Information about a Qt slot wrapper on Rust side
@@ -29,9 +59,50 @@ pub struct RustQtSlotWrapper {
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum RustWrapperTypeKind {
-    EnumWrapper,
+    EnumWrapper {
+        /// Integer type used to represent values of the enum
+        underlying_type: RustType,
+        /// Primitive type used in `#[repr]` of the generated Rust `enum`
+        /// if the enum is exhaustive, or `None` if a wrapper struct is generated.
+        exhaustive_repr: Option<String>,
+    },
     ImmovableClassWrapper,
-    MovableClassWrapper { sized_type_path: RustPath },
+    MovableClassWrapper {
+        sized_type_path: RustPath,
+    },
+}
+
+/// Deserializes `RustWrapperTypeKind`, also accepting the unit `EnumWrapper` variant
+/// stored in databases created before underlying types of enums were recorded.
+/// Such enums are assumed to be represented by `c_int`.
+fn deserialize_wrapper_type_kind<'de, D>(
+    deserializer: D,
+) -> std::result::Result<RustWrapperTypeKind, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    enum OldKind {
+        EnumWrapper,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyKind {
+        Current(RustWrapperTypeKind),
+        Old(OldKind),
+    }
+
+    Ok(match AnyKind::deserialize(deserializer)? {
+        AnyKind::Current(kind) => kind,
+        AnyKind::Old(OldKind::EnumWrapper) => RustWrapperTypeKind::EnumWrapper {
+            underlying_type: RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("std::os::raw::c_int"),
+                generic_arguments: None,
+            }),
+            exhaustive_repr: None,
+        },
+    })
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -39,12 +110,31 @@ pub struct RustSizedType {
     pub cpp_path: CppPath,
 }
 
+/// A field of an options struct corresponding to an argument with a default value
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustOptionsStructField {
+    pub name: String,
+    /// Type of the field. Its FFI type is the return type of `default_function_path`.
+    pub field_type: RustFinalType,
+    /// FFI function returning the default value of the argument
+    pub default_function_path: RustPath,
+    /// C++ expression of the default value
+    pub default_value: String,
+}
+
+/// Struct holding values of the defaulted arguments of a function
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustOptionsStruct {
+    pub fields: Vec<RustOptionsStructField>,
+}
+
 /// Information about a Rust type wrapper
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum RustStructKind {
-    WrapperType(RustWrapperTypeKind),
+    WrapperType(#[serde(deserialize_with = "deserialize_wrapper_type_kind")] RustWrapperTypeKind),
     QtSlotWrapper(RustQtSlotWrapper), // TODO: remove
     SizedType(RustSizedType),
+    OptionsStruct(RustOptionsStruct),
 }
 
 impl RustStructKind {
@@ -86,6 +176,13 @@ impl RustStructKind {
                     false
                 }
             }
+            RustStructKind::OptionsStruct(_) => {
+                if let RustStructKind::OptionsStruct(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
         }
     }
 }
@@ -145,12 +242,80 @@ pub struct RustFfiWrapperData {
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct RustSignalOrSlotGetter {}
 
+/// Information about a function that calls another function,
+/// taking values of the defaulted arguments from an options struct.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustOptionsWrapperData {
+    /// Path of the called function
+    pub function_path: RustPath,
+    /// Rust expressions passed as the arguments of the called function
+    pub call_arguments: Vec<String>,
+}
+
+/// Information about a generic function that calls the trait function
+/// implemented for each instantiation of a C++ template function.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustTemplateWrapperData {
+    /// Path of the trait the generic parameter is bounded by
+    pub trait_path: RustPath,
+    /// Rust expressions passed as the arguments of the trait function
+    pub call_arguments: Vec<String>,
+}
+
+/// Information about a trait function that calls one of the overloads of a function,
+/// taking the values of its arguments from a tuple.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustOverloadWrapperData {
+    /// Path of the called function
+    pub function_path: RustPath,
+    /// Rust expressions passed as the arguments of the called function
+    pub call_arguments: Vec<String>,
+}
+
+/// Information about a function of the trait that allows to override virtual functions
+/// of a C++ class. The function is called when the virtual function is called.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustVirtualFunctionData {
+    /// Path of the method of the subclass that calls the base class implementation,
+    /// or `None` if the virtual function is pure virtual. The trait function
+    /// has a default implementation only if the path is set.
+    pub base_function_path: Option<RustPath>,
+    /// Rust expressions passed as the arguments of the base class implementation
+    pub call_arguments: Vec<String>,
+}
+
+/// Information about a virtual function overridden by `RustOverridesSetterData`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustOverrideData {
+    /// Path of the method of the subclass that sets the callback
+    pub setter_path: RustPath,
+    /// Name of the trait function called by the callback
+    pub function_name: String,
+    /// Names of the arguments of the callback
+    pub argument_names: Vec<String>,
+}
+
+/// Information about a generic method of a subclass that overrides
+/// its virtual functions with the functions of a trait implementation.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustOverridesSetterData {
+    /// Path of the trait the generic parameter is bounded by
+    pub trait_path: RustPath,
+    /// Virtual functions overridden by the method
+    pub overrides: Vec<RustOverrideData>,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum RustFunctionKind {
     FfiWrapper(RustFfiWrapperData),
     SignalOrSlotGetter(RustSignalOrSlotGetter),
     FfiFunction,
+    OptionsWrapper(RustOptionsWrapperData),
+    TemplateWrapper(RustTemplateWrapperData),
+    OverloadWrapper(RustOverloadWrapperData),
+    VirtualFunction(RustVirtualFunctionData),
+    OverridesSetter(RustOverridesSetterData),
 }
 
 impl RustFunctionKind {
@@ -161,6 +326,27 @@ impl RustFunctionKind {
             }
             RustFunctionKind::SignalOrSlotGetter(_) => "SignalOrSlotGetter".to_string(),
             RustFunctionKind::FfiFunction => "FfiFunction".to_string(),
+            RustFunctionKind::OptionsWrapper(data) => {
+                format!("OptionsWrapper({})", data.function_path.last())
+            }
+            RustFunctionKind::TemplateWrapper(data) => {
+                format!("TemplateWrapper({})", data.trait_path.last())
+            }
+            RustFunctionKind::OverloadWrapper(data) => {
+                format!("OverloadWrapper({})", data.function_path.last())
+            }
+            RustFunctionKind::VirtualFunction(_) => "VirtualFunction".to_string(),
+            RustFunctionKind::OverridesSetter(data) => {
+                format!("OverridesSetter({})", data.trait_path.last())
+            }
+        }
+    }
+
+    pub fn is_ffi_wrapper(&self) -> bool {
+        if let RustFunctionKind::FfiWrapper(_) = self {
+            true
+        } else {
+            false
         }
     }
 
@@ -374,12 +560,30 @@ pub struct RustTraitImpl {
     pub extra_kind: RustTraitImplExtraKind,
 }
 
+/// Information about a trait declared by the crate.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustTrait {
+    /// Full name of the trait.
+    pub path: RustPath,
+    pub is_public: bool,
+    /// Names of associated types of the trait.
+    pub associated_types: Vec<String>,
+    /// Prototypes of functions of the trait.
+    pub functions: Vec<RustFunction>,
+    /// If set, the trait is implemented for this type by the functions
+    /// nested in the trait's path. Their prototypes are added to the trait.
+    /// Otherwise, the nested functions are added to the trait as
+    /// default implementations.
+    pub implemented_for: Option<RustType>,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum RustSpecialModuleKind {
     CrateRoot,
     Ffi,
     Ops,
     SizedTypes,
+    Macros,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
@@ -397,6 +601,7 @@ impl RustModuleKind {
                 RustSpecialModuleKind::Ffi => false,
                 RustSpecialModuleKind::Ops => true,
                 RustSpecialModuleKind::SizedTypes => false,
+                RustSpecialModuleKind::Macros => true,
             },
             RustModuleKind::CppNamespace { .. } => true,
             RustModuleKind::CppNestedTypes { .. } => false,
@@ -546,7 +751,12 @@ pub struct RustExtraImpl {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum RustReexportSource {
-    DependencyCrate { crate_name: String },
+    DependencyCrate {
+        crate_name: String,
+    },
+    /// A function of the FFI module that can't be wrapped
+    /// (e.g. a variadic function)
+    FfiFunction,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -556,12 +766,22 @@ pub struct RustReexport {
     pub source: RustReexportSource,
 }
 
+/// Information about a Rust type alias corresponding to a C++ typedef.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RustTypeAlias {
+    pub path: RustPath,
+    pub target_type: RustType,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RustItem {
     Module(RustModule),
     Struct(RustStruct),
+    TypeAlias(RustTypeAlias),
     EnumValue(RustEnumValue),
+    Constant(RustConstant),
+    Trait(RustTrait),
     TraitImpl(RustTraitImpl),
     ExtraImpl(RustExtraImpl),
     Function(RustFunction),
@@ -573,7 +793,10 @@ impl RustItem {
         match self {
             RustItem::Module(data) => Some(&data.path),
             RustItem::Struct(data) => Some(&data.path),
+            RustItem::TypeAlias(data) => Some(&data.path),
             RustItem::EnumValue(data) => Some(&data.path),
+            RustItem::Constant(data) => Some(&data.path),
+            RustItem::Trait(data) => Some(&data.path),
             RustItem::Function(data) => Some(&data.path),
             RustItem::Reexport(data) => Some(&data.path),
             RustItem::TraitImpl(_) | RustItem::ExtraImpl(_) => None,
@@ -620,6 +843,13 @@ impl RustItem {
             None
         }
     }
+    pub fn as_type_alias_ref(&self) -> Option<&RustTypeAlias> {
+        if let RustItem::TypeAlias(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
     pub fn as_enum_value_ref(&self) -> Option<&RustEnumValue> {
         if let RustItem::EnumValue(data) = self {
             Some(data)
@@ -627,6 +857,13 @@ impl RustItem {
             None
         }
     }
+    pub fn as_constant_ref(&self) -> Option<&RustConstant> {
+        if let RustItem::Constant(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
     pub fn as_reexport_ref(&self) -> Option<&RustReexport> {
         if let RustItem::Reexport(value) = self {
             Some(value)
@@ -641,6 +878,13 @@ impl RustItem {
             None
         }
     }
+    pub fn as_trait_ref(&self) -> Option<&RustTrait> {
+        if let RustItem::Trait(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
     pub fn as_trait_impl_ref(&self) -> Option<&RustTraitImpl> {
         if let RustItem::TraitImpl(value) = self {
             Some(value)
@@ -672,6 +916,13 @@ impl RustItem {
                     false
                 }
             }
+            RustItem::TypeAlias(_) => {
+                if let RustItem::TypeAlias(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
             RustItem::EnumValue(_) => {
                 if let RustItem::EnumValue(_) = other {
                     true
@@ -679,9 +930,23 @@ impl RustItem {
                     false
                 }
             }
+            RustItem::Constant(_) => {
+                if let RustItem::Constant(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
+            RustItem::Trait(_) => {
+                if let RustItem::Trait(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
             RustItem::TraitImpl(data) => {
                 if let RustItem::TraitImpl(other) = other {
-                    data.extra_kind == other.extra_kind
+                    data.extra_kind == other.extra_kind && data.trait_type == other.trait_type
                 } else {
                     false
                 }
@@ -727,10 +992,65 @@ impl RustItem {
                         false
                     }
                 }
+                RustFunctionKind::OptionsWrapper(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::OptionsWrapper(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
+                RustFunctionKind::TemplateWrapper(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::TemplateWrapper(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
+                RustFunctionKind::OverloadWrapper(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::OverloadWrapper(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
+                RustFunctionKind::VirtualFunction(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::VirtualFunction(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
+                RustFunctionKind::OverridesSetter(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::OverridesSetter(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
             },
             RustItem::Reexport(data) => {
                 if let RustItem::Reexport(other) = other {
-                    data.source == other.source
+                    data.source == other.source && data.target == other.target
                 } else {
                     false
                 }
@@ -786,7 +1106,14 @@ impl RustItem {
         match self {
             RustItem::Module(data) => format!("mod {}", data.path.full_name(None)),
             RustItem::Struct(data) => format!("struct {}", data.path.full_name(None)),
+            RustItem::TypeAlias(data) => format!(
+                "type {} = {}",
+                data.path.full_name(None),
+                rust_type_to_code(&data.target_type, None)
+            ),
             RustItem::EnumValue(data) => format!("enum value {}", data.path.full_name(None)),
+            RustItem::Constant(data) => format!("const {}", data.path.full_name(None)),
+            RustItem::Trait(data) => format!("trait {}", data.path.full_name(None)),
             RustItem::TraitImpl(data) => format!(
                 "impl {} for {}",
                 rust_common_type_to_code(&data.trait_type, None),
@@ -826,6 +1153,8 @@ pub enum NameType<'a> {
         is_from_other_crate: bool,
     },
     EnumValue,
+    Constant,
+    MacroConstant,
     Module {
         is_from_other_crate: bool,
     },
@@ -842,6 +1171,9 @@ pub enum NameType<'a> {
     QtSignalWrapper {
         signal_arguments: &'a [CppType],
     },
+    VirtualSubclass {
+        base_class_path: &'a CppPath,
+    },
 }
 
 impl NameType<'_> {
@@ -167,6 +167,9 @@ pub enum RustToFfiTypeConversion {
     RefTo(Box<RustToFfiTypeConversion>),
     ImplCastInto(Box<RustToFfiTypeConversion>),
     ClosureToCallback(Box<RustClosureToCallbackConversion>),
+    /// Rust public type is `Result<T, cpp_core::CppException>`, where `T` is
+    /// the public type of the inner conversion
+    ExceptionToResult(Box<RustToFfiTypeConversion>),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -305,6 +308,19 @@ impl RustFinalType {
             RustToFfiTypeConversion::ClosureToCallback { .. } => {
                 RustType::GenericParameter("T".into())
             }
+            RustToFfiTypeConversion::ExceptionToResult(conversion) => {
+                let intermediate = RustFinalType::new(ffi_type.clone(), (**conversion).clone())?;
+                RustType::Common(RustCommonType {
+                    path: RustPath::from_good_str("std::result::Result"),
+                    generic_arguments: Some(vec![
+                        intermediate.api_type,
+                        RustType::Common(RustCommonType {
+                            path: RustPath::from_good_str("cpp_core::CppException"),
+                            generic_arguments: None,
+                        }),
+                    ]),
+                })
+            }
         };
         Ok(RustFinalType {
             api_type,
@@ -325,6 +341,17 @@ impl RustFinalType {
         &self.conversion
     }
 
+    /// Returns a copy of this type with `alias` used in place of `target`
+    /// in the API type. `alias` must be a type alias of `target`, so
+    /// the FFI type and the conversion are not affected.
+    pub fn with_api_type_alias(&self, target: &RustType, alias: &RustType) -> Self {
+        RustFinalType {
+            api_type: self.api_type.replace(target, alias),
+            ffi_type: self.ffi_type.clone(),
+            conversion: self.conversion.clone(),
+        }
+    }
+
     pub fn with_lifetime(&self, lifetime: String) -> Result<Self> {
         if let RustToFfiTypeConversion::RefToPtr { .. } = &self.conversion {
             RustFinalType::new(
@@ -453,6 +480,9 @@ pub struct RustFunctionPointerType {
     pub return_type: Box<RustType>,
     /// Argument types of the function.
     pub arguments: Vec<RustType>,
+    /// Whether the argument list is terminated with "..."
+    #[serde(default)]
+    pub allows_variadic_arguments: bool,
 }
 
 /// A Rust type
@@ -472,6 +502,11 @@ pub enum RustType {
     },
     ImplTrait(RustCommonType),
     GenericParameter(String),
+    /// Fixed-size array (`[T; N]`)
+    Array {
+        element: Box<RustType>,
+        len: usize,
+    },
 }
 
 impl RustType {
@@ -515,6 +550,14 @@ impl RustType {
         }
     }
 
+    pub fn is_primitive(&self) -> bool {
+        if let RustType::Primitive(_) = self {
+            true
+        } else {
+            false
+        }
+    }
+
     /// Returns alphanumeric description of this type
     /// for purposes of name disambiguation.
     pub fn caption(&self, context: &RustPath, strategy: RustTypeCaptionStrategy) -> Result<String> {
@@ -595,6 +638,9 @@ impl RustType {
                 name
             }
             RustType::FunctionPointer { .. } => "fn".to_string(),
+            RustType::Array { element, len } => {
+                format!("{}_array_{}", element.caption(context, strategy)?, len)
+            }
             RustType::ImplTrait(trait_type) => {
                 if trait_type.path == RustPath::from_good_str("cpp_core::CastInto") {
                     trait_type
@@ -610,6 +656,52 @@ impl RustType {
         })
     }
 
+    /// Returns a copy of this type with all occurrences of `old` replaced with `new`.
+    pub fn replace(&self, old: &RustType, new: &RustType) -> RustType {
+        if self == old {
+            return new.clone();
+        }
+        let replace_common = |common: &RustCommonType| RustCommonType {
+            path: common.path.clone(),
+            generic_arguments: common
+                .generic_arguments
+                .as_ref()
+                .map(|args| args.iter().map(|arg| arg.replace(old, new)).collect()),
+        };
+        match self {
+            RustType::Tuple(types) => {
+                RustType::Tuple(types.iter().map(|t| t.replace(old, new)).collect())
+            }
+            RustType::Primitive(_) | RustType::GenericParameter(_) => self.clone(),
+            RustType::Common(common) => RustType::Common(replace_common(common)),
+            RustType::ImplTrait(common) => RustType::ImplTrait(replace_common(common)),
+            RustType::FunctionPointer(function) => {
+                RustType::FunctionPointer(RustFunctionPointerType {
+                    return_type: Box::new(function.return_type.replace(old, new)),
+                    arguments: function
+                        .arguments
+                        .iter()
+                        .map(|arg| arg.replace(old, new))
+                        .collect(),
+                    allows_variadic_arguments: function.allows_variadic_arguments,
+                })
+            }
+            RustType::PointerLike {
+                kind,
+                is_const,
+                target,
+            } => RustType::PointerLike {
+                kind: kind.clone(),
+                is_const: *is_const,
+                target: Box::new(target.replace(old, new)),
+            },
+            RustType::Array { element, len } => RustType::Array {
+                element: Box::new(element.replace(old, new)),
+                len: *len,
+            },
+        }
+    }
+
     /// Returns true if this type is a reference.
     pub fn is_ref(&self) -> bool {
         match self {
@@ -687,6 +779,7 @@ impl RustType {
                     || function.arguments.iter().any(RustType::is_unsafe_argument)
             }
             RustType::ImplTrait(_) => true,
+            RustType::Array { element, .. } => element.is_unsafe_argument(),
         }
     }
 
@@ -752,6 +845,7 @@ impl RustType {
             RustType::FunctionPointer(function) => {
                 if let RustType::FunctionPointer(other) = other {
                     function.return_type.can_be_same_as(&other.return_type)
+                        && function.allows_variadic_arguments == other.allows_variadic_arguments
                         && function.arguments.len() == other.arguments.len()
                         && function
                             .arguments
@@ -788,6 +882,17 @@ impl RustType {
                 }
             }
             RustType::GenericParameter(_) => self == other,
+            RustType::Array { element, len } => {
+                if let RustType::Array {
+                    element: other_element,
+                    len: other_len,
+                } = other
+                {
+                    len == other_len && element.can_be_same_as(other_element)
+                } else {
+                    false
+                }
+            }
         }
     }
 }
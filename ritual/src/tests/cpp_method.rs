@@ -3,6 +3,7 @@ use crate::cpp_ffi_data::*;
 use crate::cpp_ffi_generator::NewFfiFunctionKind;
 use crate::cpp_function::*;
 use crate::cpp_type::*;
+use crate::cpp_virtual_subclasses::generate_override;
 use itertools::Itertools;
 
 #[test]
@@ -25,6 +26,7 @@ pub fn empty_membership() -> CppFunctionMemberData {
         kind: CppFunctionKind::Regular,
         is_virtual: false,
         is_pure_virtual: false,
+        is_final: false,
         is_const: false,
         is_static: false,
         visibility: CppVisibility::Public,
@@ -40,8 +42,13 @@ pub fn empty_regular_method() -> CppFunction {
         return_type: CppType::Void,
         arguments: vec![],
         allows_variadic_arguments: false,
+        has_c_linkage: false,
         operator: None,
         declaration_code: None,
+        deprecation: None,
+        is_deleted: false,
+        is_friend: false,
+        return_type_alias: None,
         cast: None,
     }
 }
@@ -62,6 +69,8 @@ fn argument_types_equal2() {
         argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     assert!(!method1.argument_types_equal(&method2));
     assert!(!method2.argument_types_equal(&method1));
@@ -75,11 +84,15 @@ fn argument_types_equal3() {
         argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     method2.arguments.push(CppFunctionArgument {
         argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
         name: "x".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     assert!(method1.argument_types_equal(&method2));
     assert!(method2.argument_types_equal(&method1));
@@ -93,11 +106,15 @@ fn argument_types_equal4() {
         argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     method2.arguments.push(CppFunctionArgument {
         argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
         name: "arg1".to_string(),
         has_default_value: true,
+        default_value: None,
+        type_alias: None,
     });
     assert!(method1.argument_types_equal(&method2));
     assert!(method2.argument_types_equal(&method1));
@@ -111,6 +128,8 @@ fn argument_types_equal5() {
         argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     method2.arguments.push(CppFunctionArgument {
         argument_type: CppType::Enum {
@@ -118,6 +137,8 @@ fn argument_types_equal5() {
         },
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     assert!(!method1.argument_types_equal(&method2));
     assert!(!method2.argument_types_equal(&method1));
@@ -131,6 +152,8 @@ fn argument_types_equal6() {
         argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     method2.arguments.push(CppFunctionArgument {
         argument_type: CppType::new_pointer(
@@ -139,6 +162,8 @@ fn argument_types_equal6() {
         ),
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     assert!(!method1.argument_types_equal(&method2));
     assert!(!method2.argument_types_equal(&method1));
@@ -151,6 +176,8 @@ fn argument_types_equal7() {
         argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     };
     let mut method2 = empty_regular_method();
     method1.arguments.push(int.clone());
@@ -196,6 +223,44 @@ fn c_signature_empty() {
     assert!(r.return_type.ffi_type().is_void());
 }
 
+#[test]
+fn c_signature_catch_exceptions() {
+    let mut method1 = empty_regular_method();
+    method1.return_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    method1.arguments.push(CppFunctionArgument {
+        argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        name: "arg1".to_string(),
+        has_default_value: false,
+        default_value: None,
+        type_alias: None,
+    });
+
+    let items = crate::cpp_ffi_generator::generate_ffi_methods_for_method(
+        &method1,
+        &[],
+        true,
+        &mut crate::cpp_ffi_generator::FfiNameProvider::testing(),
+    )
+    .unwrap();
+    assert_eq!(items.len(), 1);
+    let r = items[0].as_function_ref().unwrap();
+    assert_eq!(r.arguments.len(), 2);
+    assert_eq!(r.arguments[0].meaning, CppFfiArgumentMeaning::Argument(0));
+    assert_eq!(r.arguments[1].name, "exception");
+    assert_eq!(
+        r.arguments[1].meaning,
+        CppFfiArgumentMeaning::ExceptionMessage
+    );
+    assert_eq!(
+        r.arguments[1].to_cpp_code().unwrap(),
+        "char const * * exception"
+    );
+    assert_eq!(
+        r.return_type.ffi_type(),
+        &CppType::BuiltInNumeric(CppBuiltInNumericType::Int)
+    );
+}
+
 #[test]
 fn c_signature_simple_func() {
     let mut method1 = empty_regular_method();
@@ -206,6 +271,8 @@ fn c_signature_simple_func() {
         },
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     let r = to_ffi(&method1, None);
     assert!(r.arguments.len() == 1);
@@ -236,6 +303,8 @@ fn c_signature_method_with_this() {
         argument_type: CppType::Class(CppPath::from_good_str("MyClass2")),
         name: "my_arg".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
 
     assert!(!method1.is_constructor());
@@ -288,6 +357,8 @@ fn c_signature_static_method() {
         },
         name: "arg1".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     let r = to_ffi(&method1, None);
     assert!(r.arguments.len() == 1);
@@ -322,6 +393,8 @@ fn c_signature_constructor() {
         ),
         name: "arg1".to_string(),
         has_default_value: true,
+        default_value: None,
+        type_alias: None,
     });
 
     assert!(method1.is_constructor());
@@ -463,6 +536,8 @@ fn c_signature_method_returning_class() {
         argument_type: CppType::Class(CppPath::from_good_str("MyClass2")),
         name: "my_arg".to_string(),
         has_default_value: false,
+        default_value: None,
+        type_alias: None,
     });
     let r_stack = to_ffi(&method1, Some(CppPath::from_good_str("MyClass3")));
     assert!(r_stack.arguments.len() == 3);
@@ -600,6 +675,7 @@ fn short_text1() {
             kind: CppFunctionKind::Regular,
             is_virtual: false,
             is_pure_virtual: false,
+            is_final: false,
             is_const: true,
             is_static: false,
             visibility: CppVisibility::Protected,
@@ -613,19 +689,88 @@ fn short_text1() {
                 argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
                 name: "arg1".to_string(),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             },
             CppFunctionArgument {
                 argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Double),
                 name: "arg2".to_string(),
                 has_default_value: true,
+                default_value: None,
+                type_alias: None,
             },
         ],
         allows_variadic_arguments: false,
+        has_c_linkage: false,
         cast: None,
         declaration_code: None,
+        deprecation: None,
+        is_deleted: false,
+        is_friend: false,
+        return_type_alias: None,
     };
     assert_eq!(
         method.short_text(),
         "protected int Class1::method1(int arg1, double arg2 = …) const"
     );
 }
+
+#[test]
+fn virtual_function_override() {
+    let string_type = CppType::Class(CppPath::from_good_str("QString"));
+    let mut method = empty_regular_method();
+    method.path = CppPath::from_good_str("Widget::text");
+    method.member = Some(CppFunctionMemberData {
+        is_virtual: true,
+        is_const: true,
+        ..empty_membership()
+    });
+    method.return_type = string_type.clone();
+    method.arguments.push(CppFunctionArgument {
+        argument_type: CppType::new_reference(true, string_type.clone()),
+        name: "prefix".to_string(),
+        has_default_value: false,
+        default_value: None,
+        type_alias: None,
+    });
+    method.arguments.push(CppFunctionArgument {
+        argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Bool),
+        name: "flag".to_string(),
+        has_default_value: false,
+        default_value: None,
+        type_alias: None,
+    });
+
+    let r = generate_override(&method, "text".to_string()).unwrap();
+    assert_eq!(r.setter_name(), "override_text");
+    assert_eq!(r.base_function_name(), "base_text");
+    assert_eq!(r.arguments.len(), 2);
+    assert_eq!(
+        r.arguments[0].conversion(),
+        &CppToFfiTypeConversion::ReferenceToPointer
+    );
+    assert_eq!(
+        r.arguments[1].conversion(),
+        &CppToFfiTypeConversion::NoChange
+    );
+    assert_eq!(
+        r.return_type.ffi_type(),
+        &CppType::new_pointer(false, string_type.clone())
+    );
+    assert_eq!(
+        r.function_type.arguments,
+        vec![
+            CppType::new_pointer(false, CppType::Void),
+            CppType::new_pointer(true, string_type.clone()),
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Bool),
+        ]
+    );
+    assert_eq!(
+        *r.function_type.return_type,
+        CppType::new_pointer(false, string_type.clone())
+    );
+
+    // the callback can't take ownership of a temporary object
+    method.arguments[1].argument_type = string_type;
+    assert!(generate_override(&method, "override_text".to_string()).is_err());
+}
@@ -12,14 +12,27 @@ use std::io::Write;
 
 struct ParserCppData {
     types: Vec<CppTypeDeclaration>,
+    type_aliases: Vec<CppTypeAlias>,
     bases: Vec<CppBaseSpecifier>,
     fields: Vec<CppClassField>,
+    variables: Vec<CppVariable>,
     methods: Vec<CppFunction>,
     enum_values: Vec<CppEnumValue>,
     namespaces: Vec<CppPath>,
+    /// Parsed items with HTML of their documentation
+    docs: Vec<(CppItem, String)>,
 }
 
 fn run_parser(code: &'static str) -> ParserCppData {
+    run_parser_with_config(code, &[], |_| {})
+}
+
+/// Runs the parser and additional `steps` with the config modified by `f`.
+fn run_parser_with_config(
+    code: &'static str,
+    steps: &[&str],
+    f: impl FnOnce(&mut Config),
+) -> ParserCppData {
     let dir = tempdir::TempDir::new("test_cpp_parser_run").unwrap();
 
     let mut workspace = Workspace::new(dir.path().into()).unwrap();
@@ -40,8 +53,14 @@ fn run_parser(code: &'static str) -> ParserCppData {
     config.add_include_directive(include_name);
     config.set_cpp_build_paths(paths);
     config.add_target_include_path(include_file_path);
+    f(&mut config);
 
-    processor::process(&mut workspace, &config, &["cpp_parser".into()], None).unwrap();
+    let steps = ["cpp_parser"]
+        .iter()
+        .chain(steps)
+        .map(|&step| step.to_string())
+        .collect::<Vec<_>>();
+    processor::process(&mut workspace, &config, &steps, None).unwrap();
 
     let database = workspace
         .get_database_client("A", &[], true, false)
@@ -53,6 +72,11 @@ fn run_parser(code: &'static str) -> ParserCppData {
             .filter_map(|item| item.item.as_type_ref())
             .cloned()
             .collect(),
+        type_aliases: database
+            .cpp_items()
+            .filter_map(|item| item.item.as_type_alias_ref())
+            .cloned()
+            .collect(),
         bases: database
             .cpp_items()
             .filter_map(|item| item.item.as_base_ref())
@@ -63,6 +87,11 @@ fn run_parser(code: &'static str) -> ParserCppData {
             .filter_map(|item| item.item.as_field_ref())
             .cloned()
             .collect(),
+        variables: database
+            .cpp_items()
+            .filter_map(|item| item.item.as_variable_ref())
+            .cloned()
+            .collect(),
         enum_values: database
             .cpp_items()
             .filter_map(|item| item.item.as_enum_value_ref())
@@ -78,6 +107,13 @@ fn run_parser(code: &'static str) -> ParserCppData {
             .filter_map(|item| item.item.as_namespace_ref())
             .map(|ns| ns.path.clone())
             .collect(),
+        docs: database
+            .cpp_items()
+            .filter_map(|item| {
+                let doc = database.find_doc_for(&item.id).unwrap()?;
+                Some((item.item.clone(), doc.item.html.clone()))
+            })
+            .collect(),
     }
 }
 
@@ -97,10 +133,17 @@ fn simple_func() {
                 name: "x".to_string(),
                 argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             }],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("int func1 ( int x )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }
     );
 }
@@ -127,12 +170,53 @@ fn simple_func_with_default_value() {
                 name: "x".to_string(),
                 argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
                 has_default_value: true,
+                default_value: Some("42".to_string()),
+                type_alias: None,
             }],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("bool func1 ( int x = 42 )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
+        }
+    );
+}
+
+#[test]
+fn func_with_default_value_expressions() {
+    let data = run_parser(
+        "
+        namespace ns {
+            enum Flag { A = 1, B = 2 };
+            struct Size {
+                Size(int w, int h);
+            };
         }
+        void func1(ns::Size size = ns::Size(-1, 0), int flags = ns::A | ns::B, const char* name = nullptr);
+        ",
     );
+    let function = data
+        .methods
+        .iter()
+        .find(|m| m.path == CppPath::from_good_str("func1"))
+        .unwrap();
+    let default_values = function
+        .arguments
+        .iter()
+        .map(|arg| arg.default_value.as_ref().map(|s| s.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        default_values,
+        vec![
+            Some("ns::Size(-1, 0)"),
+            Some("ns::A | ns::B"),
+            Some("nullptr")
+        ]
+    );
+    assert!(function.arguments.iter().all(|arg| arg.has_default_value));
 }
 
 #[test]
@@ -192,10 +276,17 @@ fn functions_with_class_arg() {
                 name: "x".to_string(),
                 argument_type: CppType::Class(CppPath::from_good_str("Magic")),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             }],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("bool func1 ( Magic x )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }
     );
     assert_eq!(
@@ -212,10 +303,17 @@ fn functions_with_class_arg() {
                     CppType::Class(CppPath::from_good_str("Magic"))
                 ),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             }],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("bool func1 ( Magic * x )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }
     );
     assert_eq!(
@@ -232,10 +330,17 @@ fn functions_with_class_arg() {
                     CppType::Class(CppPath::from_good_str("Magic"))
                 ),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             }],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("bool func2 ( const Magic & )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }
     );
 }
@@ -254,6 +359,36 @@ fn free_operator() {
     assert_eq!(data.methods.len(), 1);
 }
 
+#[test]
+fn friend_operator() {
+    let data = run_parser(
+        "
+        namespace ns {
+            class C1 {
+                friend bool operator==(const C1& one, const C1& other) { return true; }
+                friend class C2;
+            };
+            bool operator!=(const C1& one, const C1& other);
+        }
+        ",
+    );
+
+    assert_eq!(data.methods.len(), 2);
+    assert_eq!(
+        data.methods[0].path,
+        CppPath::from_good_str("ns::operator==")
+    );
+    assert_eq!(data.methods[0].operator, Some(CppOperator::EqualTo));
+    assert!(data.methods[0].member.is_none());
+    assert!(data.methods[0].is_friend);
+
+    assert_eq!(
+        data.methods[1].path,
+        CppPath::from_good_str("ns::operator!=")
+    );
+    assert!(!data.methods[1].is_friend);
+}
+
 #[test]
 fn func_with_unknown_type() {
     let data = run_parser(
@@ -285,12 +420,37 @@ fn variadic_func() {
                     CppType::BuiltInNumeric(CppBuiltInNumericType::Char)
                 ),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             }],
             allows_variadic_arguments: true,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("int my_printf ( const char * format , ... )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
+        }
+    );
+}
+
+#[test]
+fn variadic_func_with_c_linkage() {
+    let data = run_parser(
+        "
+        extern \"C\" {
+            int my_printf(const char* format, ...);
         }
+        extern \"C++\" int my_printf2(const char* format, ...);
+        ",
     );
+    assert_eq!(data.methods.len(), 2);
+    assert_eq!(data.methods[0].path, CppPath::from_good_str("my_printf"));
+    assert!(data.methods[0].allows_variadic_arguments);
+    assert!(data.methods[0].has_c_linkage);
+    assert_eq!(data.methods[1].path, CppPath::from_good_str("my_printf2"));
+    assert!(!data.methods[1].has_c_linkage);
 }
 
 #[test]
@@ -311,7 +471,9 @@ fn free_template_func() {
             nested_level: 0,
             index: 0,
             name: "T".into(),
+            value_type: None,
         })]),
+        inline_namespaces: Vec::new(),
     };
     assert_eq!(
         data.methods[0],
@@ -323,6 +485,7 @@ fn free_template_func() {
                 nested_level: 0,
                 index: 0,
                 name: "T".into(),
+                value_type: None,
             }),
             arguments: vec![CppFunctionArgument {
                 name: "value".to_string(),
@@ -330,12 +493,20 @@ fn free_template_func() {
                     nested_level: 0,
                     index: 0,
                     name: "T".into(),
+                    value_type: None,
                 }),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             }],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("template < typename T > T abs ( T value )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }
     );
 }
@@ -367,16 +538,25 @@ fn free_func_operator_sub() {
                         name: "a".to_string(),
                         argument_type: CppType::Class(CppPath::from_good_str("C1")),
                         has_default_value: false,
+                        default_value: None,
+                        type_alias: None,
                     },
                     CppFunctionArgument {
                         name: "b".to_string(),
                         argument_type: CppType::Class(CppPath::from_good_str("C1")),
                         has_default_value: false,
+                        default_value: None,
+                        type_alias: None,
                     },
                 ],
                 allows_variadic_arguments: false,
+                has_c_linkage: false,
                 cast: None,
                 declaration_code: Some("C1 operator - ( C1 a , C1 b )".to_string()),
+                deprecation: None,
+                is_deleted: false,
+                is_friend: false,
+                return_type_alias: None,
             }
         );
     }
@@ -410,6 +590,7 @@ fn simple_class_method() {
                 kind: CppFunctionKind::Regular,
                 is_virtual: false,
                 is_pure_virtual: false,
+                is_final: false,
                 is_const: false,
                 is_static: false,
                 visibility: CppVisibility::Public,
@@ -422,10 +603,17 @@ fn simple_class_method() {
                 name: "x".to_string(),
                 argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             }],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("int func1 ( int x )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }
     );
 }
@@ -534,13 +722,16 @@ fn advanced_class_methods() {
                 nested_level: 0,
                 index: 0,
                 name: "K".into(),
+                value_type: None,
             }),
             CppType::TemplateParameter(CppTemplateParameter {
                 nested_level: 0,
                 index: 1,
                 name: "V".into(),
+                value_type: None,
             }),
         ]),
+        inline_namespaces: Vec::new(),
     };
     assert_eq!(
         data.methods[7].path,
@@ -553,6 +744,7 @@ fn advanced_class_methods() {
             nested_level: 0,
             index: 1,
             name: "V".into(),
+            value_type: None,
         }),
     );
 }
@@ -577,7 +769,9 @@ fn template_class_method() {
             nested_level: 0,
             index: 0,
             name: "T".into(),
+            value_type: None,
         })]),
+        inline_namespaces: Vec::new(),
     };
     let my_vector_path = CppPath::from_item(my_vector_item.clone());
     assert_eq!(data.types[0].path, my_vector_path);
@@ -600,6 +794,7 @@ fn template_class_method() {
                 kind: CppFunctionKind::Regular,
                 is_virtual: false,
                 is_pure_virtual: false,
+                is_final: false,
                 is_const: false,
                 is_static: false,
                 visibility: CppVisibility::Public,
@@ -611,15 +806,23 @@ fn template_class_method() {
                 nested_level: 0,
                 index: 0,
                 name: "T".into(),
+                value_type: None,
             }),
             arguments: vec![CppFunctionArgument {
                 name: "index".to_string(),
                 argument_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
                 has_default_value: false,
+                default_value: None,
+                type_alias: None,
             }],
             allows_variadic_arguments: false,
+            has_c_linkage: false,
             cast: None,
             declaration_code: Some("T get ( int index )".to_string()),
+            deprecation: None,
+            is_deleted: false,
+            is_friend: false,
+            return_type_alias: None,
         }
     );
     assert_eq!(
@@ -636,6 +839,110 @@ fn template_class_method() {
     assert!(data.namespaces.is_empty());
 }
 
+#[test]
+fn template_class_with_value_parameter() {
+    let data = run_parser(
+        "
+        template<class T, int N>
+        class MyArray {
+        public:
+            T get(int index);
+        };
+        MyArray<bool, 8> make_array();
+        ",
+    );
+    let my_array_item = CppPathItem {
+        name: "MyArray".into(),
+        template_arguments: Some(vec![
+            CppType::TemplateParameter(CppTemplateParameter {
+                nested_level: 0,
+                index: 0,
+                name: "T".into(),
+                value_type: None,
+            }),
+            CppType::TemplateParameter(CppTemplateParameter {
+                nested_level: 0,
+                index: 1,
+                name: "N".into(),
+                value_type: Some(Box::new(CppType::BuiltInNumeric(
+                    CppBuiltInNumericType::Int,
+                ))),
+            }),
+        ]),
+        inline_namespaces: Vec::new(),
+    };
+    assert_eq!(data.types.len(), 1);
+    assert_eq!(
+        data.types[0].path,
+        CppPath::from_item(my_array_item.clone())
+    );
+
+    assert_eq!(data.methods.len(), 2);
+    assert_eq!(
+        data.methods[0].path,
+        CppPath::from_items(vec![my_array_item, CppPathItem::from_good_str("get")])
+    );
+    let instantiated_path = CppPath::from_item(CppPathItem {
+        name: "MyArray".into(),
+        template_arguments: Some(vec![
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Bool),
+            CppType::IntegralConstant {
+                value_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Int)),
+                value: 8,
+            },
+        ]),
+        inline_namespaces: Vec::new(),
+    });
+    assert_eq!(data.methods[1].path, CppPath::from_good_str("make_array"));
+    assert_eq!(
+        data.methods[1].return_type,
+        CppType::Class(instantiated_path.clone())
+    );
+    assert_eq!(
+        instantiated_path.to_cpp_code().unwrap(),
+        "MyArray< bool, 8 >"
+    );
+}
+
+#[test]
+fn requested_template_instantiation() {
+    let data = run_parser_with_config(
+        "
+        template<class T, int N>
+        class MyArray {
+        public:
+            T get(int index);
+        };
+        ",
+        &["find_template_instantiations", "instantiate_templates"],
+        |config| config.add_template_instantiation("MyArray<bool, 8>"),
+    );
+    let instantiated_path = CppPath::from_item(CppPathItem {
+        name: "MyArray".into(),
+        template_arguments: Some(vec![
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Bool),
+            CppType::IntegralConstant {
+                value_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Int)),
+                value: 8,
+            },
+        ]),
+        inline_namespaces: Vec::new(),
+    });
+    assert_eq!(data.types.len(), 2);
+    assert!(data.types.iter().any(|t| t.path == instantiated_path));
+
+    assert_eq!(data.methods.len(), 2);
+    let get = data
+        .methods
+        .iter()
+        .find(|m| m.path == instantiated_path.join(CppPathItem::from_good_str("get")))
+        .unwrap();
+    assert_eq!(
+        get.return_type,
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)
+    );
+}
+
 #[test]
 fn template_parameter_level() {
     let data = run_parser(
@@ -656,6 +963,7 @@ fn template_parameter_level() {
             nested_level: 1,
             index: 0,
             name: "T".into(),
+            value_type: None,
         })
     );
 }
@@ -682,6 +990,7 @@ fn template_parameter_level2() {
             nested_level: 1,
             index: 0,
             name: "T".into(),
+            value_type: None,
         })
     );
 }
@@ -706,7 +1015,9 @@ fn template_class_template_method() {
             nested_level: 0,
             index: 0,
             name: "T".into(),
+            value_type: None,
         })]),
+        inline_namespaces: Vec::new(),
     };
     assert_eq!(
         data.methods[0].path,
@@ -718,7 +1029,9 @@ fn template_class_template_method() {
                     nested_level: 1,
                     index: 0,
                     name: "F".into(),
-                })])
+                    value_type: None,
+                })]),
+                inline_namespaces: Vec::new(),
             }
         ])
     );
@@ -728,6 +1041,7 @@ fn template_class_template_method() {
             nested_level: 1,
             index: 0,
             name: "F".into(),
+            value_type: None,
         }),
     );
 
@@ -741,6 +1055,7 @@ fn template_class_template_method() {
             nested_level: 0,
             index: 0,
             name: "T".into(),
+            value_type: None,
         }),
     );
 }
@@ -757,7 +1072,12 @@ fn simple_enum() {
     );
     assert_eq!(data.types.len(), 1);
     assert_eq!(data.types[0].path, CppPath::from_good_str("Enum1"));
-    assert_eq!(data.types[0].kind, CppTypeDeclarationKind::Enum);
+    assert_eq!(
+        data.types[0].kind,
+        CppTypeDeclarationKind::Enum {
+            underlying_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        }
+    );
     assert_eq!(
         data.enum_values,
         vec![
@@ -788,7 +1108,12 @@ fn simple_enum2() {
     );
     assert_eq!(data.types.len(), 1);
     assert_eq!(data.types[0].path, CppPath::from_good_str("ns1::Enum1"));
-    assert_eq!(data.types[0].kind, CppTypeDeclarationKind::Enum);
+    assert_eq!(
+        data.types[0].kind,
+        CppTypeDeclarationKind::Enum {
+            underlying_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        }
+    );
     assert_eq!(
         data.enum_values,
         vec![
@@ -826,6 +1151,7 @@ fn template_instantiation() {
     let vector_int_item = CppPathItem {
         name: "Vector".to_string(),
         template_arguments: Some(vec![int]),
+        inline_namespaces: Vec::new(),
     };
     assert_eq!(
         data.methods[0].return_type,
@@ -1054,6 +1380,7 @@ fn fixed_size_integers() {
     let vector_gluint64_item = CppPathItem {
         name: "QVector".to_string(),
         template_arguments: Some(vec![type1]),
+        inline_namespaces: Vec::new(),
     };
     assert_eq!(
         &data.methods[1].return_type,
@@ -1061,6 +1388,53 @@ fn fixed_size_integers() {
     );
 }
 
+#[test]
+fn array_field() {
+    let data = run_parser(
+        "
+        struct Item {
+            char name[32];
+            float matrix[4][4];
+        };
+        ",
+    );
+    assert_eq!(data.fields.len(), 2);
+    assert_eq!(data.fields[0].path, CppPath::from_good_str("Item::name"));
+    assert_eq!(
+        data.fields[0].field_type,
+        CppType::Array {
+            element: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Char)),
+            len: 32,
+        },
+    );
+    assert_eq!(
+        data.fields[1].field_type,
+        CppType::Array {
+            element: Box::new(CppType::Array {
+                element: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Float)),
+                len: 4,
+            }),
+            len: 4,
+        },
+    );
+}
+
+#[test]
+fn array_argument() {
+    let data = run_parser("void transform(float m[4][4]);");
+    assert_eq!(data.methods.len(), 1);
+    assert_eq!(
+        data.methods[0].arguments[0].argument_type,
+        CppType::Array {
+            element: Box::new(CppType::Array {
+                element: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Float)),
+                len: 4,
+            }),
+            len: 4,
+        },
+    );
+}
+
 #[test]
 fn template_class_with_base() {
     let data = run_parser(
@@ -1079,7 +1453,9 @@ fn template_class_with_base() {
             nested_level: 0,
             index: 0,
             name: "T".into(),
+            value_type: None,
         })]),
+        inline_namespaces: Vec::new(),
     };
     assert_eq!(data.types[0].path, CppPath::from_item(c1_item));
     assert!(data.types[0].kind.is_class());
@@ -1090,7 +1466,9 @@ fn template_class_with_base() {
             nested_level: 0,
             index: 0,
             name: "T".into(),
+            value_type: None,
         })]),
+        inline_namespaces: Vec::new(),
     };
     assert_eq!(data.types[1].path, CppPath::from_item(c2_item));
     assert!(data.types[1].kind.is_class());
@@ -1126,6 +1504,71 @@ fn namespaces() {
     assert!(data.namespaces.contains(&CppPath::from_good_str("a::c")));
 }
 
+#[test]
+fn inline_namespaces() {
+    let data = run_parser(
+        "
+        namespace a {
+            inline namespace v1 {
+                class X {};
+                void f(X* x);
+            }
+            namespace v1 {
+                void g();
+            }
+        }
+        ",
+    );
+    assert_eq!(data.namespaces, vec![CppPath::from_good_str("a")]);
+    assert_eq!(data.types.len(), 1);
+    assert_eq!(data.types[0].path, CppPath::from_good_str("a::X"));
+    // the inline namespace is only kept for the C++ code
+    assert_eq!(data.types[0].path.to_cpp_code().unwrap(), "a::v1::X");
+
+    let f = data
+        .methods
+        .iter()
+        .find(|m| m.path == CppPath::from_good_str("a::f"))
+        .unwrap();
+    assert_eq!(
+        f.arguments[0].argument_type,
+        CppType::new_pointer(false, CppType::Class(CppPath::from_good_str("a::X")))
+    );
+    assert!(data
+        .methods
+        .iter()
+        .any(|m| m.path == CppPath::from_good_str("a::g")));
+}
+
+#[test]
+fn inline_namespaces_opened_by_macros() {
+    let data = run_parser(
+        "
+        #define BEGIN_NAMESPACE_B namespace b { inline namespace v2 {
+        #define END_NAMESPACE_B } }
+
+        BEGIN_NAMESPACE_B
+        class Y {};
+        void h(Y* y);
+        END_NAMESPACE_B
+        ",
+    );
+    assert_eq!(data.namespaces, vec![CppPath::from_good_str("b")]);
+    assert_eq!(data.types.len(), 1);
+    assert_eq!(data.types[0].path, CppPath::from_good_str("b::Y"));
+    assert_eq!(data.types[0].path.to_cpp_code().unwrap(), "b::v2::Y");
+
+    let h = data
+        .methods
+        .iter()
+        .find(|m| m.path == CppPath::from_good_str("b::h"))
+        .unwrap();
+    assert_eq!(
+        h.arguments[0].argument_type,
+        CppType::new_pointer(false, CppType::Class(CppPath::from_good_str("b::Y")))
+    );
+}
+
 #[test]
 fn empty_namespace() {
     let data = run_parser(
@@ -1188,6 +1631,7 @@ fn template_conversion_operator() {
     let vec_item = CppPathItem {
         name: "Vec".to_string(),
         template_arguments: Some(vec![CppType::BuiltInNumeric(CppBuiltInNumericType::Int)]),
+        inline_namespaces: Vec::new(),
     };
     let vec_type = CppType::Class(CppPath::from_item(vec_item));
 
@@ -1220,3 +1664,382 @@ fn elaborated_enum_type() {
         }
     );
 }
+
+#[test]
+fn type_aliases() {
+    let data = run_parser(
+        "
+        namespace ns {
+            class A {
+            public:
+                typedef unsigned int size_type;
+            };
+            typedef A* APtr;
+            using Handle = int;
+            template<typename T> using Ptr = T*;
+        }
+        typedef struct B { int x; } B;
+        ",
+    );
+
+    assert_eq!(data.type_aliases.len(), 3);
+    assert!(data.type_aliases.contains(&CppTypeAlias {
+        path: CppPath::from_good_str("ns::A::size_type"),
+        target_type: CppType::BuiltInNumeric(CppBuiltInNumericType::UInt),
+    }));
+    assert!(data.type_aliases.contains(&CppTypeAlias {
+        path: CppPath::from_good_str("ns::APtr"),
+        target_type: CppType::new_pointer(false, CppType::Class(CppPath::from_good_str("ns::A"))),
+    }));
+    assert!(data.type_aliases.contains(&CppTypeAlias {
+        path: CppPath::from_good_str("ns::Handle"),
+        target_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+    }));
+}
+
+#[test]
+fn type_aliases_in_signatures() {
+    let data = run_parser(
+        "
+        namespace ns {
+            using Handle = int;
+            Handle f(const Handle& a, Handle* b, int c);
+        }
+        ",
+    );
+
+    assert_eq!(data.methods.len(), 1);
+    let handle = CppPath::from_good_str("ns::Handle");
+    let function = &data.methods[0];
+    assert_eq!(
+        function.return_type,
+        CppType::BuiltInNumeric(CppBuiltInNumericType::Int)
+    );
+    assert_eq!(function.return_type_alias, Some(handle.clone()));
+    assert_eq!(
+        function.arguments[0].argument_type,
+        CppType::new_reference(true, CppType::BuiltInNumeric(CppBuiltInNumericType::Int))
+    );
+    assert_eq!(function.arguments[0].type_alias, Some(handle.clone()));
+    assert_eq!(function.arguments[1].type_alias, Some(handle));
+    assert_eq!(function.arguments[2].type_alias, None);
+}
+
+#[test]
+fn variables_and_constants() {
+    let data = run_parser(
+        "
+        namespace ns {
+            extern int counter;
+            constexpr int version = 0x050d00;
+            const double ratio = 1.5;
+            class A {
+            public:
+                static const unsigned long npos = static_cast<unsigned long>(-1);
+                static int instances;
+            };
+        }
+        ",
+    );
+
+    assert_eq!(data.variables.len(), 3);
+    assert!(data.variables.contains(&CppVariable {
+        path: CppPath::from_good_str("ns::counter"),
+        variable_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        is_const: false,
+        value: None,
+    }));
+    assert!(data.variables.contains(&CppVariable {
+        path: CppPath::from_good_str("ns::version"),
+        variable_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        is_const: true,
+        value: Some(CppConstantValue::SignedInteger(0x050d00)),
+    }));
+    assert!(data.variables.contains(&CppVariable {
+        path: CppPath::from_good_str("ns::ratio"),
+        variable_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Double),
+        is_const: true,
+        value: Some(CppConstantValue::FloatingPoint("1.5".to_string())),
+    }));
+
+    assert_eq!(data.fields.len(), 2);
+    assert_eq!(data.fields[0].path, CppPath::from_good_str("ns::A::npos"));
+    assert!(data.fields[0].is_static);
+    assert!(data.fields[0].is_const);
+    assert_eq!(
+        data.fields[0].value,
+        Some(CppConstantValue::UnsignedInteger(std::u64::MAX))
+    );
+    assert_eq!(
+        data.fields[1].path,
+        CppPath::from_good_str("ns::A::instances")
+    );
+    assert!(data.fields[1].is_static);
+    assert!(!data.fields[1].is_const);
+    assert_eq!(data.fields[1].value, None);
+}
+
+#[test]
+fn unions_and_anonymous_members() {
+    let data = run_parser(
+        "
+        union Value {
+            int i;
+            float f;
+        };
+        struct Event {
+            int type;
+            union {
+                int key;
+                struct {
+                    int x;
+                    int y;
+                };
+            };
+            struct {
+                int width;
+                int height;
+            } size;
+        };
+        ",
+    );
+
+    assert_eq!(data.types.len(), 2);
+    assert_eq!(data.types[0].path, CppPath::from_good_str("Value"));
+    assert_eq!(data.types[0].kind, CppTypeDeclarationKind::Union);
+    assert!(data.types[0].kind.is_class());
+    assert_eq!(data.types[1].path, CppPath::from_good_str("Event"));
+    assert_eq!(data.types[1].kind, CppTypeDeclarationKind::Class);
+
+    let fields = data
+        .fields
+        .iter()
+        .map(|f| (f.path.to_cpp_pseudo_code(), f.member_access.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("Value::i".to_string(), None),
+            ("Value::f".to_string(), None),
+            ("Event::type".to_string(), None),
+            ("Event::key".to_string(), None),
+            ("Event::x".to_string(), None),
+            ("Event::y".to_string(), None),
+            (
+                "Event::size_width".to_string(),
+                Some("size.width".to_string())
+            ),
+            (
+                "Event::size_height".to_string(),
+                Some("size.height".to_string())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn enum_underlying_types() {
+    let data = run_parser(
+        "
+        enum class Small : unsigned char { A = 1, B = 255 };
+        enum Flags { D = 0x80000000 };
+        enum Negative { E = -1 };
+        ",
+    );
+    let kinds = data
+        .types
+        .iter()
+        .map(|t| (t.path.to_cpp_pseudo_code(), t.kind.clone()))
+        .collect::<Vec<_>>();
+    let enum_kind = |underlying_type| CppTypeDeclarationKind::Enum {
+        underlying_type: CppType::BuiltInNumeric(underlying_type),
+    };
+    assert_eq!(
+        kinds,
+        vec![
+            ("Small".to_string(), enum_kind(CppBuiltInNumericType::UChar)),
+            ("Flags".to_string(), enum_kind(CppBuiltInNumericType::UInt)),
+            (
+                "Negative".to_string(),
+                enum_kind(CppBuiltInNumericType::Int)
+            ),
+        ]
+    );
+    assert_eq!(data.enum_values[1].path, CppPath::from_good_str("Small::B"));
+    assert_eq!(data.enum_values[1].value, 255);
+    assert_eq!(data.enum_values[2].value, 0x8000_0000);
+}
+
+#[test]
+fn deprecated_and_deleted_items() {
+    let data = run_parser(
+        "
+        class __attribute__((deprecated(\"use Class2\"))) Class1 {};
+        class Class2 {
+        public:
+            Class2();
+            Class2(const Class2& other) = delete;
+            __attribute__((deprecated)) void f1();
+            __attribute__((deprecated(\"use f1\"))) void f2();
+            void f3();
+        };
+        ",
+    );
+    let class1 = data
+        .types
+        .iter()
+        .find(|t| t.path == CppPath::from_good_str("Class1"))
+        .unwrap();
+    assert_eq!(
+        class1.deprecation,
+        Some(CppDeprecation {
+            message: Some("use Class2".to_string())
+        })
+    );
+    assert!(!class1.is_deleted);
+
+    let find = |name: &str, args_count: usize| {
+        let function = data
+            .methods
+            .iter()
+            .find(|m| m.path.to_cpp_pseudo_code() == name && m.arguments.len() == args_count)
+            .unwrap();
+        (
+            function.deprecation.clone().map(|d| d.message),
+            function.is_deleted,
+        )
+    };
+    assert_eq!(find("Class2::Class2", 0), (None, false));
+    assert_eq!(find("Class2::Class2", 1), (None, true));
+    assert_eq!(find("Class2::f1", 0), (Some(None), false));
+    assert_eq!(
+        find("Class2::f2", 0),
+        (Some(Some("use f1".to_string())), false)
+    );
+    assert_eq!(find("Class2::f3", 0), (None, false));
+}
+
+#[test]
+fn deprecated_class_with_deprecated_members() {
+    let data = run_parser(
+        "
+        #define DEPRECATED_X(text) __attribute__((deprecated(text)))
+        class [[deprecated]] C1 {
+        public:
+            [[deprecated(\"use g\")]] void f();
+            void g();
+        };
+        class DEPRECATED_X(\"use C1\") C2 {
+        public:
+            DEPRECATED_X(\"use g\") void f();
+        };
+        ",
+    );
+    let find_type = |name: &str| {
+        data.types
+            .iter()
+            .find(|t| t.path == CppPath::from_good_str(name))
+            .unwrap()
+            .deprecation
+            .clone()
+            .map(|d| d.message)
+    };
+    // messages of members are not used for the class
+    assert_eq!(find_type("C1"), Some(None));
+    assert_eq!(find_type("C2"), Some(Some("use C1".to_string())));
+
+    let find_method = |name: &str| {
+        data.methods
+            .iter()
+            .find(|m| m.path.to_cpp_pseudo_code() == name)
+            .unwrap()
+            .deprecation
+            .clone()
+            .map(|d| d.message)
+    };
+    assert_eq!(find_method("C1::f"), Some(Some("use g".to_string())));
+    assert_eq!(find_method("C1::g"), None);
+    assert_eq!(find_method("C2::f"), Some(Some("use g".to_string())));
+}
+
+#[test]
+fn doc_comments() {
+    let code = r#"
+        /// Brief description of the class.
+        class Class1 {
+        public:
+            /**
+             * Does something.
+             * \param x Some value.
+             */
+            void f(int x);
+
+            int g();
+        };
+
+        void h();
+        /// Declared twice.
+        void h();"#;
+
+    let data = run_parser(code);
+    assert!(data.docs.is_empty());
+
+    let data = run_parser_with_config(code, &[], |config| config.set_parse_doc_comments(true));
+    assert_eq!(data.docs.len(), 3);
+    let class_doc = data
+        .docs
+        .iter()
+        .find(|(item, _)| item.path() == Some(&CppPath::from_good_str("Class1")))
+        .map(|(_, html)| html)
+        .unwrap();
+    assert!(class_doc.contains("Brief description of the class."));
+    let f_doc = data
+        .docs
+        .iter()
+        .find(|(item, _)| item.path() == Some(&CppPath::from_good_str("Class1::f")))
+        .map(|(_, html)| html)
+        .unwrap();
+    assert!(f_doc.contains("Does something."));
+    assert!(f_doc.contains("Some value."));
+    let h_doc = data
+        .docs
+        .iter()
+        .find(|(item, _)| item.path() == Some(&CppPath::from_good_str("h")))
+        .map(|(_, html)| html)
+        .unwrap();
+    assert!(h_doc.contains("Declared twice."));
+}
+
+#[test]
+fn member_function_template_instantiation() {
+    let data = run_parser_with_config(
+        "
+        class Item {};
+        class Finder {
+        public:
+            template<class T> T find();
+        };
+        ",
+        &["instantiate_template_functions"],
+        |config| config.set_instantiate_template_functions(true),
+    );
+    // the template and its instantiations for `Item`, `Item*`, `Finder` and `Finder*`
+    assert_eq!(data.methods.len(), 5);
+
+    let item_type = CppType::Class(CppPath::from_good_str("Item"));
+    let item_ptr = CppType::new_pointer(false, item_type);
+    let find = data
+        .methods
+        .iter()
+        .find(|m| {
+            m.path
+                == CppPath::from_good_str("Finder").join(CppPathItem {
+                    name: "find".into(),
+                    template_arguments: Some(vec![item_ptr.clone()]),
+                    inline_namespaces: Vec::new(),
+                })
+        })
+        .unwrap();
+    assert_eq!(find.return_type, item_ptr);
+    assert!(find.arguments.is_empty());
+}
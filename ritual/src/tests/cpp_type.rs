@@ -2,7 +2,7 @@ use crate::cpp_data::{CppPath, CppPathItem};
 use crate::cpp_ffi_data::CppToFfiTypeConversion;
 use crate::cpp_ffi_generator::ffi_type;
 use crate::cpp_type::{
-    CppBuiltInNumericType, CppFunctionPointerType, CppSpecificNumericType,
+    CppBuiltInNumericType, CppFunctionPointerType, CppPointerLikeTypeKind, CppSpecificNumericType,
     CppSpecificNumericTypeKind, CppTemplateParameter, CppType, CppTypeRole,
 };
 
@@ -205,6 +205,32 @@ fn class_mut_ref() {
     }
 }
 
+#[test]
+fn class_rvalue_ref() {
+    let type1 = CppType::PointerLike {
+        kind: CppPointerLikeTypeKind::RValueReference,
+        is_const: false,
+        target: Box::new(CppType::Class(CppPath::from_good_str("QRectF"))),
+    };
+    assert_eq!(type1.is_void(), false);
+    assert_eq!(type1.is_class(), false);
+    assert_eq!(type1.to_cpp_code(None).unwrap(), "QRectF &&");
+
+    let ffi1 = ffi_type(&type1, CppTypeRole::NotReturnType).unwrap();
+    assert_eq!(ffi1.original_type(), &type1);
+    assert_eq!(
+        ffi1.ffi_type(),
+        &CppType::new_pointer(false, CppType::Class(CppPath::from_good_str("QRectF")))
+    );
+    assert_eq!(ffi1.ffi_type().to_cpp_code(None).unwrap(), "QRectF *");
+    assert_eq!(
+        ffi1.conversion(),
+        &CppToFfiTypeConversion::RValueReferenceToPointer
+    );
+
+    assert!(ffi_type(&type1, CppTypeRole::ReturnType).is_err());
+}
+
 #[test]
 fn class_mut_ptr() {
     let type1 = CppType::new_pointer(false, CppType::Class(CppPath::from_good_str("QObject")));
@@ -222,6 +248,7 @@ fn class_with_template_args() {
     let type1 = CppType::Class(CppPath::from_item(CppPathItem {
         name: "QVector".into(),
         template_arguments: args.clone(),
+        inline_namespaces: Vec::new(),
     }));
     assert_eq!(type1.is_void(), false);
     assert_eq!(type1.is_class(), true);
@@ -237,7 +264,8 @@ fn class_with_template_args() {
             false,
             CppType::Class(CppPath::from_item(CppPathItem {
                 name: "QVector".into(),
-                template_arguments: args.clone()
+                template_arguments: args.clone(),
+                inline_namespaces: Vec::new(),
             }))
         ),
     );
@@ -261,6 +289,7 @@ fn class_with_template_args() {
             CppType::Class(CppPath::from_item(CppPathItem {
                 name: "QVector".into(),
                 template_arguments: args,
+                inline_namespaces: Vec::new(),
             }))
         )
     );
@@ -282,11 +311,13 @@ fn nested_template_cpp_code() {
         CppType::Class(CppPath::from_item(CppPathItem {
             name: "QList".into(),
             template_arguments: qlist_args,
+            inline_namespaces: Vec::new(),
         })),
     ]);
     let type1 = CppType::Class(CppPath::from_item(CppPathItem {
         name: "QHash".into(),
         template_arguments: qhash_args,
+        inline_namespaces: Vec::new(),
     }));
     let code = type1.to_cpp_code(None).unwrap();
     assert_eq!(&code, "QHash< QString, QList< QString > >");
@@ -302,6 +333,7 @@ fn qflags() {
     let type1 = CppType::Class(CppPath::from_item(CppPathItem {
         name: "QFlags".into(),
         template_arguments: args,
+        inline_namespaces: Vec::new(),
     }));
     assert_eq!(type1.is_void(), false);
     assert_eq!(type1.is_class(), true);
@@ -332,6 +364,7 @@ fn template_parameter() {
             nested_level: 0,
             index: 0,
             name: "T".into(),
+            value_type: None,
         }),
     );
     assert_eq!(type1.is_void(), false);
@@ -360,6 +393,81 @@ fn function1() {
     assert_type_to_ffi_unchanged(&type1);
 }
 
+#[test]
+fn variadic_function() {
+    let type1 = CppType::FunctionPointer(CppFunctionPointerType {
+        allows_variadic_arguments: true,
+        return_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Int)),
+        arguments: vec![CppType::new_pointer(
+            true,
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Char),
+        )],
+    });
+    assert_eq!(
+        type1.to_cpp_code(Some("func")).unwrap(),
+        "int (*func)(char const *, ...)"
+    );
+    assert_type_to_ffi_unchanged(&type1);
+}
+
+#[test]
+fn std_function() {
+    let rect_ref = CppType::new_reference(true, CppType::Class(CppPath::from_good_str("QRectF")));
+    let function_type = CppFunctionPointerType {
+        allows_variadic_arguments: false,
+        return_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)),
+        arguments: vec![
+            rect_ref.clone(),
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        ],
+    };
+    let type1 = CppType::Class(CppPath::from_items(vec![
+        CppPathItem::from_good_str("std"),
+        CppPathItem {
+            name: "function".into(),
+            template_arguments: Some(vec![CppType::FunctionPointer(function_type.clone())]),
+            inline_namespaces: Vec::new(),
+        },
+    ]));
+    assert_eq!(type1.as_std_function(), Some(&function_type));
+    assert_eq!(
+        type1.to_cpp_code(None).unwrap(),
+        "std::function< bool(QRectF const &, int) >"
+    );
+
+    for t in &[type1.clone(), CppType::new_reference(true, type1.clone())] {
+        let ffi1 = ffi_type(t, CppTypeRole::NotReturnType).unwrap();
+        assert_eq!(ffi1.original_type(), t);
+        assert_eq!(
+            ffi1.ffi_type().to_cpp_code(Some("callback")).unwrap(),
+            "bool (*callback)(void *, QRectF const *, int)"
+        );
+        if let CppToFfiTypeConversion::FunctionToCallback {
+            arguments,
+            return_type,
+        } = ffi1.conversion()
+        {
+            assert_eq!(arguments.len(), 2);
+            assert_eq!(arguments[0].original_type(), &rect_ref);
+            assert_eq!(
+                arguments[0].conversion(),
+                &CppToFfiTypeConversion::ReferenceToPointer
+            );
+            assert_eq!(return_type.conversion(), &CppToFfiTypeConversion::NoChange);
+        } else {
+            panic!("unexpected conversion: {:?}", ffi1.conversion());
+        }
+    }
+
+    let ffi_return_type = ffi_type(&type1, CppTypeRole::ReturnType).unwrap();
+    assert_eq!(
+        ffi_return_type.conversion(),
+        &CppToFfiTypeConversion::ValueToPointer {
+            is_ffi_const: false
+        }
+    );
+}
+
 #[test]
 fn instantiate1() {
     let type1 = CppType::new_reference(
@@ -368,6 +476,7 @@ fn instantiate1() {
             nested_level: 0,
             index: 0,
             name: "T".into(),
+            value_type: None,
         }),
     );
     let type2 = CppType::new_pointer(false, CppType::BuiltInNumeric(CppBuiltInNumericType::Bool));
@@ -380,3 +489,120 @@ fn instantiate1() {
         )
     );
 }
+
+#[test]
+fn integral_constant() {
+    let int_value = CppType::IntegralConstant {
+        value_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Int)),
+        value: -8,
+    };
+    assert_eq!(int_value.is_class(), false);
+    assert_eq!(int_value.is_template_parameter(), false);
+    assert_eq!(int_value.to_cpp_code(None).unwrap(), "-8");
+    assert_eq!(int_value.ascii_caption(), "minus_8");
+    assert!(ffi_type(&int_value, CppTypeRole::NotReturnType).is_err());
+
+    let bool_value = CppType::IntegralConstant {
+        value_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)),
+        value: 1,
+    };
+    assert_eq!(bool_value.to_cpp_code(None).unwrap(), "true");
+
+    let enum_value = CppType::IntegralConstant {
+        value_type: Box::new(CppType::Enum {
+            path: CppPath::from_good_str("Qt::AlignmentFlag"),
+        }),
+        value: 4,
+    };
+    assert_eq!(
+        enum_value.to_cpp_code(None).unwrap(),
+        "static_cast<Qt::AlignmentFlag>(4)"
+    );
+}
+
+#[test]
+fn instantiate_value_parameter() {
+    let array_path = CppPath::from_good_str("std").join(CppPathItem {
+        name: "array".into(),
+        template_arguments: Some(vec![
+            CppType::TemplateParameter(CppTemplateParameter {
+                nested_level: 0,
+                index: 0,
+                name: "T".into(),
+                value_type: None,
+            }),
+            CppType::TemplateParameter(CppTemplateParameter {
+                nested_level: 0,
+                index: 1,
+                name: "N".into(),
+                value_type: Some(Box::new(CppType::BuiltInNumeric(
+                    CppBuiltInNumericType::ULong,
+                ))),
+            }),
+        ]),
+        inline_namespaces: Vec::new(),
+    });
+    let type1 = CppType::new_pointer(false, CppType::Class(array_path));
+    let value = CppType::IntegralConstant {
+        value_type: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::ULong)),
+        value: 8,
+    };
+    let r = type1
+        .instantiate(
+            0,
+            &[CppType::BuiltInNumeric(CppBuiltInNumericType::Int), value],
+        )
+        .unwrap();
+    assert_eq!(r.to_cpp_code(None).unwrap(), "std::array< int, 8 > *");
+    assert!(!r.is_or_contains_template_parameter());
+
+    let r_path = r.pointer_like_to_target().unwrap();
+    if let CppType::Class(path) = r_path {
+        let deinstantiated = path.deinstantiate();
+        if let Some(CppType::TemplateParameter(param)) = deinstantiated
+            .last()
+            .template_arguments
+            .as_ref()
+            .and_then(|args| args.get(1))
+        {
+            assert_eq!(
+                param.value_type,
+                Some(Box::new(CppType::BuiltInNumeric(
+                    CppBuiltInNumericType::ULong
+                )))
+            );
+        } else {
+            panic!("expected template parameter");
+        }
+    } else {
+        panic!("expected class type");
+    }
+}
+
+#[test]
+fn array() {
+    let float4 = CppType::Array {
+        element: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Float)),
+        len: 4,
+    };
+    let type1 = CppType::Array {
+        element: Box::new(float4.clone()),
+        len: 3,
+    };
+    assert!(type1.is_array());
+    assert_eq!(type1.to_cpp_code(None).unwrap(), "float[3][4]");
+    assert_eq!(type1.ascii_caption(), "float_array_4_array_3");
+
+    let ptr = CppType::new_pointer(false, float4.clone());
+    assert_eq!(
+        ptr.to_cpp_code(None).unwrap(),
+        "std::add_pointer< float[4] >::type"
+    );
+
+    for role in &[CppTypeRole::NotReturnType, CppTypeRole::ReturnType] {
+        let ffi1 = ffi_type(&float4, *role).unwrap();
+        assert_eq!(ffi1.original_type(), &float4);
+        assert_eq!(ffi1.ffi_type(), &ptr);
+        assert_eq!(ffi1.conversion(), &CppToFfiTypeConversion::ArrayToPointer);
+    }
+}
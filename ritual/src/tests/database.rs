@@ -0,0 +1,68 @@
+use crate::cpp_data::{CppPath, CppTypeDeclaration, CppTypeDeclarationKind};
+use crate::cpp_type::{CppBuiltInNumericType, CppType};
+use crate::rust_info::{RustEnumValue, RustStructKind, RustWrapperTypeKind};
+use crate::rust_type::{RustCommonType, RustPath, RustType};
+use serde_json::json;
+
+#[test]
+fn load_old_enum_type_declaration() {
+    let declaration = CppTypeDeclaration {
+        path: CppPath::from_good_str("ns::E"),
+        kind: CppTypeDeclarationKind::Class,
+        deprecation: None,
+        is_deleted: false,
+        is_final: false,
+    };
+    let mut value = serde_json::to_value(&declaration).unwrap();
+    // databases created before underlying types of enums were recorded
+    // store the kind of enums as a unit variant
+    value["kind"] = json!("Enum");
+    let loaded: CppTypeDeclaration = serde_json::from_value(value).unwrap();
+    assert_eq!(
+        loaded.kind,
+        CppTypeDeclarationKind::Enum {
+            underlying_type: CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        }
+    );
+
+    let value = serde_json::to_value(&declaration).unwrap();
+    let loaded: CppTypeDeclaration = serde_json::from_value(value).unwrap();
+    assert_eq!(loaded, declaration);
+}
+
+#[test]
+fn load_old_enum_rust_items() {
+    let enum_value = RustEnumValue {
+        path: RustPath::from_good_str("A::ns::E::Value"),
+        value: "-1".into(),
+    };
+    let mut value = serde_json::to_value(&enum_value).unwrap();
+    assert_eq!(
+        serde_json::from_value::<RustEnumValue>(value.clone()).unwrap(),
+        enum_value
+    );
+    value["value"] = json!(-1);
+    assert_eq!(
+        serde_json::from_value::<RustEnumValue>(value).unwrap(),
+        enum_value
+    );
+
+    let loaded: RustStructKind =
+        serde_json::from_value(json!({ "WrapperType": "EnumWrapper" })).unwrap();
+    assert_eq!(
+        loaded,
+        RustStructKind::WrapperType(RustWrapperTypeKind::EnumWrapper {
+            underlying_type: RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("std::os::raw::c_int"),
+                generic_arguments: None,
+            }),
+            exhaustive_repr: None,
+        })
+    );
+    let loaded: RustStructKind =
+        serde_json::from_value(json!({ "WrapperType": "ImmovableClassWrapper" })).unwrap();
+    assert_eq!(
+        loaded,
+        RustStructKind::WrapperType(RustWrapperTypeKind::ImmovableClassWrapper)
+    );
+}
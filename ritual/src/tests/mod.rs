@@ -5,3 +5,5 @@ mod cpp_method;
 mod cpp_operator;
 mod cpp_parser;
 mod cpp_type;
+mod database;
+mod rust_generator;
@@ -0,0 +1,1420 @@
+use crate::config::{
+    Config, CrateDependencyKind, CrateDependencySource, CrateProperties, MovableTypesHookOutput,
+};
+use crate::cpp_checks::CppChecksItem;
+use crate::cpp_code_generator;
+use crate::cpp_data::CppPath;
+use crate::cpp_ffi_data::{CppFfiArgumentMeaning, CppToFfiTypeConversion};
+use crate::cpp_operator::CppOperator;
+use crate::cpp_type::{CppBuiltInNumericType, CppType};
+use crate::database::DatabaseClient;
+use crate::doc_formatter;
+use crate::processor::{self, ProcessorData};
+use crate::rust_code_generator::rust_type_to_code;
+use crate::rust_info::{RustFunctionKind, RustReexportSource, RustStructKind};
+use crate::rust_type::{RustCommonType, RustPath, RustToFfiTypeConversion, RustType};
+use crate::workspace::Workspace;
+use itertools::Itertools;
+use ritual_common::cpp_build_config::CppBuildPaths;
+use ritual_common::errors::Result;
+use ritual_common::file_utils::create_dir;
+use ritual_common::file_utils::create_file;
+use ritual_common::sized_types;
+use ritual_common::target::{current_target, LibraryTarget};
+use std::io::Write;
+
+/// Marks all FFI items of the current crate as successfully checked
+/// without invoking the C++ compiler.
+fn assume_cpp_checks_success(data: &mut ProcessorData<'_>) -> Result<()> {
+    let env = LibraryTarget {
+        target: current_target(),
+        cpp_library_version: None,
+    };
+    data.db.add_environment(env.clone());
+    for ffi_item_id in data.db.ffi_item_ids().collect_vec() {
+        data.db.add_cpp_checks_item(
+            ffi_item_id,
+            CppChecksItem {
+                env: env.clone(),
+                is_success: true,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Runs the Rust generator for `crates` in order and returns
+/// the database of the last crate. Each crate is specified by its name and
+/// the content of its header `<name>.h`. Each crate depends on all
+/// previous crates, so their headers can be included.
+/// The config of each crate is modified by `f`.
+fn run_generator(crates: &[(&str, &'static str)], f: impl Fn(&mut Config)) -> DatabaseClient {
+    let steps = [
+        "[cpp_parser..add_virtual_subclasses]",
+        "assume_cpp_checks_success",
+        "rust_generator",
+    ];
+    run_generator_with_steps(crates, &steps, f)
+}
+
+/// Same as `run_generator`, but also parses the generated C++ items
+/// (e.g. virtual subclasses) before running the Rust generator.
+fn run_generator_with_stage2(
+    crates: &[(&str, &'static str)],
+    f: impl Fn(&mut Config),
+) -> DatabaseClient {
+    let steps = [
+        "[cpp_parser..add_virtual_subclasses]",
+        "assume_cpp_checks_success",
+        "[cpp_parser_stage2..add_virtual_subclasses_stage2]",
+        "assume_cpp_checks_success",
+        "rust_generator",
+    ];
+    run_generator_with_steps(crates, &steps, f)
+}
+
+fn run_generator_with_steps(
+    crates: &[(&str, &'static str)],
+    steps: &[&str],
+    f: impl Fn(&mut Config),
+) -> DatabaseClient {
+    let dir = tempdir::TempDir::new("test_rust_generator_run").unwrap();
+
+    let mut workspace = Workspace::new(dir.path().into()).unwrap();
+
+    let include_dir = dir.path().join("include");
+    create_dir(&include_dir).unwrap();
+
+    let mut paths = CppBuildPaths::new();
+    paths.add_include_path(include_dir.clone());
+
+    let steps = steps
+        .iter()
+        .map(|&step| step.to_string())
+        .collect::<Vec<_>>();
+
+    let mut crate_properties = None;
+    for &(crate_name, code) in crates {
+        let include_name = format!("{}.h", crate_name);
+        let include_file_path = include_dir.join(&include_name);
+        {
+            let mut include_file = create_file(&include_file_path).unwrap();
+            writeln!(include_file, "{}", code).unwrap();
+        }
+
+        let mut properties = CrateProperties::new(crate_name, "0.0.0");
+        for &(dependency, _) in crates.iter().take_while(|(name, _)| name != &crate_name) {
+            properties
+                .add_dependency(
+                    dependency,
+                    CrateDependencyKind::Ritual,
+                    CrateDependencySource::CurrentWorkspace,
+                )
+                .unwrap();
+        }
+
+        let mut config = Config::new(properties.clone());
+        config.add_include_directive(&include_name);
+        config.set_cpp_build_paths(paths.clone());
+        config.add_target_include_path(include_file_path);
+        config
+            .processing_steps_mut()
+            .add_custom("assume_cpp_checks_success", assume_cpp_checks_success);
+        f(&mut config);
+
+        processor::process(&mut workspace, &config, &steps, None).unwrap();
+        crate_properties = Some(properties);
+    }
+
+    let crate_properties = crate_properties.expect("no crates specified");
+    workspace
+        .get_database_client(
+            crate_properties.name(),
+            crate_properties.dependencies(),
+            true,
+            false,
+        )
+        .unwrap()
+}
+
+/// Returns C++ code of FFI wrappers of the functions with `cpp_path`.
+fn ffi_function_code(db: &DatabaseClient, cpp_path: &str) -> Vec<String> {
+    let cpp_path = CppPath::from_good_str(cpp_path);
+    db.all_ffi_items()
+        .filter_map(|item| item.filter_map(|item| item.as_function_ref()))
+        .filter(|item| {
+            db.source_cpp_item(&item.id)
+                .unwrap()
+                .map_or(false, |cpp_item| cpp_item.item.path() == Some(&cpp_path))
+        })
+        .map(|item| cpp_code_generator::function_implementation(db, item).unwrap())
+        .collect()
+}
+
+#[test]
+fn inline_namespace_wrappers() {
+    let db = run_generator(
+        &[(
+            "A",
+            "namespace a {
+                inline namespace v1 {
+                    class X {
+                    public:
+                        int f();
+                    };
+                    void g(X* x);
+                }
+            }",
+        )],
+        |_| {},
+    );
+
+    // the generated C++ code uses fully qualified names
+    let code = ffi_function_code(&db, "a::X::f");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains("a::v1::X*"));
+    let code = ffi_function_code(&db, "a::g");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains("a::v1::X*"));
+    assert!(code[0].contains("a::v1::g(x)"));
+
+    // Rust names don't include the inline namespace
+    assert!(db
+        .find_rust_item(&RustPath::from_good_str("A::a::X"))
+        .is_some());
+}
+
+#[test]
+fn array_argument() {
+    let db = run_generator(&[("A", "void transform(float m[4][4]);")], |_| {});
+
+    let cpp_path = CppPath::from_good_str("transform");
+    let ffi_function = db
+        .ffi_items()
+        .filter_map(|item| item.filter_map(|item| item.as_function_ref()))
+        .find(|item| {
+            db.source_cpp_item(&item.id)
+                .unwrap()
+                .map_or(false, |cpp_item| cpp_item.item.path() == Some(&cpp_path))
+        })
+        .unwrap();
+    assert_eq!(ffi_function.item.arguments.len(), 1);
+    let argument = &ffi_function.item.arguments[0];
+    let array_type = CppType::Array {
+        element: Box::new(CppType::Array {
+            element: Box::new(CppType::BuiltInNumeric(CppBuiltInNumericType::Float)),
+            len: 4,
+        }),
+        len: 4,
+    };
+    assert_eq!(argument.argument_type.original_type(), &array_type);
+    assert_eq!(
+        argument.argument_type.ffi_type(),
+        &CppType::new_pointer(false, array_type)
+    );
+    assert_eq!(
+        argument.argument_type.conversion(),
+        &CppToFfiTypeConversion::ArrayToPointer
+    );
+
+    // the array is passed by pointer and dereferenced in the call
+    let code = ffi_function_code(&db, "transform");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains("transform(*m)"));
+}
+
+#[test]
+fn cross_crate_operator() {
+    let db = run_generator(
+        &[
+            (
+                "A",
+                "namespace na {
+                    class C1 {
+                    public:
+                        friend bool operator==(const C1& one, const C1& other) {
+                            return true;
+                        }
+                    };
+                }",
+            ),
+            (
+                "B",
+                "#include \"A.h\"
+                namespace nb {
+                    class C2 {};
+                    namespace ops {
+                        bool operator==(const nb::C2& one, const na::C1& other);
+                    }
+                    bool operator==(const na::C1& one, const nb::C2& other);
+                    nb::C2 operator*(double one, const nb::C2& other);
+                }",
+            ),
+        ],
+        |_| {},
+    );
+
+    // the friend operator can only be found by argument-dependent lookup
+    let code = ffi_function_code(&db, "na::operator==");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains("operator=="));
+    assert!(!code[0].contains("na::operator=="));
+
+    // arguments of this operator don't bring its namespace into the lookup
+    let code = ffi_function_code(&db, "nb::ops::operator==");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains("nb::ops::operator=="));
+
+    let local_type = RustType::Common(RustCommonType {
+        path: RustPath::from_good_str("B::nb::C2"),
+        generic_arguments: None,
+    });
+    let dependency_type = RustType::Common(RustCommonType {
+        path: RustPath::from_good_str("A::na::C1"),
+        generic_arguments: None,
+    });
+    let find_impl = |trait_path: &str, target_type: &RustType| {
+        let trait_impls = db
+            .rust_items()
+            .filter_map(|item| item.item.as_trait_impl_ref())
+            .filter(|item| item.trait_type.path == trait_path && &item.target_type == target_type)
+            .collect_vec();
+        assert_eq!(trait_impls.len(), 1);
+        trait_impls[0]
+    };
+
+    // the first operand is local
+    let trait_impl = find_impl("std::cmp::PartialEq", &local_type);
+    assert_eq!(trait_impl.parent_path, RustPath::from_good_str("B::nb"));
+
+    // the first operand is a type of the dependency
+    let trait_impl = find_impl("std::cmp::PartialEq", &dependency_type);
+    assert_eq!(
+        trait_impl.trait_type.generic_arguments,
+        Some(vec![local_type.clone()])
+    );
+    assert_eq!(trait_impl.parent_path, RustPath::from_good_str("B::nb"));
+
+    // the first operand is primitive
+    let trait_impl = find_impl("std::ops::Mul", &RustType::Primitive("f64".into()));
+    assert_eq!(
+        trait_impl.trait_type.generic_arguments,
+        Some(vec![RustType::new_reference(true, local_type.clone())])
+    );
+    assert_eq!(trait_impl.parent_path, RustPath::from_good_str("B::nb"));
+    assert_eq!(
+        trait_impl.associated_types[0].value,
+        RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("cpp_core::CppBox"),
+            generic_arguments: Some(vec![local_type.clone()]),
+        })
+    );
+}
+
+#[test]
+fn conversion_operator_to_cast_from() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            public:
+                operator int() const;
+                operator double() const;
+            };",
+        )],
+        |config| {
+            config.set_catch_exceptions_hook(|function| {
+                Ok(function.operator
+                    == Some(CppOperator::Conversion(CppType::BuiltInNumeric(
+                        CppBuiltInNumericType::Double,
+                    ))))
+            });
+        },
+    );
+
+    let cast_from_impls = db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_impl_ref())
+        .filter(|item| item.trait_type.path == "cpp_core::CastFrom")
+        .collect_vec();
+    assert_eq!(cast_from_impls.len(), 1);
+    assert_eq!(
+        cast_from_impls[0].target_type,
+        RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("std::os::raw::c_int"),
+            generic_arguments: None,
+        })
+    );
+
+    // the conversion that can throw is only available as a method
+    let throwing_functions = db
+        .rust_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .filter(|function| {
+            if let RustFunctionKind::FfiWrapper(_) = function.kind {
+                if let RustToFfiTypeConversion::ExceptionToResult(_) =
+                    function.return_type.conversion()
+                {
+                    return true;
+                }
+            }
+            false
+        })
+        .collect_vec();
+    assert_eq!(throwing_functions.len(), 1);
+}
+
+#[test]
+fn throwing_operator_is_not_trait_impl() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            public:
+                bool operator==(const C1& other) const;
+                bool operator<(const C1& other) const;
+            };",
+        )],
+        |config| {
+            config.set_catch_exceptions_hook(|function| {
+                Ok(function.operator == Some(CppOperator::EqualTo))
+            });
+        },
+    );
+
+    let trait_impls = db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_impl_ref())
+        .collect_vec();
+    assert!(trait_impls
+        .iter()
+        .all(|item| item.trait_type.path != "std::cmp::PartialEq"));
+    assert!(trait_impls
+        .iter()
+        .any(|item| item.trait_type.path == "cpp_core::cmp::Lt"));
+
+    // the operator that can throw is still available as a method
+    let throwing_functions = db
+        .rust_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .filter(|function| {
+            if let RustToFfiTypeConversion::ExceptionToResult(_) = function.return_type.conversion()
+            {
+                function.path.parent().ok() == Some(RustPath::from_good_str("A::C1"))
+            } else {
+                false
+            }
+        })
+        .collect_vec();
+    assert_eq!(throwing_functions.len(), 1);
+}
+
+#[test]
+fn total_order_requires_opt_in() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            public:
+                bool operator==(const C1& other) const;
+                bool operator<(const C1& other) const;
+            };
+            class C2 {
+            public:
+                bool operator==(const C2& other) const;
+                bool operator<(const C2& other) const;
+            };",
+        )],
+        |config| {
+            config.add_total_order_type(CppPath::from_good_str("C2"));
+        },
+    );
+
+    let marker_types = |trait_path: &str| {
+        db.rust_items()
+            .filter_map(|item| item.item.as_trait_impl_ref())
+            .filter(|item| item.trait_type.path == trait_path)
+            .map(|item| item.target_type.clone())
+            .collect_vec()
+    };
+    let c2_type = RustType::Common(RustCommonType {
+        path: RustPath::from_good_str("A::C2"),
+        generic_arguments: None,
+    });
+    // C1 is not opted in, so its operators are not assumed to define a total order
+    assert_eq!(
+        marker_types("cpp_core::cmp::TotalEq"),
+        vec![c2_type.clone()]
+    );
+    assert_eq!(marker_types("cpp_core::cmp::TotalOrd"), vec![c2_type]);
+}
+
+#[test]
+fn constructor_and_hash_impls() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            public:
+                C1();
+                C1(const C1& other);
+                bool operator==(const C1& other) const;
+            };
+            unsigned int qHash(const C1& value);
+            class C2 {
+            public:
+                C2();
+                C2(const C2& other);
+            };",
+        )],
+        |config| {
+            config.add_hash_function("qHash");
+            config.add_total_order_type(CppPath::from_good_str("C1"));
+            config.set_movable_types_hook(|path| {
+                Ok(if path == &CppPath::from_good_str("C2") {
+                    MovableTypesHookOutput::Movable
+                } else {
+                    MovableTypesHookOutput::Immovable
+                })
+            });
+        },
+    );
+
+    let c1_type = RustType::Common(RustCommonType {
+        path: RustPath::from_good_str("A::C1"),
+        generic_arguments: None,
+    });
+    let c2_type = RustType::Common(RustCommonType {
+        path: RustPath::from_good_str("A::C2"),
+        generic_arguments: None,
+    });
+    let find_impl = |trait_path: &str| {
+        let trait_impls = db
+            .rust_items()
+            .filter_map(|item| item.item.as_trait_impl_ref())
+            .filter(|item| item.trait_type.path == trait_path)
+            .collect_vec();
+        assert_eq!(trait_impls.len(), 1, "{}", trait_path);
+        trait_impls[0]
+    };
+
+    // heap allocated types get `cpp_core` traits returning `CppBox`
+    let clone_impl = find_impl("cpp_core::CppClone");
+    assert_eq!(clone_impl.target_type, c1_type);
+    assert!(clone_impl.functions[0].is_unsafe);
+    assert_eq!(
+        clone_impl.functions[0].return_type.conversion(),
+        &RustToFfiTypeConversion::CppBoxToPtr
+    );
+    let default_impl = find_impl("cpp_core::CppDefault");
+    assert_eq!(default_impl.target_type, c1_type);
+    assert!(default_impl.functions[0].arguments.is_empty());
+
+    // movable types get `std` traits returning values
+    let clone_impl = find_impl("std::clone::Clone");
+    assert_eq!(clone_impl.target_type, c2_type);
+    assert!(!clone_impl.functions[0].is_unsafe);
+    assert_eq!(
+        clone_impl.functions[0].return_type.conversion(),
+        &RustToFfiTypeConversion::ValueToPtr
+    );
+    let default_impl = find_impl("std::default::Default");
+    assert_eq!(default_impl.target_type, c2_type);
+
+    // the hash function result is converted to `u64`
+    let hash_impl = find_impl("cpp_core::CppHash");
+    assert_eq!(hash_impl.target_type, c1_type);
+    assert_eq!(hash_impl.functions[0].path.last(), "hash_value");
+    assert_eq!(
+        hash_impl.functions[0].return_type.api_type(),
+        &RustType::Primitive("u64".into())
+    );
+    assert_eq!(
+        hash_impl.functions[0].return_type.conversion(),
+        &RustToFfiTypeConversion::AsCast {
+            api_type: RustType::Primitive("u64".into()),
+        }
+    );
+
+    // the hash function is enough for `TotalEq`, but `TotalOrd` needs `operator<`
+    assert_eq!(find_impl("cpp_core::cmp::TotalEq").target_type, c1_type);
+    assert!(db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_impl_ref())
+        .all(|item| item.trait_type.path != "cpp_core::cmp::TotalOrd"));
+}
+
+#[test]
+fn sized_types_are_determined_at_compile_time() {
+    let db = run_generator(
+        &[(
+            "A",
+            "namespace ns {
+                class C1 {
+                public:
+                    C1();
+                    int x;
+                };
+            }",
+        )],
+        |config| {
+            config.set_movable_types_hook(|_| Ok(MovableTypesHookOutput::Movable));
+        },
+    );
+
+    let mut code = Vec::new();
+    cpp_code_generator::generate_cpp_type_size_requester(&db, &[], &mut code).unwrap();
+    let code = String::from_utf8(code).unwrap();
+    // the generated code only declares data, so it can be compiled for any target
+    assert!(!code.contains("main"));
+    assert!(code.contains("extern \"C\" const unsigned char ritual_sized_types[]"));
+    let magic = sized_types::MAGIC.iter().join(", ");
+    assert!(code.contains(&magic));
+    let sized_type = db
+        .rust_items()
+        .filter_map(|item| item.item.as_struct_ref())
+        .find(|item| item.kind.is_sized_type())
+        .unwrap();
+    let name = sized_type.path.last().as_bytes().iter().join(", ");
+    assert!(code.contains(&format!("  {}, 0,\n", name)));
+    assert!(code.contains("RITUAL_U64_BYTES(sizeof(ns::C1)), RITUAL_U64_BYTES(alignof(ns::C1))"));
+}
+
+#[test]
+fn variadic_function_is_public() {
+    let db = run_generator(
+        &[(
+            "A",
+            "extern \"C\" {
+                int my_printf(const char* format, ...);
+            }",
+        )],
+        |_| {},
+    );
+
+    let reexports = db
+        .rust_items()
+        .filter_map(|item| item.item.as_reexport_ref())
+        .filter(|item| item.source == RustReexportSource::FfiFunction)
+        .collect_vec();
+    assert_eq!(reexports.len(), 1);
+    assert_eq!(reexports[0].path, RustPath::from_good_str("A::my_printf"));
+    assert_eq!(
+        reexports[0].target,
+        RustPath::from_good_str("A::__ffi::my_printf")
+    );
+}
+
+#[test]
+fn type_aliases_in_signatures() {
+    let db = run_generator(
+        &[(
+            "A",
+            "namespace ns {
+                using Handle = int;
+                Handle f(Handle a, int b);
+            }
+            namespace ns2 {
+                class C1 {
+                public:
+                    const ns::Handle& g();
+                };
+            }",
+        )],
+        |_| {},
+    );
+
+    let function = |path: &str| {
+        let path = RustPath::from_good_str(path);
+        db.rust_items()
+            .filter_map(|item| item.filter_map(|item| item.as_function_ref()))
+            .find(|function| function.item.path == path)
+            .unwrap()
+    };
+    let type_code = |rust_type: &RustType| rust_type_to_code(rust_type, Some("A"));
+
+    let f = function("A::ns::f");
+    assert_eq!(
+        type_code(f.item.return_type.api_type()),
+        "crate::ns::Handle"
+    );
+    assert_eq!(
+        type_code(f.item.arguments[0].argument_type.api_type()),
+        "crate::ns::Handle"
+    );
+    assert_eq!(
+        type_code(f.item.arguments[1].argument_type.api_type()),
+        "::std::os::raw::c_int"
+    );
+    // the FFI signature still uses the resolved type
+    assert_eq!(
+        type_code(f.item.return_type.ffi_type()),
+        "::std::os::raw::c_int"
+    );
+    let doc = doc_formatter::function_doc(f, &db).unwrap();
+    assert!(doc.contains("Type aliases used in the C++ signature: [`Handle`](type.Handle.html)."));
+
+    let g = function("A::ns2::C1::g");
+    assert!(type_code(g.item.return_type.api_type()).contains("crate::ns::Handle"));
+    // methods are documented on the page of their struct in `ns2`
+    let doc = doc_formatter::function_doc(g, &db).unwrap();
+    assert!(doc.contains("[`Handle`](../ns/type.Handle.html)"));
+}
+
+#[test]
+fn type_aliases_in_overloads() {
+    let db = run_generator(
+        &[(
+            "A",
+            "namespace ns {
+                using Real = double;
+                void set(double a, Real b);
+                void set(int a, int b);
+            }",
+        )],
+        |_| {},
+    );
+
+    let functions = db
+        .rust_items()
+        .filter_map(|item| item.filter_map(|item| item.as_function_ref()))
+        .filter(|function| function.item.path.parent().unwrap() == RustPath::from_good_str("A::ns"))
+        .collect_vec();
+    // names are chosen using the resolved types, so `double` and `Real` are counted
+    // as the same type and the alias doesn't affect the name
+    let set_f64 = functions
+        .iter()
+        .find(|function| function.item.path.last() == "set_2_f64")
+        .unwrap();
+    assert!(functions
+        .iter()
+        .any(|function| function.item.path.last() == "set_2_int"));
+    // the final signature still uses the alias
+    let arg_type_code = |index: usize| {
+        rust_type_to_code(
+            set_f64.item.arguments[index].argument_type.api_type(),
+            Some("A"),
+        )
+    };
+    assert_eq!(arg_type_code(0), "f64");
+    assert_eq!(arg_type_code(1), "crate::ns::Real");
+}
+
+#[test]
+fn static_constants_visibility() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            public:
+                static constexpr int visible = 1;
+            protected:
+                static constexpr int inherited = 2;
+            private:
+                static constexpr int hidden = 3;
+            };",
+        )],
+        |_| {},
+    );
+
+    let constants = db
+        .rust_items()
+        .filter_map(|item| item.item.as_constant_ref())
+        .map(|constant| (constant.path.last().to_string(), constant.value.clone()))
+        .collect_vec();
+    // only public constants are exposed
+    assert_eq!(constants, vec![("VISIBLE".to_string(), "1".to_string())]);
+}
+
+#[test]
+fn subscript_operator_without_const_overload() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            public:
+                int& operator[](int index);
+            };
+            class C2 {
+            public:
+                int& operator[](int index);
+                const int& operator[](int index) const;
+            };",
+        )],
+        |_| {},
+    );
+
+    let ffi_function_path = |trait_path: &str, type_path: &str| {
+        let target_type = RustType::Common(RustCommonType {
+            path: RustPath::from_good_str(type_path),
+            generic_arguments: None,
+        });
+        let trait_impls = db
+            .rust_items()
+            .filter_map(|item| item.item.as_trait_impl_ref())
+            .filter(|item| item.trait_type.path == trait_path && item.target_type == target_type)
+            .collect_vec();
+        assert_eq!(trait_impls.len(), 1);
+        match &trait_impls[0].functions[0].kind {
+            RustFunctionKind::FfiWrapper(data) => data.ffi_function_path.clone(),
+            kind => panic!("unexpected function kind: {:?}", kind),
+        }
+    };
+
+    // `Index` is implemented using the non-const operator if there is no const one
+    assert_eq!(
+        ffi_function_path("cpp_core::ops::Index", "A::C1"),
+        ffi_function_path("cpp_core::ops::IndexMut", "A::C1")
+    );
+    assert_ne!(
+        ffi_function_path("cpp_core::ops::Index", "A::C2"),
+        ffi_function_path("cpp_core::ops::IndexMut", "A::C2")
+    );
+}
+
+#[test]
+fn call_operators_are_detected_by_operator_kind() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            public:
+                int operator()(int arg);
+                int operator()(double arg1, int arg2);
+            };
+            class C2 {
+            public:
+                void call(int arg);
+                void call(double arg);
+            };",
+        )],
+        |_| {},
+    );
+
+    let traits = db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_ref())
+        .filter(|item| item.path.last().ends_with("CallArgs"))
+        .collect_vec();
+    assert_eq!(traits.len(), 1);
+    assert_eq!(traits[0].path, RustPath::from_good_str("A::C1CallArgs"));
+
+    let trait_impls = db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_impl_ref())
+        .filter(|item| item.trait_type.path == traits[0].path)
+        .collect_vec();
+    assert_eq!(trait_impls.len(), 2);
+}
+
+#[test]
+fn protected_members() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            public:
+                int f1();
+            protected:
+                int f2(int arg) const;
+                static int f3();
+                void f4(int arg);
+                void f4(double arg);
+            };
+            class C2 {
+            public:
+                int f5();
+            protected:
+                C2();
+                ~C2();
+            };",
+        )],
+        |config| {
+            config.set_expose_protected_members(true);
+        },
+    );
+
+    let accessor = "struct ritual_protected_accessor : C1 { using C1::f2; };";
+    let code = ffi_function_code(&db, "C1::f2");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains(accessor));
+    assert!(code[0].contains(
+        "return (this_ptr->*static_cast<int (C1::*)(int) const>\
+         (&ritual_protected_accessor::f2))(arg);"
+    ));
+
+    let code = ffi_function_code(&db, "C1::f3");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains("struct ritual_protected_accessor : C1 { using C1::f3; };"));
+    assert!(code[0].contains("return ritual_protected_accessor::f3();"));
+
+    // the using-declaration makes all overloads accessible,
+    // and the member pointer type selects one of them
+    let code = ffi_function_code(&db, "C1::f4");
+    assert_eq!(code.len(), 2);
+    for code in &code {
+        assert!(code.contains("struct ritual_protected_accessor : C1 { using C1::f4; };"));
+    }
+    assert!(code.iter().any(|code| code.contains(
+        "(this_ptr->*static_cast<void (C1::*)(int)>(&ritual_protected_accessor::f4))(arg);"
+    )));
+    assert!(code.iter().any(|code| code.contains(
+        "(this_ptr->*static_cast<void (C1::*)(double)>(&ritual_protected_accessor::f4))(arg);"
+    )));
+
+    // public members are called directly
+    let code = ffi_function_code(&db, "C1::f1");
+    assert_eq!(code.len(), 1);
+    assert!(!code[0].contains("ritual_protected_accessor"));
+
+    // protected constructors and destructors are not wrapped,
+    // so `C2` doesn't get an empty trait
+    let protected_traits = db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_ref())
+        .collect_vec();
+    assert_eq!(protected_traits.len(), 1);
+    let protected_trait = protected_traits[0];
+    assert_eq!(
+        protected_trait.path,
+        RustPath::from_good_str("A::C1Protected")
+    );
+    assert_eq!(
+        protected_trait.implemented_for,
+        Some(RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("A::C1"),
+            generic_arguments: None,
+        }))
+    );
+
+    let functions = db
+        .rust_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .filter(|function| function.kind.is_ffi_wrapper())
+        .map(|function| function.path.clone())
+        .collect_vec();
+    let protected_functions = functions
+        .iter()
+        .filter(|path| path.parent().ok().as_ref() == Some(&protected_trait.path))
+        .map(|path| path.last().to_string())
+        .sorted()
+        .collect_vec();
+    assert_eq!(protected_functions.len(), 4);
+    assert!(protected_functions.contains(&"f2".to_string()));
+    assert!(protected_functions.contains(&"f3".to_string()));
+    assert_eq!(
+        protected_functions
+            .iter()
+            .filter(|name| name.starts_with("f4"))
+            .count(),
+        2
+    );
+    // only public members are methods of the struct
+    let struct_functions = functions
+        .iter()
+        .filter(|path| path.parent().ok() == Some(RustPath::from_good_str("A::C1")))
+        .map(|path| path.last())
+        .collect_vec();
+    assert!(struct_functions.contains(&"f1"));
+    assert!(struct_functions
+        .iter()
+        .all(|name| !["f2", "f3"].contains(name) && !name.starts_with("f4")));
+}
+
+#[test]
+fn protected_fields() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class C1 {
+            protected:
+                int m;
+            };",
+        )],
+        |config| {
+            config.set_expose_protected_members(true);
+        },
+    );
+
+    let code = ffi_function_code(&db, "C1::m");
+    assert!(!code.is_empty());
+    for code in &code {
+        assert!(code.contains("struct ritual_protected_accessor : C1 { using C1::m; };"));
+        assert!(code.contains("(this_ptr->*&ritual_protected_accessor::m)"));
+    }
+}
+
+#[test]
+fn options_structs() {
+    let db = run_generator(
+        &[(
+            "A",
+            "enum E { E1, E2 };
+            class C1 {
+            public:
+                int f(int a, int b = 2, E c = E2, double d = 0.5);
+            };",
+        )],
+        |config| {
+            config.set_options_struct_min_default_arguments(Some(2));
+        },
+    );
+
+    let options_structs = db
+        .rust_items()
+        .filter_map(|item| item.item.as_struct_ref())
+        .filter_map(|item| match &item.kind {
+            RustStructKind::OptionsStruct(options) => Some((&item.path, options)),
+            _ => None,
+        })
+        .collect_vec();
+    assert_eq!(options_structs.len(), 1);
+    let (struct_path, options) = options_structs[0];
+    assert_eq!(struct_path.parent().unwrap(), RustPath::from_good_str("A"));
+    assert!(struct_path.last().starts_with("C1F"));
+    assert!(struct_path.last().ends_with("Options"));
+
+    let fields = options
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.default_value.as_str()))
+        .collect_vec();
+    assert_eq!(fields, vec![("b", "2"), ("c", "E2"), ("d", "0.5")]);
+    // `Default` impl calls the getters of the default values
+    for field in &options.fields {
+        let getter = db
+            .find_rust_item(&field.default_function_path)
+            .and_then(|item| item.item.as_function_ref())
+            .unwrap();
+        assert!(getter.kind.is_ffi_function());
+    }
+
+    let code = ffi_function_code(&db, "C1::f");
+    assert!(code
+        .iter()
+        .any(|code| code.contains("return static_cast<int>(2);")));
+    assert!(code.iter().any(|code| code.contains("(E2);")));
+    assert!(code
+        .iter()
+        .any(|code| code.contains("return static_cast<double>(0.5);")));
+
+    let wrappers = db
+        .rust_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .filter_map(|function| match &function.kind {
+            RustFunctionKind::OptionsWrapper(data) => Some((function, data)),
+            _ => None,
+        })
+        .collect_vec();
+    assert_eq!(wrappers.len(), 1);
+    let (wrapper, data) = wrappers[0];
+    assert_eq!(
+        wrapper.path,
+        data.function_path
+            .parent()
+            .unwrap()
+            .join(format!("{}_with_options", data.function_path.last()))
+    );
+    assert_eq!(
+        data.call_arguments,
+        vec!["self", "a", "options.b", "options.c", "options.d"]
+    );
+    let argument_names = wrapper
+        .arguments
+        .iter()
+        .map(|arg| arg.name.as_str())
+        .collect_vec();
+    assert_eq!(argument_names, vec!["self", "a", "options"]);
+    assert_eq!(
+        wrapper.arguments[2].argument_type.api_type(),
+        &RustType::Common(RustCommonType {
+            path: struct_path.clone(),
+            generic_arguments: None,
+        })
+    );
+}
+
+const DEPENDENCY_TEMPLATE_CRATES: &[(&str, &str)] = &[
+    (
+        "A",
+        "class Finder {
+        public:
+            template<class T> T find();
+            template<class T> void put(T* value);
+        };",
+    ),
+    (
+        "B",
+        "#include \"A.h\"
+        class Widget {};",
+    ),
+];
+
+fn finder_type() -> RustType {
+    RustType::Common(RustCommonType {
+        path: RustPath::from_good_str("A::Finder"),
+        generic_arguments: None,
+    })
+}
+
+/// Returns paths of all functions of the current crate located in `parent`.
+fn rust_function_paths(db: &DatabaseClient, parent: &RustPath) -> Vec<RustPath> {
+    db.rust_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .filter(|function| function.path.parent().ok().as_ref() == Some(parent))
+        .map(|function| function.path.clone())
+        .collect()
+}
+
+#[test]
+fn dependency_template_instantiations() {
+    // the template is only instantiated in the second crate
+    let db = run_generator(DEPENDENCY_TEMPLATE_CRATES, |config| {
+        let is_b = config.crate_properties().name() == "B";
+        config.set_instantiate_template_functions(is_b);
+    });
+
+    // methods can't be added to a struct of another crate
+    assert!(db
+        .rust_items()
+        .filter_map(|item| item.item.path())
+        .all(|path| path.crate_name() == "B"));
+
+    let ext_traits = db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_ref())
+        .filter(|item| item.implemented_for == Some(finder_type()))
+        .collect_vec();
+    assert_eq!(ext_traits.len(), 1);
+    let ext_trait_path = RustPath::from_good_str("B::FinderExt");
+    assert_eq!(ext_traits[0].path, ext_trait_path);
+
+    let ext_functions = rust_function_paths(&db, &ext_trait_path);
+    assert!(ext_functions
+        .iter()
+        .any(|path| path.last().starts_with("put")));
+    let find = ext_trait_path.join("find");
+    assert!(ext_functions.contains(&find));
+    let find = db.find_rust_item(&find).unwrap();
+    let find_trait_path = match &find.item.as_function_ref().unwrap().kind {
+        RustFunctionKind::TemplateWrapper(data) => data.trait_path.clone(),
+        kind => panic!("unexpected function kind: {:?}", kind),
+    };
+    assert_eq!(find_trait_path, RustPath::from_good_str("B::FinderFindArg"));
+
+    let trait_impls = db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_impl_ref())
+        .filter(|item| item.trait_type.path == find_trait_path)
+        .collect_vec();
+    assert_eq!(trait_impls.len(), 1);
+    assert_eq!(
+        trait_impls[0].target_type,
+        RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("B::Widget"),
+            generic_arguments: None,
+        })
+    );
+
+    let doc = doc_formatter::trait_doc(
+        db.find_rust_item(&ext_trait_path)
+            .unwrap()
+            .map(|item| item.as_trait_ref().unwrap()),
+        &db,
+    )
+    .unwrap();
+    assert!(doc.contains("added by this crate"));
+}
+
+#[test]
+fn dependency_template_trait_is_reused() {
+    let db = run_generator(DEPENDENCY_TEMPLATE_CRATES, |config| {
+        config.set_instantiate_template_functions(true);
+    });
+
+    // the generic method and its trait are only available in the first crate
+    let find_trait_path = RustPath::from_good_str("A::FinderFindArg");
+    let trait_impls = db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_impl_ref())
+        .filter(|item| item.trait_type.path == find_trait_path)
+        .collect_vec();
+    assert_eq!(trait_impls.len(), 1);
+    assert_eq!(trait_impls[0].parent_path, RustPath::from_good_str("B"));
+    assert_eq!(
+        trait_impls[0].target_type,
+        RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("B::Widget"),
+            generic_arguments: None,
+        })
+    );
+    assert!(db
+        .rust_items()
+        .filter_map(|item| item.item.as_trait_ref())
+        .all(|item| !item.path.last().starts_with("FinderFindArg")));
+
+    let ext_trait_path = RustPath::from_good_str("B::FinderExt");
+    let ext_functions = rust_function_paths(&db, &ext_trait_path);
+    assert!(!ext_functions.is_empty());
+    assert!(ext_functions
+        .iter()
+        .all(|path| path.last().starts_with("put")));
+}
+
+#[test]
+fn virtual_subclass_overrides() {
+    let db = run_generator_with_stage2(
+        &[(
+            "A",
+            "class Event {};
+            class Widget {
+            public:
+                virtual ~Widget();
+                virtual void event(Event* event);
+                virtual void event(Widget* sender, Event* event);
+                virtual int size() const = 0;
+            };",
+        )],
+        |config| {
+            config.set_generate_virtual_subclasses(true);
+        },
+    );
+
+    let subclasses = db
+        .ffi_items()
+        .filter_map(|item| item.item.as_virtual_subclass_ref())
+        .collect_vec();
+    assert_eq!(subclasses.len(), 1);
+    let subclass = subclasses[0];
+    // overloads have different names
+    let names = subclass
+        .virtual_functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        names,
+        vec!["event_Event_ptr", "event_Widget_ptr_Event_ptr", "size"]
+    );
+
+    // overrides are accessible through a pointer to the subclass
+    let code = cpp_code_generator::virtual_subclass(&db, subclass).unwrap();
+    assert!(!code.contains("protected:"));
+    assert!(code.contains("void override_event_Event_ptr("));
+    assert!(code.contains("void override_event_Widget_ptr_Event_ptr("));
+    assert!(code.contains("void base_event_Event_ptr(Event* arg0)"));
+    assert!(!code.contains("base_size"));
+
+    let trait_path = RustPath::from_good_str("A::WidgetOverrides");
+    let rust_trait = db
+        .find_rust_item(&trait_path)
+        .unwrap()
+        .item
+        .as_trait_ref()
+        .unwrap();
+    assert_eq!(rust_trait.implemented_for, None);
+
+    let trait_functions = db
+        .rust_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .filter(|f| f.path.parent().ok().as_ref() == Some(&trait_path))
+        .collect_vec();
+    assert_eq!(trait_functions.len(), 3);
+    for function in &trait_functions {
+        let data = match &function.kind {
+            RustFunctionKind::VirtualFunction(data) => data,
+            kind => panic!("unexpected function kind: {:?}", kind),
+        };
+        assert_eq!(function.arguments[0].name, "self");
+        assert_eq!(function.arguments[1].name, "object");
+        assert_eq!(data.call_arguments[0], "object");
+        if function.path.last() == "size" {
+            // pure virtual functions have no default implementation
+            assert!(data.base_function_path.is_none());
+            assert_eq!(function.arguments.len(), 2);
+        } else {
+            let base_path = data.base_function_path.as_ref().unwrap();
+            assert_eq!(
+                base_path.parent().unwrap(),
+                RustPath::from_good_str("A::WidgetSubclass")
+            );
+            assert!(base_path.last().starts_with("base_event"));
+        }
+    }
+
+    let setter = db
+        .find_rust_item(&RustPath::from_good_str("A::WidgetSubclass::set_overrides"))
+        .unwrap()
+        .item
+        .as_function_ref()
+        .unwrap();
+    let data = match &setter.kind {
+        RustFunctionKind::OverridesSetter(data) => data,
+        kind => panic!("unexpected function kind: {:?}", kind),
+    };
+    assert_eq!(data.trait_path, trait_path);
+    assert_eq!(data.overrides.len(), 3);
+    let setter_paths = data
+        .overrides
+        .iter()
+        .map(|item| &item.setter_path)
+        .unique()
+        .collect_vec();
+    assert_eq!(setter_paths.len(), 3);
+}
+
+#[test]
+fn virtual_subclasses_of_final_items() {
+    let db = run_generator(
+        &[(
+            "A",
+            "class Base {
+            public:
+                virtual ~Base();
+                virtual void f();
+                virtual void g();
+            };
+            class Derived : public Base {
+            public:
+                void f() final;
+            };
+            class Sealed final : public Base {};",
+        )],
+        |config| {
+            config.set_generate_virtual_subclasses(true);
+        },
+    );
+
+    let subclasses = db
+        .ffi_items()
+        .filter_map(|item| item.item.as_virtual_subclass_ref())
+        .map(|subclass| {
+            let names = subclass
+                .virtual_functions
+                .iter()
+                .map(|f| f.name.as_str())
+                .sorted()
+                .collect_vec();
+            (subclass.base_class_path.to_cpp_pseudo_code(), names)
+        })
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        subclasses,
+        vec![
+            ("Base".to_string(), vec!["f", "g"]),
+            // `Derived::f` is final and hides `Base::f`
+            ("Derived".to_string(), vec!["g"]),
+        ]
+    );
+}
+
+#[test]
+fn std_function_argument() {
+    let db = run_generator(
+        &[(
+            "A",
+            "#include <functional>
+            void set_filter(int limit, std::function<bool(int)> filter);
+            void set_handler(std::function<void(int)> handler, int flags);
+            void set_listener(void* data, int callback, std::function<void(int)> listener);",
+        )],
+        |_| {},
+    );
+
+    let cpp_path = CppPath::from_good_str("set_filter");
+    let ffi_function = db
+        .ffi_items()
+        .filter_map(|item| item.filter_map(|item| item.as_function_ref()))
+        .find(|item| {
+            db.source_cpp_item(&item.id)
+                .unwrap()
+                .map_or(false, |cpp_item| cpp_item.item.path() == Some(&cpp_path))
+        })
+        .unwrap();
+    let arguments = ffi_function
+        .item
+        .arguments
+        .iter()
+        .map(|arg| (arg.name.as_str(), arg.meaning.clone()))
+        .collect_vec();
+    assert_eq!(
+        arguments,
+        vec![
+            ("limit", CppFfiArgumentMeaning::Argument(0)),
+            ("ritual_callback", CppFfiArgumentMeaning::Argument(1)),
+            (
+                "ritual_callback_deleter",
+                CppFfiArgumentMeaning::CallbackDeleter(1)
+            ),
+            (
+                "ritual_callback_data",
+                CppFfiArgumentMeaning::CallbackData(1)
+            ),
+        ]
+    );
+    if let CppToFfiTypeConversion::FunctionToCallback {
+        arguments,
+        return_type,
+    } = ffi_function.item.arguments[1].argument_type.conversion()
+    {
+        assert_eq!(arguments.len(), 1);
+        assert_eq!(
+            arguments[0].original_type(),
+            &CppType::BuiltInNumeric(CppBuiltInNumericType::Int)
+        );
+        assert_eq!(
+            return_type.original_type(),
+            &CppType::BuiltInNumeric(CppBuiltInNumericType::Bool)
+        );
+    } else {
+        panic!("expected FunctionToCallback conversion");
+    }
+
+    // the wrapper constructs `std::function` from the callback, its data and deleter
+    let code = ffi_function_code(&db, "set_filter");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains("std::function< bool(int) >(ritual::bind_callback_data("));
+    assert!(code[0].contains("[ritual_callback](void* data, int arg0) -> bool {"));
+    assert!(code[0].contains("return ritual_callback(data, arg0);"));
+    assert!(code[0].contains("}, ritual_callback_deleter, ritual_callback_data))"));
+
+    // the callback arguments don't conflict with other arguments
+    let code = ffi_function_code(&db, "set_listener");
+    assert_eq!(code.len(), 1);
+    assert!(code[0].contains("set_listener(data, callback, std::function< void(int) >("));
+    assert!(code[0].contains("}, ritual_callback_deleter, ritual_callback_data))"));
+
+    // `std::function` is only supported as the last argument
+    assert!(ffi_function_code(&db, "set_handler").is_empty());
+
+    let rust_function = db
+        .rust_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .find(|function| {
+            if let RustFunctionKind::FfiWrapper(_) = function.kind {
+                function.path.last() == "set_filter"
+            } else {
+                false
+            }
+        })
+        .unwrap();
+    assert_eq!(rust_function.arguments.len(), 2);
+    // the closure argument is named after the `std::function` argument
+    assert_eq!(rust_function.arguments[1].name, "filter");
+    let closure = rust_function.arguments[1]
+        .argument_type
+        .conversion()
+        .as_callback_ref()
+        .unwrap();
+    assert_eq!(closure.closure_arguments.len(), 1);
+    assert_eq!(
+        closure.closure_arguments[0].api_type(),
+        &RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("std::os::raw::c_int"),
+            generic_arguments: None,
+        })
+    );
+    assert_eq!(closure.closure_return_type.api_type(), &RustType::bool());
+}
@@ -4,6 +4,7 @@ use crate::config::MovableTypesHookOutput;
 use crate::cpp_data::{CppItem, CppPath};
 use crate::cpp_type::{CppPointerLikeTypeKind, CppType};
 use crate::processor::ProcessorData;
+use crate::rust_info::{RustItem, RustStructKind, RustWrapperTypeKind};
 use log::{info, trace};
 use ritual_common::errors::Result;
 use std::collections::HashMap;
@@ -99,6 +100,47 @@ fn check_type(
 
 const MAX_ITEMS: usize = 10;
 
+/// Returns true if objects of the class `path` should be stored inline
+/// on Rust side instead of being allocated on the heap.
+///
+/// Classes of the current crate are movable if the movable types hook says so.
+/// Classes of the dependencies are movable if they have a movable wrapper in their crate.
+pub fn is_movable(data: &ProcessorData<'_>, path: &CppPath) -> Result<bool> {
+    if CppType::Class(path.clone()).is_or_contains_template_parameter() {
+        return Ok(false);
+    }
+    let is_current = data.db.find_cpp_items_by_path(path).any(|item| {
+        item.id.crate_name() == data.db.crate_name()
+            && item
+                .item
+                .as_type_ref()
+                .map_or(false, |type1| type1.kind.is_class())
+    });
+
+    if is_current {
+        if let Some(hook) = data.config.movable_types_hook() {
+            return Ok(hook(path)? == MovableTypesHookOutput::Movable);
+        }
+        return Ok(false);
+    }
+
+    let is_movable = data
+        .db
+        .find_rust_items_for_cpp_path(path, true)?
+        .any(|item| {
+            if let RustItem::Struct(rust_struct) = item.item {
+                if let RustStructKind::WrapperType(RustWrapperTypeKind::MovableClassWrapper {
+                    ..
+                }) = &rust_struct.kind
+                {
+                    return true;
+                }
+            }
+            false
+        });
+    Ok(is_movable)
+}
+
 /// Detects the preferred type allocation place for each type based on
 /// API of all known methods. Doesn't actually change the data,
 /// only suggests stack allocated types for manual configuration.
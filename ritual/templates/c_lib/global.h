@@ -14,6 +14,22 @@
 // placement new statements require this
 #include <new>
 
+// for std::move()
+#include <utility>
+
+// for std::add_pointer and other type traits
+#include <type_traits>
+
+// for std::unique_ptr
+#include <memory>
+
+// for catching exceptions in FFI functions
+#include <exception>
+#include <string>
+
+// for std::memcpy()
+#include <cstring>
+
 // for exit()
 #include <cstdlib>
 
@@ -38,6 +54,15 @@ namespace ritual {
         x->~T();
     }
 
+    // Stores the message of an exception caught in an FFI function.
+    // The returned pointer is valid until the next exception is caught
+    // in the current thread.
+    inline const char* exception_message(const char* message) {
+        static thread_local std::string value;
+        value = message;
+        return value.c_str();
+    }
+
     template<class T>
     class Callback {
     public:
@@ -67,6 +92,43 @@ namespace ritual {
         void (*m_deleter)(void*);
         T m_callback;
     };
+
+    // Calls `deleter` on the data of a Rust callback.
+    struct CallbackDataDeleter {
+        void (*deleter)(void*);
+        void operator()(void* data) const {
+            if (deleter) {
+                deleter(data);
+            }
+        }
+    };
+
+    // Function object that calls `function` with the data of a Rust callback
+    // as the first argument. The data is shared between copies of the object
+    // and deleted when the last copy is destroyed.
+    template<class F>
+    class BoundCallback {
+    public:
+        BoundCallback(F function, void (*deleter)(void*), void* data)
+        : m_function(function), m_data(data, CallbackDataDeleter{deleter}) {}
+
+        template<class... Args>
+        auto operator()(Args&&... args) const
+        -> decltype(std::declval<const F&>()(static_cast<void*>(nullptr), std::forward<Args>(args)...)) {
+            return m_function(m_data.get(), std::forward<Args>(args)...);
+        }
+
+    private:
+        F m_function;
+        std::shared_ptr<void> m_data;
+    };
+
+    // Creates a function object that can be converted to `std::function`
+    // from a Rust callback passed to an FFI function.
+    template<class F>
+    BoundCallback<F> bind_callback_data(F function, void (*deleter)(void*), void* data) {
+        return BoundCallback<F>(function, deleter, data);
+    }
 }
 
 #endif // RITUAL_GLOBAL_H
@@ -0,0 +1,10 @@
+// Class generated by ritual.
+// See the template at "ritual/templates/c_lib/virtual_subclass.h".
+class {class_name} : public {base_class_name} {{
+public:
+{constructors}
+{public_methods}
+{overrides}
+private:
+{fields}
+}};
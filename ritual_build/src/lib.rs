@@ -28,11 +28,12 @@ use log::info;
 pub use ritual_common as common;
 use ritual_common::cpp_build_config::{CppBuildConfig, CppBuildPaths, CppLibraryType};
 use ritual_common::cpp_lib_builder::{BuildType, CMakeConfigData, CppLibBuilder};
-use ritual_common::errors::{bail, err_msg, FancyUnwrap, Result, ResultExt};
+use ritual_common::errors::{bail, err_msg, format_err, FancyUnwrap, Result, ResultExt};
 use ritual_common::file_utils::{create_file, file_to_string, load_json, path_to_str};
 use ritual_common::target::{current_target, LibraryTarget, OS};
-use ritual_common::{env_var_names, BuildScriptData};
+use ritual_common::{env_var_names, sized_types, BuildScriptData};
 use std::env;
+use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process;
@@ -194,6 +195,35 @@ impl Config {
             )?;
         }
 
+        {
+            info!("Generating sized_types.rs file");
+            let mut sized_types_file = create_file(out_dir.join("sized_types.rs"))?;
+            // the sized types library is only generated if there are movable types
+            if manifest_dir.join("c_lib").join("sized_types.cxx").exists() {
+                // the sizes are read from the library compiled for the target
+                // instead of running target code on the build platform
+                let lib_name = format!(
+                    "{}_sized_types",
+                    &self.build_script_data.cpp_wrapper_lib_name
+                );
+                let lib_path = [format!("lib{}.a", lib_name), format!("{}.lib", lib_name)]
+                    .iter()
+                    .map(|file_name| c_lib_install_dir.join(file_name))
+                    .find(|path| path.exists())
+                    .ok_or_else(|| {
+                        format_err!(
+                            "sized types library not found in {}",
+                            c_lib_install_dir.display()
+                        )
+                    })?;
+                let data = fs::read(&lib_path)
+                    .with_context(|_| format!("failed to read {}", lib_path.display()))?;
+                let types = sized_types::parse(&data)
+                    .with_context(|_| format!("failed to parse {}", lib_path.display()))?;
+                write!(sized_types_file, "{}", sized_types::rust_code(&types))?;
+            }
+        }
+
         for name in cpp_build_config_data.linked_libs() {
             println!("cargo:rustc-link-lib={}", name);
         }
@@ -238,7 +268,7 @@ impl Config {
     ///
     /// - Build the C++ wrapper library;
     /// - Generate `ffi.rs` file with actual link attributes;
-    /// - Determine C++ type sizes on current platform and generate `sized_types.rs`;
+    /// - Determine C++ type sizes on the target platform and generate `sized_types.rs`;
     /// - Report linking information to `cargo`.
     ///
     /// This function ends the process with the appropriate error code and never
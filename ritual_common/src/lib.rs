@@ -14,6 +14,7 @@ pub mod cpp_lib_builder;
 pub mod env_var_names;
 pub mod errors;
 pub mod file_utils;
+pub mod sized_types;
 pub mod string_utils;
 pub mod target;
 pub mod utils;
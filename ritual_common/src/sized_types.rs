@@ -0,0 +1,83 @@
+//! Sizes and alignments of C++ types stored inline in Rust structs.
+//!
+//! The build script can't run code compiled for the target when cross-compiling,
+//! so the sizes are determined at compile time. The C++ wrapper library build
+//! produces a static library containing a byte array that describes the types,
+//! and the build script finds this array in the compiled library.
+//!
+//! The array starts with `MAGIC`. Each type is represented by the name of
+//! its Rust struct terminated by a zero byte, followed by the size and the alignment
+//! of the type as 8-byte little endian integers. An empty name terminates the array.
+
+use crate::errors::{bail, err_msg, Result, ResultExt};
+use std::str;
+
+/// Bytes that mark the start of the sized types array.
+pub const MAGIC: &[u8] = b"RITUAL_SIZED_TYPES_V1";
+
+/// Size and alignment of a C++ type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizedType {
+    /// Name of the Rust struct
+    pub name: String,
+    /// Value of `sizeof` of the C++ type
+    pub size: u64,
+    /// Value of `alignof` of the C++ type
+    pub alignment: u64,
+}
+
+fn read_u64(data: &mut &[u8]) -> Result<u64> {
+    if data.len() < 8 {
+        bail!("unexpected end of sized types data");
+    }
+    let value = data[..8]
+        .iter()
+        .rev()
+        .fold(0, |value, &byte| value << 8 | u64::from(byte));
+    *data = &data[8..];
+    Ok(value)
+}
+
+/// Finds the sized types array in `data` (content of a compiled library)
+/// and returns the types it describes.
+pub fn parse(data: &[u8]) -> Result<Vec<SizedType>> {
+    let start = data
+        .windows(MAGIC.len())
+        .position(|window| window == MAGIC)
+        .ok_or_else(|| err_msg("sized types data not found"))?;
+    let mut data = &data[start + MAGIC.len()..];
+    let mut types = Vec::new();
+    loop {
+        let name_len = data
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or_else(|| err_msg("unterminated type name in sized types data"))?;
+        let name = str::from_utf8(&data[..name_len])
+            .with_context(|_| "invalid type name in sized types data")?
+            .to_string();
+        data = &data[name_len + 1..];
+        if name.is_empty() {
+            return Ok(types);
+        }
+        let size = read_u64(&mut data)?;
+        let alignment = read_u64(&mut data)?;
+        types.push(SizedType {
+            name,
+            size,
+            alignment,
+        });
+    }
+}
+
+/// Generates Rust declarations of the structs for `types`.
+pub fn rust_code(types: &[SizedType]) -> String {
+    types
+        .iter()
+        .map(|sized_type| {
+            format!(
+                "#[repr(C, align({}))]\npub struct {}([u8; {}]);\n\n",
+                sized_type.alignment, sized_type.name, sized_type.size
+            )
+        })
+        .collect()
+}
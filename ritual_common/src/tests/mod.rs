@@ -1 +1,2 @@
+mod sized_types;
 mod utils;
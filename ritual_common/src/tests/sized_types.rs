@@ -0,0 +1,60 @@
+use crate::sized_types::{parse, rust_code, SizedType, MAGIC};
+
+fn u64_bytes(value: u64) -> Vec<u8> {
+    (0..8).map(|i| (value >> (i * 8)) as u8).collect()
+}
+
+#[test]
+fn parse_sized_types() {
+    let mut data = b"\x7fELF unrelated bytes\0".to_vec();
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(b"QPoint\0");
+    data.extend(u64_bytes(8));
+    data.extend(u64_bytes(4));
+    data.extend_from_slice(b"QString\0");
+    data.extend(u64_bytes(0x1_0000_0008));
+    data.extend(u64_bytes(8));
+    data.push(0);
+    data.extend_from_slice(b"trailing bytes");
+
+    let types = parse(&data).unwrap();
+    assert_eq!(
+        types,
+        vec![
+            SizedType {
+                name: "QPoint".into(),
+                size: 8,
+                alignment: 4,
+            },
+            SizedType {
+                name: "QString".into(),
+                size: 0x1_0000_0008,
+                alignment: 8,
+            },
+        ]
+    );
+    assert_eq!(
+        rust_code(&types[..1]),
+        "#[repr(C, align(4))]\npub struct QPoint([u8; 8]);\n\n"
+    );
+}
+
+#[test]
+fn parse_empty_sized_types() {
+    let mut data = MAGIC.to_vec();
+    data.push(0);
+    assert_eq!(parse(&data).unwrap(), Vec::new());
+    assert_eq!(rust_code(&[]), "");
+}
+
+#[test]
+fn parse_invalid_sized_types() {
+    assert!(parse(b"no sized types here").is_err());
+
+    let mut data = MAGIC.to_vec();
+    data.extend_from_slice(b"QPoint\0");
+    data.extend(u64_bytes(8));
+    // the alignment is truncated
+    data.extend_from_slice(&[4, 0, 0]);
+    assert!(parse(&data).is_err());
+}